@@ -0,0 +1,357 @@
+use crate::{
+    empty::is_empty, failure::FailureFormat, is_message_pack_type, msgpack_response,
+    rejection::NotAcceptable, response_content_type::DefaultContentType, vary::append_vary_accept,
+    ApplicationMsgPack, ContentType,
+};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::{convert::Infallible, ops::Deref};
+
+/// The types [`AcceptedMsgPackMime`] can pick, listed by [`NotAcceptable`].
+const PRODUCIBLE: &[&str] = &[
+    "application/msgpack",
+    "application/x-msgpack",
+    "application/*+msgpack",
+];
+
+/// Extractor for the MessagePack media type the client accepts for the response.
+///
+/// Picks the MessagePack type with the highest quality from the `Accept` header, matching types
+/// the same way [`MsgPack`](crate::MsgPack) checks the `Content-Type` header of requests, so
+/// `application/msgpack`, `application/x-msgpack` and `application/*+msgpack` are recognized.
+/// Falls back to `application/msgpack` if the header does not name one. Respond with
+/// [`AcceptedMsgPackMime::respond`] to echo the type back:
+///
+/// ```no_run
+/// use axum::{response::Response, routing::get, Router};
+/// use axum_msgpack::AcceptedMsgPackMime;
+///
+/// async fn get_user(accepted: AcceptedMsgPackMime) -> Response {
+///     // with `Accept: application/vnd.myapi+msgpack`, responds with
+///     // `Content-Type: application/vnd.myapi+msgpack`
+///     accepted.respond("steve")
+/// }
+///
+/// let app: Router = Router::new().route("/user", get(get_user));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptedMsgPackMime(pub mime::Mime);
+
+impl AcceptedMsgPackMime {
+    /// Reads the accepted MessagePack type from `headers`.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut best: Option<(mime::Mime, f32)> = None;
+        for (mime, quality) in accept_ranges(headers) {
+            if is_message_pack_type(&mime) && !best.as_ref().is_some_and(|(_, q)| quality <= *q) {
+                best = Some((mime, quality));
+            }
+        }
+
+        // Drop parameters such as `q`, which belong to the `Accept` header only.
+        let mime = best
+            .and_then(|(mime, _)| mime.essence_str().parse().ok())
+            .unwrap_or_else(|| ApplicationMsgPack::CONTENT_TYPE.parse().unwrap());
+        Self(mime)
+    }
+
+    /// Serializes `value` like [`MsgPack`](crate::MsgPack), with the accepted type as
+    /// `Content-Type` and a `Vary: Accept` header.
+    pub fn respond<T>(&self, value: T) -> Response
+    where
+        T: Serialize,
+    {
+        let mut res = if is_empty(&value) {
+            StatusCode::NO_CONTENT.into_response()
+        } else {
+            let encoded = rmp_serde::encode::to_vec_named(&value);
+            let encoded_ok = encoded.is_ok();
+            let mut res = msgpack_response::<T, _>(
+                encoded,
+                ApplicationMsgPack::CONTENT_TYPE,
+                FailureFormat::default(),
+            );
+            // Only successful responses carry the accepted type, not the failure response.
+            if let (true, Ok(content_type)) = (encoded_ok, HeaderValue::from_str(self.0.as_ref())) {
+                res.headers_mut().insert(header::CONTENT_TYPE, content_type);
+                // The echoed type wins over a configured default.
+                res.extensions_mut().remove::<DefaultContentType>();
+            }
+            res
+        };
+        append_vary_accept(res.headers_mut());
+        res
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AcceptedMsgPackMime
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// Extractor for the MessagePack media type the client accepts for the response, rejecting
+/// requests that accept none.
+///
+/// Works like [`AcceptedMsgPackMime`], which it dereferences to, but instead of falling back to
+/// `application/msgpack` it rejects the request with [`NotAcceptable`], a
+/// `406 Not Acceptable` response listing the types that can be produced, if the `Accept` header
+/// excludes MessagePack. That is the case unless the header lists a MessagePack type, or
+/// `*/*` or `application/*` without also giving a MessagePack type a quality of `0`. Requests
+/// without an `Accept` header accept any type.
+///
+/// ```no_run
+/// use axum::{response::Response, routing::get, Router};
+/// use axum_msgpack::AcceptableMsgPackMime;
+///
+/// async fn get_user(accepted: AcceptableMsgPackMime) -> Response {
+///     // with `Accept: text/html`, the handler is not called
+///     accepted.respond("steve")
+/// }
+///
+/// let app: Router = Router::new().route("/user", get(get_user));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptableMsgPackMime(pub AcceptedMsgPackMime);
+
+impl AcceptableMsgPackMime {
+    /// Reads the accepted MessagePack type from `headers`, failing if they exclude MessagePack.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, NotAcceptable> {
+        if headers.get(header::ACCEPT).is_none() {
+            return Ok(Self(AcceptedMsgPackMime::from_headers(headers)));
+        }
+
+        let mut wildcard = false;
+        let mut excluded = false;
+        for (mime, quality) in parse_accept_ranges(headers) {
+            if is_message_pack_type(&mime) {
+                if quality > 0.0 {
+                    return Ok(Self(AcceptedMsgPackMime::from_headers(headers)));
+                }
+                excluded = true;
+            } else if mime.type_() == mime::STAR
+                || (mime.type_() == mime::APPLICATION && mime.subtype() == mime::STAR)
+            {
+                wildcard |= quality > 0.0;
+            }
+        }
+        if wildcard && !excluded {
+            return Ok(Self(AcceptedMsgPackMime::from_headers(headers)));
+        }
+
+        let accept = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .map(|accept| String::from_utf8_lossy(accept.as_bytes()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(NotAcceptable::new(accept, PRODUCIBLE))
+    }
+}
+
+impl Deref for AcceptableMsgPackMime {
+    type Target = AcceptedMsgPackMime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AcceptableMsgPackMime
+where
+    S: Send + Sync,
+{
+    type Rejection = NotAcceptable;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Self::from_headers(&parts.headers)
+    }
+}
+
+/// Parses the media ranges of the `Accept` headers, with their quality, skipping ranges that
+/// cannot be parsed or are not acceptable.
+pub(crate) fn accept_ranges(headers: &HeaderMap) -> impl Iterator<Item = (mime::Mime, f32)> + '_ {
+    parse_accept_ranges(headers).filter(|(_, quality)| *quality > 0.0)
+}
+
+/// Parses the media ranges of the `Accept` headers, with their quality, skipping ranges that
+/// cannot be parsed. Ranges with a malformed quality get the default quality of `1`.
+fn parse_accept_ranges(headers: &HeaderMap) -> impl Iterator<Item = (mime::Mime, f32)> + '_ {
+    accept_range_qualities(headers).map(|(mime, quality)| (mime, quality.unwrap_or(1.0)))
+}
+
+/// Parses the media ranges of the `Accept` headers, with their quality, skipping ranges that
+/// cannot be parsed. The quality is `None` if the `q` parameter is malformed.
+pub(crate) fn accept_range_qualities(
+    headers: &HeaderMap,
+) -> impl Iterator<Item = (mime::Mime, Option<f32>)> + '_ {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|accept| accept.to_str().ok())
+        .flat_map(|accept| accept.split(','))
+        .filter_map(|range| range.trim().parse::<mime::Mime>().ok())
+        .map(|mime| {
+            let quality = match mime.get_param("q") {
+                Some(q) => parse_quality(q.as_str()),
+                None => Some(1.0),
+            };
+            (mime, quality)
+        })
+}
+
+/// Parses a quality value, which is a number from `0` to `1` with at most three decimals, as in
+/// `0.5` or `1.000`.
+fn parse_quality(q: &str) -> Option<f32> {
+    let (int, fraction) = q.split_once('.').unwrap_or((q, ""));
+    let valid = matches!(int, "0" | "1")
+        && fraction.len() <= 3
+        && fraction.bytes().all(|digit| digit.is_ascii_digit())
+        && (int == "0" || fraction.bytes().all(|digit| digit == b'0'));
+    valid.then(|| q.parse().ok()).flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+        response::Response,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::{parse_quality, AcceptableMsgPackMime, AcceptedMsgPackMime};
+
+    fn accepted(accept: Option<&'static str>) -> AcceptedMsgPackMime {
+        let mut headers = HeaderMap::new();
+        if let Some(accept) = accept {
+            headers.insert(header::ACCEPT, HeaderValue::from_static(accept));
+        }
+        AcceptedMsgPackMime::from_headers(&headers)
+    }
+
+    #[test]
+    fn picks_accepted_message_pack_type() {
+        for (accept, expected) in [
+            (None, "application/msgpack"),
+            (Some("application/json"), "application/msgpack"),
+            (
+                Some("application/vnd.myapi+msgpack"),
+                "application/vnd.myapi+msgpack",
+            ),
+            (
+                Some("application/msgpack;q=0.5, application/vnd.myapi+MsgPack;q=0.9"),
+                "application/vnd.myapi+msgpack",
+            ),
+            (
+                Some("application/x-msgpack, application/vnd.myapi+msgpack;q=0"),
+                "application/x-msgpack",
+            ),
+        ] {
+            assert_eq!(accepted(accept).0, expected, "{:?}", accept);
+        }
+    }
+
+    #[test]
+    fn parses_quality_values() {
+        for (q, expected) in [
+            ("1", Some(1.0)),
+            ("1.000", Some(1.0)),
+            ("0", Some(0.0)),
+            ("0.", Some(0.0)),
+            ("0.5", Some(0.5)),
+            ("0.125", Some(0.125)),
+            ("1.5", None),
+            ("0.1234", None),
+            ("-1", None),
+            (".5", None),
+            ("NaN", None),
+            ("high", None),
+            ("", None),
+        ] {
+            assert_eq!(parse_quality(q), expected, "{:?}", q);
+        }
+    }
+
+    #[tokio::test]
+    async fn echoes_accepted_type_in_response() {
+        let res = accepted(Some("application/vnd.myapi+msgpack")).respond("steve");
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            "application/vnd.myapi+msgpack"
+        );
+        assert_eq!(res.headers()[header::VARY], "accept");
+
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(rmp_serde::from_slice::<String>(&body).unwrap(), "steve");
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_excluding_message_pack() {
+        for accept in [
+            "text/html",
+            "application/json",
+            "text/*, application/json;q=0.5",
+            "application/msgpack;q=0, */*",
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, HeaderValue::from_static(accept));
+            let rejection = AcceptableMsgPackMime::from_headers(&headers).unwrap_err();
+            assert_eq!(rejection.accept(), accept);
+        }
+
+        let app: Router = Router::new().route(
+            "/user",
+            get(|accepted: AcceptableMsgPackMime| async move { accepted.respond("steve") }),
+        );
+        let send = |accept: Option<&'static str>| {
+            let mut request = Request::get("/user");
+            if let Some(accept) = accept {
+                request = request.header(header::ACCEPT, accept);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let res: Response = send(Some("text/html")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(res.headers()[header::VARY], "accept");
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            body,
+            "None of the types in `Accept: text/html` can be produced, expected one of: \
+             application/msgpack, application/x-msgpack, application/*+msgpack"
+        );
+
+        for (accept, expected) in [
+            (None, "application/msgpack"),
+            (Some("*/*"), "application/msgpack"),
+            (Some("application/*;q=0.1"), "application/msgpack"),
+            (
+                Some("text/html, application/vnd.myapi+msgpack;q=0.5"),
+                "application/vnd.myapi+msgpack",
+            ),
+        ] {
+            let res = send(accept).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK, "{:?}", accept);
+            assert_eq!(
+                res.headers()[header::CONTENT_TYPE],
+                expected,
+                "{:?}",
+                accept
+            );
+        }
+    }
+}
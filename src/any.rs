@@ -0,0 +1,91 @@
+use crate::{body_len::read_body, decode, rejection::MsgPackRejection, MsgPackConfig};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::de::DeserializeOwned;
+
+/// MessagePack extractor that does not check the content type of the request.
+///
+/// Decodes the body like [`MsgPack`](crate::MsgPack), with the same limits and
+/// [`MsgPackConfig`], but whatever the `Content-Type` header says, or if it is missing, and only
+/// rejects the request if the body cannot be decoded. This is meant for calls between services
+/// that control both ends, where a proxy rewriting the header would otherwise cause spurious
+/// rejections.
+///
+/// It is less safe than [`MsgPack`](crate::MsgPack): the content type check keeps forms and
+/// other bodies a browser sends without a CORS preflight from reaching a MessagePack handler,
+/// and a JSON body that happens to decode as MessagePack, e.g. a number, is silently accepted.
+/// Only use it on routes that are not exposed to browsers.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackAny;
+///
+/// async fn sync_inventory(MsgPackAny(items): MsgPackAny<Vec<u32>>) {}
+///
+/// let internal: Router = Router::new().route("/internal/inventory", post(sync_inventory));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackAny<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackAny<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of(&req);
+        let bytes = read_body(req, state).await?;
+        decode(&bytes, &config).map(MsgPackAny)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+
+    use super::MsgPackAny;
+    use crate::rejection::MsgPackRejection;
+
+    async fn extract(
+        body: Vec<u8>,
+        content_type: Option<&'static str>,
+    ) -> Result<MsgPackAny<Vec<u32>>, MsgPackRejection> {
+        let mut request = Request::new(Body::from(body));
+        if let Some(content_type) = content_type {
+            request
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+        MsgPackAny::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn decodes_regardless_of_content_type() {
+        let body = rmp_serde::to_vec(&[1, 2, 3]).unwrap();
+        for content_type in [
+            Some("application/msgpack"),
+            Some("application/json"),
+            Some("text/plain; charset=utf-8"),
+            Some("not a mime"),
+            None,
+        ] {
+            let MsgPackAny(items) = extract(body.clone(), content_type).await.unwrap();
+            assert_eq!(items, [1, 2, 3], "{:?}", content_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_undecodable_body() {
+        let body = br#"{"items": [1, 2, 3]}"#.to_vec();
+        assert!(matches!(
+            extract(body, Some("application/json")).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    decode,
+    rejection::{BodyReadError, MsgPackRejection},
+    MsgPackConfig,
+};
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Decodes a MessagePack value read from `reader`, e.g. a file or a socket.
+///
+/// Reads `reader` to its end first, and then decodes the bytes like [`MsgPack`](crate::MsgPack)
+/// decodes a request body with the default [`MsgPackConfig`], so failures are reported with the
+/// same rejections. A failing read is reported as a
+/// [`BodyReadError`](crate::rejection::BodyReadError).
+///
+/// ```no_run
+/// use axum_msgpack::{decode_from_async_read, rejection::MsgPackRejection};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     name: String,
+/// }
+///
+/// async fn load_settings() -> Result<Settings, MsgPackRejection> {
+///     let file = tokio::fs::File::open("settings.msgpack").await.unwrap();
+///     decode_from_async_read(file).await
+/// }
+/// ```
+pub async fn decode_from_async_read<T, R>(reader: R) -> Result<T, MsgPackRejection>
+where
+    T: DeserializeOwned,
+    R: AsyncRead,
+{
+    let mut bytes = Vec::new();
+    std::pin::pin!(reader)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(BodyReadError::from_err)?;
+    decode(&bytes, &MsgPackConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use tokio::io::BufReader;
+
+    use super::decode_from_async_read;
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Input {
+        foo: String,
+    }
+
+    #[tokio::test]
+    async fn decodes_from_buf_reader() {
+        let bytes = rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap();
+
+        let value: Input = decode_from_async_read(BufReader::new(&bytes[..]))
+            .await
+            .unwrap();
+        assert_eq!(value, Input { foo: "bar".into() });
+
+        let outcome = decode_from_async_read::<Input, _>(BufReader::new(&[0xc1][..])).await;
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}
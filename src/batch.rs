@@ -0,0 +1,238 @@
+use crate::{
+    body_len::read_body,
+    declared_len::check_declared_lengths,
+    deserialize_seed,
+    duplicate::dedupe_keys,
+    error::Error,
+    message_pack_content_type,
+    rejection::{
+        InvalidMsgPackBatch, InvalidMsgPackBody, MsgPackRejection,
+    },
+    trace, MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+use std::marker::PhantomData;
+
+/// MessagePack Extractor for a batch of homogeneous values.
+///
+/// The request body must be a single MessagePack array. Each element is decoded into `T` on
+/// its own, so a malformed element does not hide the state of the others: if any element fails
+/// to decode, the request is rejected with
+/// [`InvalidMsgPackBatch`](crate::rejection::InvalidMsgPackBatch), which lists the index and
+/// the reason of every failed element.
+///
+/// Content-type checking is the same as for [`MsgPack`](crate::MsgPack), and each element is
+/// decoded with the options of the [`MsgPackConfig`], such as
+/// [`deny_unknown_fields`](MsgPackConfig::deny_unknown_fields) and
+/// [`finite_floats`](MsgPackConfig::finite_floats).
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackBatch;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct CreateUser {
+///     email: String,
+/// }
+///
+/// async fn create_users(MsgPackBatch(users): MsgPackBatch<CreateUser>) {
+///     // users is a `Vec<CreateUser>`
+/// }
+///
+/// let app: Router = Router::new().route("/users/bulk", post(create_users));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MsgPackBatch<T>(pub Vec<T>);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackBatch<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        message_pack_content_type(&req)?;
+        let config = MsgPackConfig::of(&req);
+        let bytes = read_body(req, state).await?;
+        decode_batch(&bytes, &config).map(MsgPackBatch)
+    }
+}
+
+fn decode_batch<T>(bytes: &[u8], config: &MsgPackConfig) -> Result<Vec<T>, MsgPackRejection>
+where
+    T: DeserializeOwned,
+{
+    check_declared_lengths(bytes, config)?;
+    let mut rd = bytes;
+    let len = rmp::decode::read_array_len(&mut rd).map_err(InvalidMsgPackBody::from_err)?;
+
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for index in 0..len as usize {
+        // Skip over the element first, so its extent is known even if it does not match `T`.
+        let element = rd;
+        IgnoredAny::deserialize(&mut rmp_serde::Deserializer::new(&mut rd))
+            .map_err(InvalidMsgPackBody::from_err)?;
+        let element = &element[..element.len() - rd.len()];
+
+        let decoded = dedupe_keys(element, config).and_then(|element| {
+            let deserializer = &mut rmp_serde::Deserializer::new(&*element);
+            deserialize_seed(PhantomData::<T>, deserializer, config)
+        });
+        match decoded {
+            Ok(value) => values.push(value),
+            Err(err) => {
+                trace::decode_failed::<T, _>(&err);
+                errors.push((index, Error::new(err)));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(InvalidMsgPackBatch::new(errors).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackBatch;
+    use crate::{rejection::MsgPackRejection, MsgPackConfig};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Input {
+        foo: String,
+    }
+
+    #[derive(Serialize)]
+    #[serde(untagged)]
+    enum Element {
+        Valid(Input),
+        Invalid(u32),
+    }
+
+    async fn extract(elements: &[Element]) -> Result<MsgPackBatch<Input>, MsgPackRejection> {
+        let body = rmp_serde::encode::to_vec_named(elements).unwrap();
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        <MsgPackBatch<Input> as FromRequest<_, _>>::from_request(request, &||{}).await
+    }
+
+    #[tokio::test]
+    async fn decodes_valid_batch() {
+        let batch = extract(&[
+            Element::Valid(Input { foo: "a".into() }),
+            Element::Valid(Input { foo: "b".into() }),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(
+            batch.0,
+            vec![Input { foo: "a".into() }, Input { foo: "b".into() }]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_failed_indices() {
+        let outcome = extract(&[
+            Element::Valid(Input { foo: "a".into() }),
+            Element::Invalid(1),
+            Element::Valid(Input { foo: "b".into() }),
+            Element::Invalid(3),
+        ])
+        .await;
+
+        match outcome {
+            Err(MsgPackRejection::InvalidMsgPackBatch(rejection)) => {
+                assert_eq!(rejection.failed_indices().collect::<Vec<_>>(), vec![1, 3]);
+                let message = rejection.to_string();
+                assert!(message.contains("[1]: "), "{}", message);
+                assert!(message.contains("[3]: "), "{}", message);
+            }
+            other => unreachable!("Expected batch rejection, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_non_array_body() {
+        let body = rmp_serde::encode::to_vec_named(&Input { foo: "a".into() }).unwrap();
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        let outcome =
+            <MsgPackBatch<Input> as FromRequest<_, _>>::from_request(request, &||{}).await;
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_elements_against_config() {
+        #[derive(Debug, Deserialize)]
+        struct Point {
+            x: f64,
+        }
+
+        #[derive(Serialize)]
+        struct Loose {
+            x: f64,
+            extra: u8,
+        }
+
+        let body = rmp_serde::encode::to_vec_named(&[
+            Loose { x: 1.0, extra: 1 },
+            Loose { x: f64::INFINITY, extra: 1 },
+        ])
+        .unwrap();
+        let extract = |config: MsgPackConfig| {
+            let mut request = Request::new(Body::from(body.clone()));
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            request.extensions_mut().insert(config);
+            <MsgPackBatch<Point> as FromRequest<_, _>>::from_request(request, &||{})
+        };
+
+        let MsgPackBatch(points) = extract(MsgPackConfig::new()).await.unwrap();
+        assert_eq!(points[0].x, 1.0);
+        assert!(points[1].x.is_infinite());
+
+        let config = MsgPackConfig::new().deny_unknown_fields(true);
+        match extract(config).await {
+            Err(MsgPackRejection::InvalidMsgPackBatch(rejection)) => {
+                assert_eq!(rejection.failed_indices().collect::<Vec<_>>(), vec![0, 1]);
+            }
+            other => unreachable!("Expected batch rejection, got: {:?}", other),
+        }
+
+        let config = MsgPackConfig::new().finite_floats(true);
+        match extract(config).await {
+            Err(MsgPackRejection::InvalidMsgPackBatch(rejection)) => {
+                assert_eq!(rejection.failed_indices().collect::<Vec<_>>(), vec![1]);
+            }
+            other => unreachable!("Expected batch rejection, got: {:?}", other),
+        }
+    }
+}
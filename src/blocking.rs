@@ -0,0 +1,177 @@
+use std::io::{self, Write};
+
+use crate::{
+    empty::is_empty, failure::FailureFormat, msgpack_response, ApplicationMsgPack, ContentType,
+    MsgPack, MsgPackRaw,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// Size up to which values are serialized on the calling task.
+const INLINE_LIMIT: usize = 64 * 1024;
+
+impl<T> MsgPack<T>
+where
+    T: Serialize + Send + 'static,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], moving the serialization
+    /// of large values off the async executor.
+    ///
+    /// The value is serialized on the calling task first. If its encoding turns out to be
+    /// larger than 64 KiB, that attempt is abandoned and the value is serialized again on
+    /// tokio's blocking thread pool, so other tasks keep running meanwhile. Small values
+    /// therefore avoid the cost of a thread hand-off, and large values waste at most 64 KiB of
+    /// work.
+    ///
+    /// ```no_run
+    /// use axum::{response::Response, routing::get, Router};
+    /// use axum_msgpack::MsgPack;
+    ///
+    /// async fn export() -> Response {
+    ///     let rows: Vec<(u64, String)> = load_rows().await;
+    ///     MsgPack(rows).into_response_async().await
+    /// }
+    ///
+    /// async fn load_rows() -> Vec<(u64, String)> {
+    ///     // ...
+    ///     # unimplemented!()
+    /// }
+    ///
+    /// let app: Router = Router::new().route("/export", get(export));
+    /// ```
+    pub async fn into_response_async(self) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            encode(self.0, true).await,
+            ApplicationMsgPack::CONTENT_TYPE,
+            FailureFormat::default(),
+        )
+    }
+}
+
+impl<T> MsgPackRaw<T>
+where
+    T: Serialize + Send + 'static,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], moving the serialization
+    /// of large values off the async executor, see [`MsgPack::into_response_async`].
+    pub async fn into_response_async(self) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            encode(self.0, false).await,
+            ApplicationMsgPack::CONTENT_TYPE,
+            FailureFormat::default(),
+        )
+    }
+}
+
+async fn encode<T>(value: T, named: bool) -> Result<Vec<u8>, rmp_serde::encode::Error>
+where
+    T: Serialize + Send + 'static,
+{
+    let mut inline = LimitedWriter::default();
+    match write(&mut inline, &value, named) {
+        Ok(()) => return Ok(inline.buf),
+        Err(err) if !inline.exceeded => return Err(err),
+        Err(_) => {}
+    }
+
+    let encoded = tokio::task::spawn_blocking(move || {
+        let mut buf = inline.buf;
+        buf.clear();
+        write(&mut buf, &value, named).map(|()| buf)
+    })
+    .await;
+    match encoded {
+        Ok(encoded) => encoded,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+fn write<W, T>(wr: &mut W, value: &T, named: bool) -> Result<(), rmp_serde::encode::Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    if named {
+        rmp_serde::encode::write_named(wr, value)
+    } else {
+        rmp_serde::encode::write(wr, value)
+    }
+}
+
+/// A writer that fails once more than [`INLINE_LIMIT`] bytes are written.
+#[derive(Default)]
+struct LimitedWriter {
+    buf: Vec<u8>,
+    exceeded: bool,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.buf.len() + data.len() > INLINE_LIMIT {
+            self.exceeded = true;
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::to_bytes, http::header, response::IntoResponse};
+    use serde::Serialize;
+
+    use super::INLINE_LIMIT;
+    use crate::{MsgPack, MsgPackRaw};
+
+    #[derive(Clone, Serialize)]
+    struct Output {
+        names: Vec<String>,
+    }
+
+    fn output(len: usize) -> Output {
+        Output {
+            names: (0..len).map(|i| format!("name-{}", i)).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn small_and_large_values_encode_identically() {
+        for len in [1, 100_000] {
+            let value = output(len);
+
+            let expected = to_bytes(
+                MsgPack(value.clone()).into_response().into_body(),
+                usize::MAX,
+            )
+            .await
+            .unwrap();
+            assert_eq!(expected.len() > INLINE_LIMIT, len > 1);
+
+            let res = MsgPack(value.clone()).into_response_async().await;
+            assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+            let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(bytes, expected);
+
+            let expected = rmp_serde::encode::to_vec(&value).unwrap();
+            let res = MsgPackRaw(value).into_response_async().await;
+            let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(bytes, expected);
+        }
+    }
+}
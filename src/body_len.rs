@@ -0,0 +1,255 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, OnceLock},
+};
+
+#[cfg(feature = "tokio")]
+use std::{future::Future, time::Duration};
+
+#[cfg(feature = "tokio")]
+use crate::rejection::BodyReadTimeout;
+use crate::{
+    check_content_encoding,
+    rejection::{BodyReadError, MsgPackBodyTooLarge, MsgPackRejection},
+    MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    body::{Body, Bytes, HttpBody},
+    extract::{
+        rejection::{BytesRejection, FailedToBufferBody},
+        FromRequest, FromRequestParts, Request,
+    },
+    http::{header, request::Parts},
+};
+use http_body_util::Limited;
+
+/// Extractor for the length of the body read by a MessagePack extractor.
+///
+/// The length is the number of bytes actually received, so it is available even if the request
+/// has no `Content-Length` header. Extract `MsgPackBodyLen` before the body extractor, and read
+/// the length with [`get`](MsgPackBodyLen::get) in the handler:
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::{MsgPack, MsgPackBodyLen};
+///
+/// async fn create_user(body_len: MsgPackBodyLen, MsgPack(payload): MsgPack<String>) {
+///     let len = body_len.get().unwrap();
+///     // ...
+/// }
+///
+/// let app: Router = Router::new().route("/users", post(create_user));
+/// ```
+///
+/// The extractor shares its slot with the request extensions, so a middleware can insert a
+/// `MsgPackBodyLen` into the request extensions, and read it once the handler returns.
+///
+/// The length is recorded by [`MsgPack`](crate::MsgPack), [`MsgPackRaw`](crate::MsgPackRaw),
+/// `MsgPackBuf`, [`MsgPackBatch`](crate::MsgPackBatch),
+/// [`from_request_seed`](crate::from_request_seed) and the extractors built on them.
+/// `MsgPackStream` does not record it, since it stops reading the body after the value.
+#[derive(Debug, Clone, Default)]
+pub struct MsgPackBodyLen(Arc<OnceLock<usize>>);
+
+impl MsgPackBodyLen {
+    /// Creates an empty slot, to be inserted into the request extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the length of the body in bytes, or `None` if no MessagePack extractor has read
+    /// the body yet.
+    pub fn get(&self) -> Option<usize> {
+        self.0.get().copied()
+    }
+
+    pub(crate) fn record(&self, len: usize) {
+        let _ = self.0.set(len);
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for MsgPackBodyLen
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get_or_insert_with(MsgPackBodyLen::default)
+            .clone())
+    }
+}
+
+/// Reads the whole body, and records its length in the [`MsgPackBodyLen`] of the request.
+///
+/// Errors of the body itself are turned into a [`BodyReadError`], other failures, such as
+/// exceeding the body limit, are passed on as the [`BytesRejection`].
+pub(crate) async fn read_body<S>(req: Request, state: &S) -> Result<Bytes, MsgPackRejection>
+where
+    S: Send + Sync,
+{
+    check_content_encoding(req.headers())?;
+    let req = limit_body_len(req)?;
+    let body_len = req.extensions().get::<MsgPackBodyLen>().cloned();
+    #[cfg(feature = "tokio")]
+    let bytes = {
+        let timeout = req
+            .extensions()
+            .get::<MsgPackConfig>()
+            .and_then(MsgPackConfig::body_read_timeout);
+        read_with_timeout(Bytes::from_request(req, state), timeout).await?
+    };
+    #[cfg(not(feature = "tokio"))]
+    let bytes = Bytes::from_request(req, state).await;
+    let bytes = bytes.map_err(|rejection| match rejection {
+        BytesRejection::FailedToBufferBody(FailedToBufferBody::UnknownBodyError(err)) => {
+            BodyReadError::from_err(err).into()
+        }
+        rejection => MsgPackRejection::from(rejection),
+    })?;
+    if let Some(body_len) = body_len {
+        body_len.record(bytes.len());
+    }
+    Ok(bytes)
+}
+
+/// Applies the [`max_body_len`](crate::MsgPackLimits::max_body_len) of the configuration of
+/// `req` to reading its body, so a body over the limit is not buffered first.
+///
+/// Bodies of a known length, from the `Content-Length` header or the body itself, are rejected
+/// with [`MsgPackBodyTooLarge`] right away. Other bodies stop being read once they exceed the
+/// limit, with the same `413 Payload Too Large` rejection as for axum's body limit.
+pub(crate) fn limit_body_len(req: Request) -> Result<Request, MsgPackRejection> {
+    let Some(limit) = MsgPackConfig::of(&req).decode_limits().max_body_len else {
+        return Ok(req);
+    };
+    let len = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok()?.parse::<u64>().ok())
+        .or_else(|| req.body().size_hint().exact());
+    if let Some(len) = len.filter(|&len| len > limit as u64) {
+        let len = usize::try_from(len).unwrap_or(usize::MAX);
+        return Err(MsgPackBodyTooLarge::new(len, limit).into());
+    }
+    Ok(req.map(|body| Body::new(Limited::new(body, limit))))
+}
+
+/// Awaits `read`, giving up once `timeout` has passed.
+#[cfg(feature = "tokio")]
+pub(crate) async fn read_with_timeout<F>(
+    read: F,
+    timeout: Option<Duration>,
+) -> Result<F::Output, BodyReadTimeout>
+where
+    F: Future,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, read)
+            .await
+            .map_err(|_| BodyReadTimeout::new(timeout)),
+        None => Ok(read.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::{FromRequest, FromRequestParts},
+        http::HeaderValue,
+    };
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackBodyLen;
+    use crate::MsgPack;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Input {
+        foo: String,
+    }
+
+    #[tokio::test]
+    async fn records_len_of_body_read() {
+        let body = rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap();
+        let mut request = Request::new(Body::from(body.clone()));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        assert!(request.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let (mut parts, body_stream) = request.into_parts();
+        let body_len = MsgPackBodyLen::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert_eq!(body_len.get(), None);
+
+        let request = Request::from_parts(parts, body_stream);
+        let outcome = <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        assert_eq!(outcome.unwrap().0, Input { foo: "bar".into() });
+        assert_eq!(body_len.get(), Some(body.len()));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn times_out_on_stalled_body() {
+        use std::{convert::Infallible, time::Duration};
+
+        use axum::{body::Bytes, http::StatusCode};
+        use futures_util::{stream, StreamExt};
+
+        use crate::{rejection::MsgPackRejection, MsgPackConfig};
+
+        let body = rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap();
+        let timeout = Duration::from_millis(50);
+        let into_request = |body: Body| {
+            let mut request = Request::new(body);
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            request
+                .extensions_mut()
+                .insert(MsgPackConfig::new().body_timeout(timeout));
+            request
+        };
+
+        let request = into_request(Body::from(body.clone()));
+        let outcome = MsgPack::<Input>::from_request(request, &()).await;
+        assert_eq!(outcome.unwrap().0, Input { foo: "bar".into() });
+
+        // Sends the first bytes, then never finishes.
+        let stalled = || {
+            let chunks = stream::iter([Ok::<_, Infallible>(Bytes::copy_from_slice(&body[..2]))])
+                .chain(stream::pending());
+            into_request(Body::from_stream(chunks))
+        };
+        match MsgPack::<Input>::from_request(stalled(), &()).await {
+            Err(MsgPackRejection::BodyReadTimeout(rejection)) => {
+                assert_eq!(rejection.timeout(), timeout);
+                assert_eq!(rejection.status(), StatusCode::REQUEST_TIMEOUT);
+            }
+            other => unreachable!("Expected body read timeout rejection, got: {:?}", other),
+        }
+
+        #[cfg(feature = "buf")]
+        {
+            use crate::MsgPackBuf;
+
+            let request = into_request(Body::from(body.clone()));
+            let outcome = MsgPackBuf::<Input>::from_request(request, &()).await;
+            assert_eq!(outcome.unwrap().0, Input { foo: "bar".into() });
+
+            assert!(matches!(
+                MsgPackBuf::<Input>::from_request(stalled(), &()).await,
+                Err(MsgPackRejection::BodyReadTimeout(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::{collections::VecDeque, marker::PhantomData};
+
+#[cfg(feature = "tokio")]
+use crate::body_len::read_with_timeout;
+use crate::{
+    body_len::limit_body_len,
+    check_content_encoding, check_trailing,
+    declared_len::check_declared_lengths,
+    deserialize_seed,
+    duplicate::dedupe_next,
+    message_pack_content_type,
+    rejection::{BodyReadError, InvalidMsgPackBody, MsgPackRejection},
+    trace, MsgPackBodyLen, MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::{FromRequest, Request},
+    RequestExt,
+};
+use bytes::Buf;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use serde::de::DeserializeOwned;
+
+/// MessagePack Extractor that decodes the request body from the chunks it was received in.
+///
+/// The whole body is still buffered before decoding starts, as with [`MsgPack`](crate::MsgPack),
+/// but the chunks are kept as they are instead of being copied into one contiguous buffer, and
+/// handed to the decoder through a [`bytes::Buf`] backed reader, so a value may freely span
+/// chunk boundaries. This only saves the contiguous copy, not the memory for the body itself,
+/// at the cost of a slightly slower decode.
+///
+/// Content-type checking and rejections are the same as for [`MsgPack`](crate::MsgPack).
+/// A [duplicate key policy](crate::DuplicateKeys) other than the default one needs the whole
+/// value in memory once more, decoded, which undoes the savings.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackBuf;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Upload {
+///     name: String,
+///     data: Vec<u8>,
+/// }
+///
+/// async fn upload(MsgPackBuf(upload): MsgPackBuf<Upload>) {
+///     // upload is an `Upload`
+/// }
+///
+/// let app: Router = Router::new().route("/upload", post(upload));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackBuf<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackBuf<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+        message_pack_content_type(&req)?;
+        check_content_encoding(req.headers())?;
+
+        let config = MsgPackConfig::of(&req);
+        let req = limit_body_len(req)?;
+        let body_len = req.extensions().get::<MsgPackBodyLen>().cloned();
+        let mut body = req.into_limited_body();
+        let read = async {
+            let mut chunks = BufList::default();
+            while let Some(frame) = body.frame().await {
+                let frame = match frame {
+                    Ok(frame) => frame,
+                    Err(err) if exceeds_length_limit(&err) => {
+                        return Err(length_limit_rejection().await)
+                    }
+                    Err(err) => return Err(BodyReadError::from_err(err).into()),
+                };
+                if let Ok(data) = frame.into_data() {
+                    chunks.push(data);
+                }
+            }
+            Ok::<_, MsgPackRejection>(chunks)
+        };
+        #[cfg(feature = "tokio")]
+        let mut chunks = read_with_timeout(read, config.body_read_timeout()).await??;
+        #[cfg(not(feature = "tokio"))]
+        let mut chunks = read.await?;
+
+        if let Some(body_len) = body_len {
+            body_len.record(chunks.remaining());
+        }
+        trace::decoding::<T>(chunks.remaining());
+        check_declared_lengths(chunks.clone(), &config)?;
+        let deduped =
+            dedupe_next((&mut chunks).reader(), &config).map_err(InvalidMsgPackBody::from_err)?;
+        let value = match deduped {
+            Some(deduped) => {
+                let deserializer = &mut rmp_serde::Deserializer::new(&deduped[..]);
+                deserialize_seed(PhantomData::<T>, deserializer, &config)
+            }
+            None => {
+                let deserializer = &mut rmp_serde::Deserializer::new((&mut chunks).reader());
+                deserialize_seed(PhantomData::<T>, deserializer, &config)
+            }
+        }
+        .inspect_err(trace::decode_failed::<T, _>)
+        .map_err(InvalidMsgPackBody::from_err)?;
+        check_trailing(chunks.remaining(), &config)?;
+        Ok(MsgPackBuf(value))
+    }
+}
+
+/// Returns `true` if reading the body failed because it exceeded the body limit.
+fn exceeds_length_limit(err: &axum::Error) -> bool {
+    let err: &(dyn std::error::Error + 'static) = err;
+    std::iter::successors(Some(err), |err| err.source()).any(|err| err.is::<LengthLimitError>())
+}
+
+/// Returns the rejection [`MsgPack`](crate::MsgPack) gives for a body over the limit, a
+/// `413 Payload Too Large` [`BytesRejection`](axum::extract::rejection::BytesRejection).
+///
+/// axum does not allow creating that rejection directly, so it is taken from reading a body that
+/// is over its limit right away.
+async fn length_limit_rejection() -> MsgPackRejection {
+    let over_limit = Limited::new(Full::new(Bytes::from_static(&[0])), 0);
+    match Bytes::from_request(Request::new(Body::new(over_limit)), &()).await {
+        Err(rejection) => rejection.into(),
+        Ok(_) => unreachable!("a body over its limit was read"),
+    }
+}
+
+/// A [`Buf`] over a queue of non-contiguous [`Bytes`] chunks.
+#[derive(Debug, Clone, Default)]
+struct BufList {
+    chunks: VecDeque<Bytes>,
+}
+
+impl BufList {
+    fn push(&mut self, chunk: Bytes) {
+        if chunk.has_remaining() {
+            self.chunks.push_back(chunk);
+        }
+    }
+}
+
+impl Buf for BufList {
+    fn remaining(&self) -> usize {
+        self.chunks.iter().map(Buf::remaining).sum()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.chunks.front().map(Buf::chunk).unwrap_or_default()
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while cnt > 0 {
+            let front = self
+                .chunks
+                .front_mut()
+                .expect("cannot advance past the end of a BufList");
+            let len = front.remaining();
+            if cnt < len {
+                front.advance(cnt);
+                return;
+            }
+            front.advance(len);
+            cnt -= len;
+            self.chunks.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::{
+        body::{Body, Bytes},
+        extract::{DefaultBodyLimit, FromRequest},
+        http::{HeaderValue, StatusCode},
+        routing::post,
+        Router,
+    };
+    use bytes::Buf;
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+    use tower::ServiceExt;
+
+    use super::{BufList, MsgPackBuf};
+    use crate::MsgPack;
+    use crate::{rejection::MsgPackRejection, DuplicateKeys, MsgPackConfig};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Input {
+        foo: String,
+        bar: Vec<u32>,
+    }
+
+    #[test]
+    fn buf_list_reads_across_chunks() {
+        let mut buf = BufList::default();
+        buf.push(Bytes::from_static(b"ab"));
+        buf.push(Bytes::new());
+        buf.push(Bytes::from_static(b"cde"));
+
+        assert_eq!(buf.remaining(), 5);
+        assert_eq!(buf.chunk(), b"ab");
+        buf.advance(3);
+        assert_eq!(buf.chunk(), b"de");
+        assert_eq!(buf.copy_to_bytes(2), Bytes::from_static(b"de"));
+        assert!(!buf.has_remaining());
+    }
+
+    #[tokio::test]
+    async fn deserializes_value_spanning_chunks() {
+        let input = Input {
+            foo: "a string long enough to be split".into(),
+            bar: vec![1, 300, 70_000],
+        };
+        let serialized =
+            rmp_serde::encode::to_vec_named(&input).expect("Failed to serialize test struct");
+
+        let chunks: Vec<Result<Vec<u8>, std::io::Error>> = serialized
+            .chunks(3)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        let mut request = Request::new(Body::from_stream(futures_util::stream::iter(chunks)));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        let outcome =
+            <MsgPackBuf<Input> as FromRequest<_, _>>::from_request(request, &||{}).await;
+
+        let outcome = outcome.unwrap();
+        assert_eq!(input, outcome.0);
+    }
+
+    #[tokio::test]
+    async fn applies_duplicate_key_policy() {
+        // `{"a": 1, "a": 2}`, split within the second key.
+        let chunks = [vec![0x82, 0xa1, b'a', 0x01, 0xa1], vec![b'a', 0x02]];
+        let extract = |policy: DuplicateKeys| {
+            let chunks = chunks.clone().map(Ok::<_, std::io::Error>);
+            let mut request = Request::new(Body::from_stream(futures_util::stream::iter(chunks)));
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            request
+                .extensions_mut()
+                .insert(MsgPackConfig::new().duplicate_keys(policy));
+            <MsgPackBuf<HashMap<String, u32>> as FromRequest<_, _>>::from_request(request, &||{})
+        };
+
+        let MsgPackBuf(map) = extract(DuplicateKeys::Allow).await.unwrap();
+        assert_eq!(map["a"], 2);
+        let MsgPackBuf(map) = extract(DuplicateKeys::KeepFirst).await.unwrap();
+        assert_eq!(map["a"], 1);
+        assert!(matches!(
+            extract(DuplicateKeys::Reject).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_body_over_limit_like_msgpack() {
+        let app = Router::new()
+            .route("/buf", post(|_: MsgPackBuf<Input>| async {}))
+            .route("/msgpack", post(|_: MsgPack<Input>| async {}))
+            .layer(DefaultBodyLimit::max(8));
+        let input = Input {
+            foo: "longer than the limit".into(),
+            bar: vec![],
+        };
+        let body = rmp_serde::encode::to_vec_named(&input).unwrap();
+
+        let mut statuses = Vec::new();
+        for uri in ["/buf", "/msgpack"] {
+            let request = Request::post(uri)
+                .header(header::CONTENT_TYPE, "application/msgpack")
+                .body(Body::from(body.clone()))
+                .unwrap();
+            statuses.push(app.clone().oneshot(request).await.unwrap().status());
+        }
+        assert_eq!(statuses, [StatusCode::PAYLOAD_TOO_LARGE; 2]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    empty::is_empty, failure::FailureFormat, msgpack_response, ApplicationMsgPack, ContentType,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// MessagePack Response serializing a borrowed value.
+///
+/// Behaves like [`MsgPack`](crate::MsgPack) used as a response, but only borrows the value,
+/// so values that are only available by reference, e.g. from shared state, do not have to be
+/// cloned.
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use axum::{extract::State, response::IntoResponse, routing::get, Router};
+/// use axum_msgpack::MsgPackRef;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Catalog {
+///     items: Vec<String>,
+/// }
+///
+/// async fn get_catalog(State(catalog): State<Arc<Catalog>>) -> impl IntoResponse {
+///     MsgPackRef(&*catalog).into_response()
+/// }
+///
+/// # let catalog: Arc<Catalog> = unimplemented!();
+/// let app: Router = Router::new()
+///     .route("/catalog", get(get_catalog))
+///     .with_state(catalog);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MsgPackRef<'a, T: ?Sized>(pub &'a T);
+
+impl<T> MsgPackRef<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        if is_empty(self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            rmp_serde::encode::to_vec_named(self.0),
+            ApplicationMsgPack::CONTENT_TYPE,
+            failure_format,
+        )
+    }
+}
+
+impl<T> IntoResponse for MsgPackRef<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    fn into_response(self) -> Response {
+        self.into_response_with(FailureFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use serde::Serialize;
+
+    use super::MsgPackRef;
+    use crate::MsgPack;
+
+    #[derive(Clone, Serialize)]
+    struct Output {
+        foo: String,
+    }
+
+    #[tokio::test]
+    async fn matches_owned_response() {
+        let output = Output { foo: "bar".into() };
+
+        let expected = MsgPack(output.clone()).into_response();
+        let res = MsgPackRef(&output).into_response();
+        assert_eq!(res.status(), expected.status());
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        assert_eq!(
+            to_bytes(res.into_body(), usize::MAX).await.unwrap(),
+            to_bytes(expected.into_body(), usize::MAX).await.unwrap()
+        );
+
+        let res = MsgPackRef(&()).into_response();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn serializes_unsized_values() {
+        let names: &[&str] = &["a", "b"];
+
+        let res = MsgPackRef(names).into_response();
+        assert_eq!(
+            to_bytes(res.into_body(), usize::MAX).await.unwrap(),
+            rmp_serde::encode::to_vec_named(names).unwrap()
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use crate::MsgPack;
+use axum::{
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+
+/// Response wrapper that adds a public `Cache-Control` header to the wrapped response.
+///
+/// Created with [`MsgPack::cache_for`], and sets `Cache-Control: public, max-age=<secs>`, with
+/// `immutable` added by [`CacheFor::immutable`] for fingerprinted resources that never change
+/// under the same URL. Only the `Cache-Control` header is touched, so it composes with status
+/// codes and other headers in axum's response tuples:
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use axum::{http::header, response::IntoResponse, routing::get, Router};
+/// use axum_msgpack::MsgPack;
+///
+/// async fn get_countries() -> impl IntoResponse {
+///     (
+///         [(header::ETAG, "\"v1\"")],
+///         MsgPack(vec!["de", "fr"]).cache_for(Duration::from_secs(3600)),
+///     )
+/// }
+///
+/// let app: Router = Router::new().route("/countries", get(get_countries));
+/// ```
+///
+/// Unsuccessful responses, e.g. the `500 Internal Server Error` sent if the value cannot be
+/// serialized, are left without the header so they are not cached.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheFor<R> {
+    inner: R,
+    max_age: Duration,
+    immutable: bool,
+}
+
+impl<R> CacheFor<R> {
+    /// Marks the response as `immutable`, so clients do not revalidate it while it is fresh.
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+}
+
+impl<T> MsgPack<T> {
+    /// Lets clients and shared caches keep the response for `max_age`.
+    pub fn cache_for(self, max_age: Duration) -> CacheFor<Self> {
+        CacheFor {
+            inner: self,
+            max_age,
+            immutable: false,
+        }
+    }
+}
+
+impl<R> IntoResponse for CacheFor<R>
+where
+    R: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut res = self.inner.into_response();
+        if res.status().is_success() {
+            let mut value = format!("public, max-age={}", self.max_age.as_secs());
+            if self.immutable {
+                value.push_str(", immutable");
+            }
+            let value = HeaderValue::from_str(&value).expect("cache control is a valid header");
+            res.headers_mut().insert(header::CACHE_CONTROL, value);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::{
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+
+    use crate::MsgPack;
+
+    #[test]
+    fn sets_cache_control() {
+        let res = MsgPack("steve")
+            .cache_for(Duration::from_secs(60))
+            .into_response();
+        assert_eq!(res.headers()[header::CACHE_CONTROL], "public, max-age=60");
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        let res = MsgPack("steve")
+            .cache_for(Duration::from_millis(365 * 24 * 3600 * 1000 + 999))
+            .immutable()
+            .into_response();
+        assert_eq!(
+            res.headers()[header::CACHE_CONTROL],
+            "public, max-age=31536000, immutable"
+        );
+    }
+
+    #[test]
+    fn composes_with_status_and_headers() {
+        let res = (
+            StatusCode::ACCEPTED,
+            [(header::ETAG, "\"v1\"")],
+            MsgPack("steve").cache_for(Duration::from_secs(5)),
+        )
+            .into_response();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        assert_eq!(res.headers()[header::ETAG], "\"v1\"");
+        assert_eq!(res.headers()[header::CACHE_CONTROL], "public, max-age=5");
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+    }
+
+    #[test]
+    fn skips_failed_responses() {
+        struct Failing;
+
+        impl serde::Serialize for Failing {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("nope"))
+            }
+        }
+
+        let res = MsgPack(Failing)
+            .cache_for(Duration::from_secs(60))
+            .into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(header::CACHE_CONTROL).is_none());
+    }
+}
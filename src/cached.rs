@@ -0,0 +1,182 @@
+use std::sync::Mutex;
+
+use crate::{
+    empty::is_empty, failure::FailureFormat, msgpack_response, ApplicationMsgPack, ContentType,
+};
+use axum::{
+    body::Bytes,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// Cache for the serialized form of a rarely-changing response.
+///
+/// Serializing a large value on every request is wasteful when it rarely changes. A
+/// `CachedMsgPack` keeps the named encoding of the last value it serialized, together with a
+/// version token supplied by the caller, and only serializes again when the token changes. The
+/// cached bytes are handed out as [`Bytes`], so every response shares the same buffer.
+///
+/// Keep it in your application state, e.g. behind an [`Arc`](std::sync::Arc):
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use axum::{extract::State, response::Response, routing::get, Router};
+/// use axum_msgpack::CachedMsgPack;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Catalog {
+///     items: Vec<String>,
+/// }
+///
+/// struct AppState {
+///     catalog: Catalog,
+///     catalog_version: u64,
+///     cache: CachedMsgPack<u64>,
+/// }
+///
+/// async fn get_catalog(State(state): State<Arc<AppState>>) -> Response {
+///     state.cache.response(state.catalog_version, &state.catalog)
+/// }
+///
+/// # let state: Arc<AppState> = unimplemented!();
+/// let app: Router = Router::new()
+///     .route("/catalog", get(get_catalog))
+///     .with_state(state);
+/// ```
+#[derive(Debug)]
+pub struct CachedMsgPack<K> {
+    cached: Mutex<Option<(K, Bytes)>>,
+}
+
+impl<K> CachedMsgPack<K>
+where
+    K: PartialEq,
+{
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self {
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns the serialized `value`, serializing it only if `token` differs from the token of
+    /// the cached bytes.
+    pub fn bytes<T>(&self, token: K, value: &T) -> Result<Bytes, rmp_serde::encode::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut cached = self.cached.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some((cached_token, bytes)) = cached.as_ref() {
+            if *cached_token == token {
+                return Ok(bytes.clone());
+            }
+        }
+
+        let bytes = Bytes::from(rmp_serde::encode::to_vec_named(value)?);
+        *cached = Some((token, bytes.clone()));
+        Ok(bytes)
+    }
+
+    /// Creates a `MsgPack` response from the cached bytes, see [`CachedMsgPack::bytes`].
+    ///
+    /// Like [`MsgPack`](crate::MsgPack), values that carry no content, `()` and unit structs,
+    /// produce an empty `204 No Content` response instead.
+    pub fn response<T>(&self, token: K, value: &T) -> Response
+    where
+        T: Serialize + ?Sized,
+    {
+        let bytes = self.bytes(token, value);
+        // Empty values encode as a single byte, nil or an empty array, so only such values are
+        // probed, keeping cache hits cheap.
+        if matches!(&bytes, Ok(bytes) if bytes.len() == 1) && is_empty(value) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            bytes,
+            ApplicationMsgPack::CONTENT_TYPE,
+            FailureFormat::default(),
+        )
+    }
+
+    /// Drops the cached bytes, so the next call serializes again.
+    pub fn clear(&self) {
+        *self.cached.lock().unwrap_or_else(|err| err.into_inner()) = None;
+    }
+}
+
+impl<K> Default for CachedMsgPack<K>
+where
+    K: PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+    };
+    use serde::{Serialize, Serializer};
+
+    use super::CachedMsgPack;
+
+    #[derive(Default)]
+    struct Counted {
+        serializations: AtomicUsize,
+    }
+
+    impl Serialize for Counted {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.serializations.fetch_add(1, Ordering::SeqCst);
+            serializer.serialize_str("catalog")
+        }
+    }
+
+    #[tokio::test]
+    async fn serializes_once_per_token() {
+        let cache = CachedMsgPack::new();
+        let value = Counted::default();
+
+        for _ in 0..3 {
+            let res = cache.response(1, &value);
+            assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+            let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(bytes, rmp_serde::encode::to_vec_named("catalog").unwrap());
+        }
+        assert_eq!(value.serializations.load(Ordering::SeqCst), 1);
+
+        cache.bytes(2, &value).unwrap();
+        cache.bytes(2, &value).unwrap();
+        assert_eq!(value.serializations.load(Ordering::SeqCst), 2);
+
+        cache.clear();
+        cache.bytes(2, &value).unwrap();
+        assert_eq!(value.serializations.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn unit_responds_with_no_content() {
+        #[derive(Serialize)]
+        struct Empty;
+
+        let res = CachedMsgPack::new().response(1, &());
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert!(res.headers().get(header::CONTENT_TYPE).is_none());
+        assert!(to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let res = CachedMsgPack::new().response(1, &Empty);
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    }
+}
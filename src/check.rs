@@ -0,0 +1,513 @@
+//! A deserializer adapter that enforces the optional checks of [`MsgPackConfig`].
+//!
+//! The adapter wraps every deserializer, visitor and access handed out while decoding.
+//!
+//! Types derived without `#[serde(deny_unknown_fields)]` skip unknown keys by deserializing
+//! their values as [`IgnoredAny`](serde::de::IgnoredAny). To reject those, the adapter
+//! remembers the last key of each map, and fails when the value of that key is deserialized
+//! with `deserialize_ignored_any`. Non-finite floats are rejected as they are visited.
+
+use std::fmt;
+
+use crate::MsgPackConfig;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, Error, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
+};
+
+/// The checks applied while decoding.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Checks {
+    unknown_fields: bool,
+    finite_floats: bool,
+}
+
+impl Checks {
+    /// Returns the checks enabled in `config`, or `None` if there are none.
+    pub(crate) fn of(config: &MsgPackConfig) -> Option<Self> {
+        let checks = Self {
+            unknown_fields: config.denies_unknown_fields(),
+            finite_floats: config.requires_finite_floats(),
+        };
+        (checks.unknown_fields || checks.finite_floats).then_some(checks)
+    }
+}
+
+/// What the wrapped deserializer is decoding.
+enum Role<'k> {
+    Any,
+    /// A map key, which is recorded so it can be named in the error.
+    Key(&'k mut Option<String>),
+    /// A map value, ignoring which means the key is unknown.
+    Value(&'k Option<String>),
+}
+
+pub(crate) struct Checked<'k, D> {
+    de: D,
+    checks: Checks,
+    role: Role<'k>,
+}
+
+impl<D> Checked<'_, D> {
+    pub(crate) fn new(de: D, checks: Checks) -> Self {
+        Self {
+            de,
+            checks,
+            role: Role::Any,
+        }
+    }
+}
+
+impl<'k, D> Checked<'k, D> {
+    fn wrap<V>(self, visitor: V) -> (D, Wrap<'k, V>) {
+        let key = match self.role {
+            Role::Key(key) => Some(key),
+            _ => None,
+        };
+        let visitor = Wrap {
+            visitor,
+            checks: self.checks,
+            key,
+        };
+        (self.de, visitor)
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+        where
+            V: Visitor<'de>,
+        {
+            let (de, visitor) = self.wrap(visitor);
+            de.$method($($arg,)* visitor)
+        }
+    )*};
+}
+
+impl<'de, D> Deserializer<'de> for Checked<'_, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let (true, Role::Value(key)) = (self.checks.unknown_fields, &self.role) {
+            return Err(match key {
+                Some(key) => D::Error::custom(format_args!("unknown field `{}`", key)),
+                None => D::Error::custom("unknown field"),
+            });
+        }
+        let (de, visitor) = self.wrap(visitor);
+        de.deserialize_ignored_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+/// Wraps the accesses handed to a visitor, records map keys and checks floats.
+struct Wrap<'k, V> {
+    visitor: V,
+    checks: Checks,
+    key: Option<&'k mut Option<String>>,
+}
+
+impl<V> Wrap<'_, V> {
+    fn record(&mut self, key: impl fmt::Display) {
+        if let Some(slot) = self.key.as_mut() {
+            **slot = Some(key.to_string());
+        }
+    }
+
+    fn check_float<E: de::Error>(&self, v: f64) -> Result<(), E> {
+        if self.checks.finite_floats && !v.is_finite() {
+            return Err(E::invalid_value(Unexpected::Float(v), &"a finite float"));
+        }
+        Ok(())
+    }
+}
+
+impl<'de, V> Visitor<'de> for Wrap<'_, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        self.visitor.visit_bool(v)
+    }
+
+    fn visit_i64<E: de::Error>(mut self, v: i64) -> Result<Self::Value, E> {
+        self.record(v);
+        self.visitor.visit_i64(v)
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        self.visitor.visit_i128(v)
+    }
+
+    fn visit_u64<E: de::Error>(mut self, v: u64) -> Result<Self::Value, E> {
+        self.record(v);
+        self.visitor.visit_u64(v)
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        self.visitor.visit_u128(v)
+    }
+
+    fn visit_f32<E: de::Error>(self, v: f32) -> Result<Self::Value, E> {
+        self.check_float(v.into())?;
+        self.visitor.visit_f32(v)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        self.check_float(v)?;
+        self.visitor.visit_f64(v)
+    }
+
+    fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+        self.visitor.visit_char(v)
+    }
+
+    fn visit_str<E: de::Error>(mut self, v: &str) -> Result<Self::Value, E> {
+        self.record(v);
+        self.visitor.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(mut self, v: &'de str) -> Result<Self::Value, E> {
+        self.record(v);
+        self.visitor.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: de::Error>(mut self, v: String) -> Result<Self::Value, E> {
+        self.record(&v);
+        self.visitor.visit_string(v)
+    }
+
+    fn visit_bytes<E: de::Error>(mut self, v: &[u8]) -> Result<Self::Value, E> {
+        self.record(String::from_utf8_lossy(v));
+        self.visitor.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(mut self, v: &'de [u8]) -> Result<Self::Value, E> {
+        self.record(String::from_utf8_lossy(v));
+        self.visitor.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E: de::Error>(mut self, v: Vec<u8>) -> Result<Self::Value, E> {
+        self.record(String::from_utf8_lossy(&v));
+        self.visitor.visit_byte_buf(v)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visitor.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.visitor
+            .visit_some(Checked::new(deserializer, self.checks))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.visitor
+            .visit_newtype_struct(Checked::new(deserializer, self.checks))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_seq(Seq(seq, self.checks))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_map(Map {
+            map,
+            checks: self.checks,
+            key: None,
+        })
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_enum(Enum(data, self.checks))
+    }
+}
+
+/// Wraps the deserializer handed to a seed.
+struct Seed<'k, S> {
+    seed: S,
+    checks: Checks,
+    role: Role<'k>,
+}
+
+impl<'de, S> DeserializeSeed<'de> for Seed<'_, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.seed.deserialize(Checked {
+            de: deserializer,
+            checks: self.checks,
+            role: self.role,
+        })
+    }
+}
+
+struct Seq<A>(A, Checks);
+
+impl<'de, A> SeqAccess<'de> for Seq<A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0.next_element_seed(Seed {
+            seed,
+            checks: self.1,
+            role: Role::Any,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+struct Map<A> {
+    map: A,
+    checks: Checks,
+    key: Option<String>,
+}
+
+impl<'de, A> MapAccess<'de> for Map<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.key = None;
+        self.map.next_key_seed(Seed {
+            seed,
+            checks: self.checks,
+            role: Role::Key(&mut self.key),
+        })
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(Seed {
+            seed,
+            checks: self.checks,
+            role: Role::Value(&self.key),
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+struct Enum<A>(A, Checks);
+
+impl<'de, A> EnumAccess<'de> for Enum<A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = Variant<A::Variant>;
+
+    fn variant_seed<T>(self, seed: T) -> Result<(T::Value, Self::Variant), A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let (value, variant) = self.0.variant_seed(Seed {
+            seed,
+            checks: self.1,
+            role: Role::Any,
+        })?;
+        Ok((value, Variant(variant, self.1)))
+    }
+}
+
+struct Variant<A>(A, Checks);
+
+impl<'de, A> VariantAccess<'de> for Variant<A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0.newtype_variant_seed(Seed {
+            seed,
+            checks: self.1,
+            role: Role::Any,
+        })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = Wrap {
+            visitor,
+            checks: self.1,
+            key: None,
+        };
+        self.0.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = Wrap {
+            visitor,
+            checks: self.1,
+            key: None,
+        };
+        self.0.struct_variant(fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{Checked, Checks};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Inner {
+        city: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Outer {
+        name: String,
+        inner: Option<Inner>,
+        tags: HashMap<String, u32>,
+    }
+
+    #[derive(Serialize)]
+    struct InnerTypo {
+        city: String,
+        zip: String,
+    }
+
+    #[derive(Serialize)]
+    struct OuterTypo {
+        name: String,
+        inner: InnerTypo,
+        tags: HashMap<String, u32>,
+    }
+
+    fn decode<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, rmp_serde::decode::Error> {
+        let checks = Checks {
+            unknown_fields: true,
+            finite_floats: true,
+        };
+        let mut de = rmp_serde::Deserializer::from_read_ref(bytes);
+        T::deserialize(Checked::new(&mut de, checks))
+    }
+
+    #[test]
+    fn accepts_known_fields() {
+        let outer = Outer {
+            name: "steve".into(),
+            inner: Some(Inner {
+                city: "Berlin".into(),
+            }),
+            tags: HashMap::from([("anything".to_owned(), 1)]),
+        };
+        let bytes = rmp_serde::encode::to_vec_named(&outer).unwrap();
+
+        assert_eq!(decode::<Outer>(&bytes).unwrap(), outer);
+    }
+
+    #[test]
+    fn rejects_nested_unknown_field() {
+        let bytes = rmp_serde::encode::to_vec_named(&OuterTypo {
+            name: "steve".into(),
+            inner: InnerTypo {
+                city: "Berlin".into(),
+                zip: "10115".into(),
+            },
+            tags: HashMap::new(),
+        })
+        .unwrap();
+
+        let err = decode::<Outer>(&bytes).unwrap_err();
+        assert_eq!(err.to_string(), "unknown field `zip`");
+    }
+}
@@ -0,0 +1,268 @@
+use std::marker::PhantomData;
+
+use crate::{
+    check_trailing,
+    declared_len::check_declared_lengths,
+    deserialize_seed,
+    duplicate::dedupe_keys,
+    empty::is_empty,
+    failure::FailureFormat,
+    msgpack_response,
+    rejection::{InvalidMsgPackBody, MsgPackBodyTooLarge, MsgPackRejection},
+    trace, ApplicationMsgPack, ContentType, MsgPackConfig,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Reusable MessagePack encoding and decoding options.
+///
+/// Bundles the options that otherwise have to be picked per call: named or positional
+/// encoding, serde's human-readable flag, the response content type, the failure format, a
+/// size limit for decoded bodies and the [`MsgPackConfig`] used for decoding. A default codec
+/// behaves exactly like [`MsgPack`](crate::MsgPack).
+///
+/// Keep the codec in your application state and use it in handlers:
+///
+/// ```no_run
+/// use axum::{body::Bytes, extract::State, response::Response, routing::post, Router};
+/// use axum_msgpack::{rejection::MsgPackRejection, MsgPackCodec};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct User {
+///     email: String,
+/// }
+///
+/// async fn create_user(
+///     State(codec): State<MsgPackCodec>,
+///     body: Bytes,
+/// ) -> Result<Response, MsgPackRejection> {
+///     let user: User = codec.decode(&body)?;
+///     Ok(codec.response(user))
+/// }
+///
+/// let codec = MsgPackCodec::new().named(false).limit(64 * 1024);
+/// let app: Router = Router::new()
+///     .route("/users", post(create_user))
+///     .with_state(codec);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MsgPackCodec {
+    named: bool,
+    human_readable: bool,
+    content_type: &'static str,
+    failure_format: FailureFormat,
+    limit: Option<usize>,
+    config: MsgPackConfig,
+}
+
+impl Default for MsgPackCodec {
+    fn default() -> Self {
+        Self {
+            named: true,
+            human_readable: false,
+            content_type: ApplicationMsgPack::CONTENT_TYPE,
+            failure_format: FailureFormat::default(),
+            limit: None,
+            config: MsgPackConfig::default(),
+        }
+    }
+}
+
+impl MsgPackCodec {
+    /// Creates a codec with the defaults of [`MsgPack`](crate::MsgPack).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes structs as maps with field names (the default), or as positional arrays like
+    /// [`MsgPackRaw`](crate::MsgPackRaw).
+    pub fn named(mut self, named: bool) -> Self {
+        self.named = named;
+        self
+    }
+
+    /// Tells types to use their human-readable representation, e.g. strings for IP addresses.
+    ///
+    /// Off by default. Both sides have to agree on the flag, so only enable it if clients do.
+    pub fn human_readable(mut self, human_readable: bool) -> Self {
+        self.human_readable = human_readable;
+        self
+    }
+
+    /// Sets the `Content-Type` of responses, `application/msgpack` by default.
+    pub fn content_type(mut self, content_type: &'static str) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Sets the format of the response sent if a value cannot be serialized.
+    pub fn failure_format(mut self, failure_format: FailureFormat) -> Self {
+        self.failure_format = failure_format;
+        self
+    }
+
+    /// Rejects bodies larger than `limit` bytes with [`MsgPackBodyTooLarge`]. No limit by
+    /// default.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the decoding options.
+    pub fn config(mut self, config: MsgPackConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Decodes `bytes` into a `T`.
+    pub fn decode<T>(&self, bytes: &[u8]) -> Result<T, MsgPackRejection>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(limit) = self.limit.filter(|limit| bytes.len() > *limit) {
+            return Err(MsgPackBodyTooLarge::new(bytes.len(), limit).into());
+        }
+
+        trace::decoding::<T>(bytes.len());
+        trace::body::<T>(bytes);
+        check_declared_lengths(bytes, &self.config)?;
+        let bytes = &*dedupe_keys(bytes, &self.config).map_err(InvalidMsgPackBody::from_err)?;
+        let mut rd = bytes;
+        let mut deserializer = rmp_serde::Deserializer::new(&mut rd);
+        let value = if self.human_readable {
+            let deserializer = &mut deserializer.with_human_readable();
+            deserialize_seed(PhantomData::<T>, deserializer, &self.config)
+        } else {
+            deserialize_seed(PhantomData::<T>, &mut deserializer, &self.config)
+        }
+        .inspect_err(trace::decode_failed::<T, _>)
+        .map_err(|err| InvalidMsgPackBody::from_err(err).with_body(bytes))?;
+        check_trailing(rd.len(), &self.config)?;
+        Ok(value)
+    }
+
+    /// Encodes `value` into a byte vector.
+    pub fn encode<T>(&self, value: &T) -> Result<Vec<u8>, rmp_serde::encode::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if !self.human_readable {
+            return if self.named {
+                rmp_serde::encode::to_vec_named(value)
+            } else {
+                rmp_serde::encode::to_vec(value)
+            };
+        }
+
+        let mut buf = Vec::new();
+        let mut serializer = rmp_serde::Serializer::new(&mut buf).with_human_readable();
+        if self.named {
+            value.serialize(&mut serializer.with_struct_map())?;
+        } else {
+            value.serialize(&mut serializer)?;
+        }
+        Ok(buf)
+    }
+
+    /// Creates a response from `value`, like [`MsgPack`](crate::MsgPack) does.
+    pub fn response<T>(&self, value: T) -> Response
+    where
+        T: Serialize,
+    {
+        if is_empty(&value) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(self.encode(&value), self.content_type, self.failure_format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackCodec;
+    use crate::{rejection::MsgPackRejection, MsgPack, MsgPackConfig};
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Host {
+        name: String,
+        addr: IpAddr,
+    }
+
+    fn host() -> Host {
+        Host {
+            name: "localhost".into(),
+            addr: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        }
+    }
+
+    #[tokio::test]
+    async fn default_matches_msgpack() {
+        let codec = MsgPackCodec::new();
+
+        let expected = MsgPack(host()).into_response();
+        let res = codec.response(host());
+        assert_eq!(res.status(), expected.status());
+        assert_eq!(res.headers(), expected.headers());
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            bytes,
+            to_bytes(expected.into_body(), usize::MAX).await.unwrap()
+        );
+
+        assert_eq!(codec.decode::<Host>(&bytes).unwrap(), host());
+        assert_eq!(codec.response(()).status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn applies_options() {
+        let codec = MsgPackCodec::new()
+            .named(false)
+            .human_readable(true)
+            .content_type("application/x-msgpack");
+
+        let res = codec.response(host());
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/x-msgpack");
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+        let expected: (String, String) = ("localhost".into(), "127.0.0.1".into());
+        assert_eq!(bytes, rmp_serde::encode::to_vec(&expected).unwrap());
+        assert_eq!(codec.decode::<Host>(&bytes).unwrap(), host());
+    }
+
+    #[test]
+    fn applies_limit_and_config() {
+        let codec = MsgPackCodec::new()
+            .limit(8)
+            .config(MsgPackConfig::new().strict(true));
+
+        let bytes = codec.encode(&host()).unwrap();
+        match codec.decode::<Host>(&bytes) {
+            Err(MsgPackRejection::MsgPackBodyTooLarge(rejection)) => {
+                assert_eq!(rejection.body_len(), bytes.len());
+                assert_eq!(rejection.limit(), 8);
+            }
+            other => unreachable!("Expected body too large rejection, got: {:?}", other),
+        }
+
+        let mut bytes = codec.encode(&1u32).unwrap();
+        assert_eq!(codec.decode::<u32>(&bytes).unwrap(), 1);
+        bytes.push(0xc0);
+        assert!(matches!(
+            codec.decode::<u32>(&bytes),
+            Err(MsgPackRejection::TrailingMsgPackBytes(_))
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    empty::is_empty, failure::FailureFormat, msgpack_response, value::Value, ApplicationMsgPack,
+    ContentType,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// MessagePack Response that leaves out map entries with a `nil` value.
+///
+/// Serializes like [`MsgPack`](crate::MsgPack), but drops every map entry whose value is `nil`,
+/// e.g. struct fields set to `None`, at any depth. This keeps payloads small for types with
+/// many optional fields, and lets clients treat the presence of a key as meaningful. `nil`
+/// elements of arrays are kept, since dropping them would shift the following elements.
+///
+/// For a single type, `#[serde(skip_serializing_if = "Option::is_none")]` on its fields does
+/// the same without the extra cost: the value is encoded, decoded into an untyped value that is
+/// pruned, and encoded again.
+///
+/// ```no_run
+/// use axum::{routing::get, Router};
+/// use axum_msgpack::MsgPackCompactNulls;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     username: String,
+///     nickname: Option<String>,
+/// }
+///
+/// async fn get_user() -> MsgPackCompactNulls<User> {
+///     // encoded as `{"username": "steve"}`
+///     MsgPackCompactNulls(User {
+///         username: "steve".into(),
+///         nickname: None,
+///     })
+/// }
+///
+/// let app: Router = Router::new().route("/user", get(get_user));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackCompactNulls<T>(pub T);
+
+impl<T> MsgPackCompactNulls<T>
+where
+    T: Serialize,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            encode_compact(&self.0),
+            ApplicationMsgPack::CONTENT_TYPE,
+            failure_format,
+        )
+    }
+}
+
+impl<T> IntoResponse for MsgPackCompactNulls<T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        self.into_response_with(FailureFormat::default())
+    }
+}
+
+fn encode_compact<T>(value: &T) -> Result<Vec<u8>, rmp_serde::encode::Error>
+where
+    T: Serialize,
+{
+    let bytes = rmp_serde::encode::to_vec_named(value)?;
+    let mut value: Value = rmp_serde::from_slice(&bytes)
+        .map_err(|err| rmp_serde::encode::Error::Syntax(err.to_string()))?;
+    prune_nils(&mut value);
+    rmp_serde::encode::to_vec(&value)
+}
+
+fn prune_nils(value: &mut Value) {
+    match value {
+        Value::Map(entries) => {
+            entries.retain(|(_, value)| !value.is_nil());
+            for (_, value) in entries {
+                prune_nils(value);
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(prune_nils),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{body::to_bytes, http::header, response::IntoResponse};
+    use serde::Serialize;
+
+    use super::MsgPackCompactNulls;
+
+    #[derive(Serialize)]
+    struct Address {
+        city: String,
+        zip: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct User {
+        username: String,
+        nickname: Option<String>,
+        address: Address,
+        history: Vec<Option<u32>>,
+        labels: BTreeMap<String, Option<String>>,
+    }
+
+    #[derive(Serialize)]
+    struct CompactAddress {
+        city: String,
+    }
+
+    #[derive(Serialize)]
+    struct CompactUser {
+        username: String,
+        address: CompactAddress,
+        history: Vec<Option<u32>>,
+        labels: BTreeMap<String, String>,
+    }
+
+    #[tokio::test]
+    async fn prunes_nil_map_values() {
+        let user = User {
+            username: "steve".into(),
+            nickname: None,
+            address: Address {
+                city: "Berlin".into(),
+                zip: None,
+            },
+            history: vec![Some(1), None],
+            labels: BTreeMap::from([("a".into(), Some("b".into())), ("c".into(), None)]),
+        };
+
+        let res = MsgPackCompactNulls(user).into_response();
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+        let expected = CompactUser {
+            username: "steve".into(),
+            address: CompactAddress {
+                city: "Berlin".into(),
+            },
+            history: vec![Some(1), None],
+            labels: BTreeMap::from([("a".into(), "b".into())]),
+        };
+        assert_eq!(bytes, rmp_serde::encode::to_vec_named(&expected).unwrap());
+    }
+}
@@ -0,0 +1,564 @@
+use axum::{
+    extract::Request,
+    http::{Extensions, HeaderValue},
+};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+
+use crate::{rejection::RejectionStatusConfig, ContentType, DuplicateKeys, MsgPackLimits};
+
+/// Decoding options for the extractors of this crate.
+///
+/// The extractors look the configuration up in the request extensions, so it can be set for a
+/// whole router, or for single routes, with an [`Extension`](axum::Extension) layer. Requests
+/// without a configuration are decoded with the defaults.
+///
+/// The defaults keep the behavior of earlier versions, so they do not include the
+/// [`MsgPackLimits`] defaults: bodies are only limited by axum's body limit and the lengths they
+/// declare. Set [`limits`](MsgPackConfig::limits) to `MsgPackLimits::new()` to apply them.
+///
+/// ```no_run
+/// use axum::{routing::post, Extension, Router};
+/// use axum_msgpack::{MsgPack, MsgPackConfig};
+///
+/// async fn create_user(MsgPack(payload): MsgPack<String>) {
+///     // payload is the only value in the body
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/users", post(create_user))
+///     .layer(Extension(MsgPackConfig::new().strict(true)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MsgPackConfig {
+    strict: bool,
+    deny_unknown_fields: bool,
+    lenient: bool,
+    accept_octet_stream: bool,
+    finite_floats: bool,
+    limits: Option<MsgPackLimits>,
+    duplicate_keys: DuplicateKeys,
+    rejection_statuses: Option<RejectionStatusConfig>,
+    response_content_type: Option<HeaderValue>,
+    #[cfg(feature = "tokio")]
+    body_timeout: Option<Duration>,
+}
+
+impl MsgPackConfig {
+    /// Creates a configuration with the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects bodies with bytes left over after the decoded value.
+    ///
+    /// By default, trailing bytes are ignored, so a body holding two concatenated values decodes
+    /// into the first one. In strict mode such bodies are rejected with
+    /// [`TrailingMsgPackBytes`](crate::rejection::TrailingMsgPackBytes) instead.
+    ///
+    /// `MsgPackStream` ignores this option, since it stops reading the body after the value.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Rejects maps with keys that the target type does not know.
+    ///
+    /// Works like putting `#[serde(deny_unknown_fields)]` on every struct and struct variant
+    /// that is decoded, including types from other crates. Keys are checked wherever a type
+    /// skips them as [`IgnoredAny`](serde::de::IgnoredAny), which is how derived `Deserialize`
+    /// implementations handle unknown fields. Hand-written implementations that skip keys by
+    /// other means, and `#[serde(flatten)]` fields, which buffer all remaining keys, are not
+    /// checked. Unknown keys are rejected with
+    /// [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody).
+    pub fn deny_unknown_fields(mut self, deny: bool) -> Self {
+        self.deny_unknown_fields = deny;
+        self
+    }
+
+    /// Decodes bodies whose `Content-Type` header is missing or cannot be parsed.
+    ///
+    /// By default, such requests are rejected with
+    /// [`MissingMsgPackContentType`](crate::rejection::MissingMsgPackContentType) without
+    /// looking at the body. In lenient mode the body is decoded anyway, and the request is only
+    /// rejected, with the same rejection, if decoding fails. Requests that name another content
+    /// type, such as `application/json`, are still rejected.
+    ///
+    /// Supported by [`MsgPack`](crate::MsgPack), [`MsgPackRaw`](crate::MsgPackRaw) and the
+    /// extractors built on them.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Decodes bodies sent as `application/octet-stream`.
+    ///
+    /// Some clients and proxies replace the content type of binary bodies with
+    /// `application/octet-stream`. By default such requests are rejected with
+    /// [`MissingMsgPackContentType`](crate::rejection::MissingMsgPackContentType) like any other
+    /// content type, with this option they are decoded as if they named a MessagePack type.
+    ///
+    /// Supported by all extractors of this crate that check the `Content-Type` header, except
+    /// [`MatchedMsgPackMime`](crate::MatchedMsgPackMime), which only hands out MessagePack
+    /// types.
+    pub fn accept_octet_stream(mut self, accept: bool) -> Self {
+        self.accept_octet_stream = accept;
+        self
+    }
+
+    /// Rejects `NaN` and infinite floats.
+    ///
+    /// MessagePack can encode any IEEE 754 value, but many applications never expect `NaN` or
+    /// `±Infinity`, which e.g. compare unequal to themselves or cannot be stored as JSON. With
+    /// this option such floats are rejected with
+    /// [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody), wherever they occur in the
+    /// decoded value.
+    pub fn finite_floats(mut self, finite: bool) -> Self {
+        self.finite_floats = finite;
+        self
+    }
+
+    /// Rejects strings longer than `len` bytes.
+    ///
+    /// The lengths of strings are checked before the body is decoded, so a long string is
+    /// rejected with [`ValueTooLong`](crate::rejection::ValueTooLong) before any memory is
+    /// allocated for it. No limit by default, other than the size of the body.
+    pub fn max_str_len(mut self, len: usize) -> Self {
+        self.limits = Some(self.decode_limits().max_str_len(len));
+        self
+    }
+
+    /// Rejects binary values longer than `len` bytes, like [`MsgPackConfig::max_str_len`] does
+    /// for strings.
+    pub fn max_bin_len(mut self, len: usize) -> Self {
+        self.limits = Some(self.decode_limits().max_bin_len(len));
+        self
+    }
+
+    /// Sets all limits on the size and shape of bodies at once, see [`MsgPackLimits`].
+    ///
+    /// Replaces the limits set with [`MsgPackConfig::max_str_len`] and
+    /// [`MsgPackConfig::max_bin_len`] before. No limits by default, for compatibility with
+    /// earlier versions, not even the defaults of [`MsgPackLimits::new`].
+    pub fn limits(mut self, limits: MsgPackLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Sets how maps with duplicate keys are handled, see [`DuplicateKeys`].
+    ///
+    /// Supported by [`MsgPack`](crate::MsgPack), [`MsgPackRaw`](crate::MsgPackRaw),
+    /// [`MsgPackCodec`](crate::MsgPackCodec), [`from_request_seed`](crate::from_request_seed)
+    /// and the extractors built on them, as well as by `MsgPackBuf`,
+    /// [`MsgPackSeq`](crate::MsgPackSeq), [`MsgPackPartial`](crate::MsgPackPartial) and
+    /// [`MsgPackBatch`](crate::MsgPackBatch).
+    pub fn duplicate_keys(mut self, policy: DuplicateKeys) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Sets the status codes of rejection responses, see [`RejectionStatusConfig`].
+    ///
+    /// Applied by the [`apply_rejection_statuses`](crate::apply_rejection_statuses) middleware,
+    /// which has to run inside the layer that adds this configuration. Rejections keep their
+    /// own status by default.
+    pub fn rejection_statuses(mut self, statuses: RejectionStatusConfig) -> Self {
+        self.rejection_statuses = Some(statuses);
+        self
+    }
+
+    /// Sets the content type of responses that are sent as `application/msgpack` by default to
+    /// the one of `CT`, e.g. to [`XMsgPack`](crate::XMsgPack) for legacy clients.
+    ///
+    /// Applied by the [`apply_response_content_type`](crate::apply_response_content_type)
+    /// middleware, which has to run inside the layer that adds this configuration. Responses
+    /// with an explicit content type keep it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the content type of `CT` is not a valid header value, which types declared with
+    /// [`msgpack_content_type!`](crate::msgpack_content_type) rule out.
+    pub fn response_content_type<CT: ContentType>(mut self) -> Self {
+        self.response_content_type = Some(HeaderValue::from_static(CT::CONTENT_TYPE));
+        self
+    }
+
+    /// Rejects requests whose body is not received within `timeout`.
+    ///
+    /// Protects against clients that keep a connection busy by sending the body slowly, or by
+    /// stalling midway. The timeout starts when the extractor starts reading the body, and
+    /// requests exceeding it are rejected with
+    /// [`BodyReadTimeout`](crate::rejection::BodyReadTimeout), a `408 Request Timeout` response.
+    /// No timeout by default.
+    ///
+    /// Supported by all extractors of this crate that read the whole body, so not by
+    /// `MsgPackStream`. Only available with the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn body_timeout(mut self, timeout: Duration) -> Self {
+        self.body_timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub(crate) fn denies_unknown_fields(&self) -> bool {
+        self.deny_unknown_fields
+    }
+
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    pub(crate) fn accepts_octet_stream(&self) -> bool {
+        self.accept_octet_stream
+    }
+
+    pub(crate) fn requires_finite_floats(&self) -> bool {
+        self.finite_floats
+    }
+
+    pub(crate) fn decode_limits(&self) -> MsgPackLimits {
+        self.limits.unwrap_or_else(MsgPackLimits::unlimited)
+    }
+
+    pub(crate) fn duplicate_key_policy(&self) -> DuplicateKeys {
+        self.duplicate_keys
+    }
+
+    pub(crate) fn rejection_status_config(&self) -> Option<&RejectionStatusConfig> {
+        self.rejection_statuses.as_ref()
+    }
+
+    pub(crate) fn default_response_content_type(&self) -> Option<&HeaderValue> {
+        self.response_content_type.as_ref()
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn body_read_timeout(&self) -> Option<Duration> {
+        self.body_timeout
+    }
+
+    pub(crate) fn of<B>(req: &Request<B>) -> Self {
+        Self::of_extensions(req.extensions())
+    }
+
+    pub(crate) fn of_extensions(extensions: &Extensions) -> Self {
+        extensions.get::<Self>().cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackConfig;
+    use crate::{rejection::MsgPackRejection, MsgPack};
+
+    fn into_request(body: Vec<u8>, config: Option<MsgPackConfig>) -> Request<Body> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        if let Some(config) = config {
+            request.extensions_mut().insert(config);
+        }
+        request
+    }
+
+    fn concatenated() -> Vec<u8> {
+        let mut body = rmp_serde::encode::to_vec("first").unwrap();
+        body.extend(rmp_serde::encode::to_vec("second").unwrap());
+        body
+    }
+
+    #[tokio::test]
+    async fn tolerates_trailing_bytes_by_default() {
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            into_request(concatenated(), None),
+            &|| {},
+        )
+        .await;
+
+        assert_eq!(outcome.unwrap().0, "first");
+    }
+
+    #[tokio::test]
+    async fn strict_accepts_exact_length() {
+        let body = rmp_serde::encode::to_vec("first").unwrap();
+        let config = MsgPackConfig::new().strict(true);
+
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            into_request(body, Some(config)),
+            &|| {},
+        )
+        .await;
+
+        assert_eq!(outcome.unwrap().0, "first");
+    }
+
+    #[tokio::test]
+    async fn strict_rejects_trailing_bytes() {
+        let config = MsgPackConfig::new().strict(true);
+
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            into_request(concatenated(), Some(config)),
+            &|| {},
+        )
+        .await;
+
+        match outcome {
+            Err(MsgPackRejection::TrailingMsgPackBytes(rejection)) => {
+                assert_eq!(rejection.trailing_len(), 7);
+                assert_eq!(
+                    rejection.to_string(),
+                    "Found 7 trailing bytes after the MsgPack value in the request body"
+                );
+            }
+            other => unreachable!("Expected trailing bytes rejection, got: {:?}", other),
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Input {
+        foo: String,
+    }
+
+    #[derive(Serialize)]
+    struct InputWithExtra {
+        foo: String,
+        bar: u32,
+    }
+
+    #[tokio::test]
+    async fn denies_unknown_fields_when_configured() {
+        let body = rmp_serde::encode::to_vec_named(&InputWithExtra {
+            foo: "foo".into(),
+            bar: 1,
+        })
+        .unwrap();
+
+        let outcome = <MsgPack<Input> as FromRequest<_, _>>::from_request(
+            into_request(body.clone(), None),
+            &|| {},
+        )
+        .await;
+        assert_eq!(outcome.unwrap().0, Input { foo: "foo".into() });
+
+        let config = MsgPackConfig::new().deny_unknown_fields(true);
+        let outcome = <MsgPack<Input> as FromRequest<_, _>>::from_request(
+            into_request(body, Some(config)),
+            &|| {},
+        )
+        .await;
+        match outcome {
+            Err(MsgPackRejection::InvalidMsgPackBody(rejection)) => {
+                let source = std::error::Error::source(&rejection).unwrap();
+                let expected = if cfg!(feature = "path-to-error") {
+                    "bar: unknown field `bar`"
+                } else {
+                    "unknown field `bar`"
+                };
+                assert_eq!(source.to_string(), expected);
+            }
+            other => unreachable!("Expected invalid body rejection, got: {:?}", other),
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Reading {
+        sensor: String,
+        values: Vec<f64>,
+    }
+
+    #[tokio::test]
+    async fn rejects_non_finite_floats_when_configured() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let reading = Reading {
+                sensor: "t1".into(),
+                values: vec![1.5, value],
+            };
+            let body = rmp_serde::encode::to_vec_named(&reading).unwrap();
+
+            let outcome = <MsgPack<Reading> as FromRequest<_, _>>::from_request(
+                into_request(body.clone(), None),
+                &|| {},
+            )
+            .await;
+            let decoded = outcome.unwrap().0;
+            assert_eq!(decoded.values[1].to_bits(), value.to_bits());
+
+            let config = MsgPackConfig::new().finite_floats(true);
+            let outcome = <MsgPack<Reading> as FromRequest<_, _>>::from_request(
+                into_request(body, Some(config)),
+                &|| {},
+            )
+            .await;
+            match outcome {
+                Err(MsgPackRejection::InvalidMsgPackBody(rejection)) => {
+                    let source = std::error::Error::source(&rejection).unwrap();
+                    let path = if cfg!(feature = "path-to-error") {
+                        "values[1]: "
+                    } else {
+                        ""
+                    };
+                    assert_eq!(
+                        source.to_string(),
+                        format!(
+                            "{}invalid value: floating point `{}`, expected a finite float",
+                            path, value
+                        )
+                    );
+                }
+                other => unreachable!("Expected invalid body rejection, got: {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn finite_floats_accepts_finite_f32() {
+        let body = rmp_serde::encode::to_vec(&(1.5f32, -0.0f32)).unwrap();
+        let config = MsgPackConfig::new().finite_floats(true);
+
+        let outcome = <MsgPack<(f32, f32)> as FromRequest<_, _>>::from_request(
+            into_request(body, Some(config)),
+            &|| {},
+        )
+        .await;
+
+        assert_eq!(outcome.unwrap().0, (1.5, -0.0));
+    }
+
+    fn with_content_type(
+        body: Vec<u8>,
+        content_type: &'static str,
+        lenient: bool,
+    ) -> Request<Body> {
+        let mut request = into_request(body, Some(MsgPackConfig::new().lenient(lenient)));
+        request
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        request
+    }
+
+    #[tokio::test]
+    async fn lenient_decodes_garbage_content_type() {
+        let body = rmp_serde::encode::to_vec("first").unwrap();
+
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            with_content_type(body.clone(), "not a mime", false),
+            &|| {},
+        )
+        .await;
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::MissingMsgPackContentType(_))
+        ));
+
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            with_content_type(body.clone(), "not a mime", true),
+            &|| {},
+        )
+        .await;
+        assert_eq!(outcome.unwrap().0, "first");
+
+        let mut request = with_content_type(body, "not a mime", true);
+        request.headers_mut().remove(header::CONTENT_TYPE);
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        assert_eq!(outcome.unwrap().0, "first");
+    }
+
+    #[tokio::test]
+    async fn lenient_rejects_undecodable_or_other_content_type() {
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            with_content_type(vec![0xc1], "not a mime", true),
+            &|| {},
+        )
+        .await;
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::MissingMsgPackContentType(_))
+        ));
+
+        let body = rmp_serde::encode::to_vec("first").unwrap();
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            with_content_type(body, "application/json", true),
+            &|| {},
+        )
+        .await;
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::MissingMsgPackContentType(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn accepts_octet_stream_when_configured() {
+        let body = rmp_serde::encode::to_vec("first").unwrap();
+        let config = MsgPackConfig::new().accept_octet_stream(true);
+
+        let mut request = into_request(body.clone(), None);
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::MissingMsgPackContentType(_))
+        ));
+
+        for content_type in ["application/octet-stream", "Application/Octet-Stream; a=b"] {
+            let mut request = into_request(body.clone(), Some(config.clone()));
+            request
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+            let outcome =
+                <MsgPack<String> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+            assert_eq!(outcome.unwrap().0, "first", "{}", content_type);
+        }
+
+        // Extractors without lenient mode accept it as well.
+        let body = rmp_serde::encode::to_vec(&["first"]).unwrap();
+        let mut request = into_request(body, Some(config));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        let outcome =
+            <crate::MsgPackBatch<String> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        assert_eq!(outcome.unwrap().0, ["first"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_strings_over_limit() {
+        let config = MsgPackConfig::new().max_str_len(8);
+
+        let body = rmp_serde::encode::to_vec("short").unwrap();
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            into_request(body, Some(config.clone())),
+            &|| {},
+        )
+        .await;
+        assert_eq!(outcome.unwrap().0, "short");
+
+        // str 32 claiming 4 GiB, on a body of a few bytes.
+        let body = vec![0xdb, 0xff, 0xff, 0xff, 0xff, b'a', b'b'];
+        let outcome = <MsgPack<String> as FromRequest<_, _>>::from_request(
+            into_request(body, Some(config)),
+            &|| {},
+        )
+        .await;
+        match outcome {
+            Err(MsgPackRejection::ValueTooLong(rejection)) => {
+                assert_eq!(rejection.declared_len(), u32::MAX as usize);
+                assert_eq!(rejection.limit(), 8);
+            }
+            other => unreachable!("Expected value too long rejection, got: {:?}", other),
+        }
+    }
+}
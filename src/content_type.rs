@@ -0,0 +1,352 @@
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{empty::is_empty, failure::FailureFormat, msgpack_response};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// A content type for MessagePack responses, chosen at compile time.
+///
+/// Implemented by the marker types [`ApplicationMsgPack`], [`XMsgPack`] and [`VndMsgPack`], and
+/// can be implemented for other `application/*+msgpack` types as well, preferably with
+/// [`msgpack_content_type!`](crate::msgpack_content_type), which checks the type at compile
+/// time. Hand-written impls with an invalid header value only fail once a response is built.
+pub trait ContentType {
+    /// Value of the `Content-Type` header.
+    const CONTENT_TYPE: &'static str;
+}
+
+/// Marker for `application/msgpack`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApplicationMsgPack;
+
+impl ContentType for ApplicationMsgPack {
+    const CONTENT_TYPE: &'static str = "application/msgpack";
+}
+
+/// Marker for `application/x-msgpack`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XMsgPack;
+
+impl ContentType for XMsgPack {
+    const CONTENT_TYPE: &'static str = "application/x-msgpack";
+}
+
+/// Marker for `application/vnd.msgpack`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VndMsgPack;
+
+impl ContentType for VndMsgPack {
+    const CONTENT_TYPE: &'static str = "application/vnd.msgpack";
+}
+
+/// Declares a [`ContentType`] marker, checking at compile time that the content type is a valid
+/// header value and a MessagePack media type.
+///
+/// Accepted are `application/msgpack`, `application/x-msgpack`, `application/vnd.msgpack` and
+/// `application/*+msgpack` types, in any case, optionally followed by parameters:
+///
+/// ```
+/// use axum::{http::header, response::IntoResponse};
+/// use axum_msgpack::{msgpack_content_type, MsgPackAs};
+///
+/// msgpack_content_type! {
+///     /// Marker for `application/problem+msgpack`.
+///     pub ProblemMsgPack = "application/problem+msgpack"
+/// }
+///
+/// let res = MsgPackAs::<_, ProblemMsgPack>::new("not found").into_response();
+/// assert_eq!(res.headers()[header::CONTENT_TYPE], "application/problem+msgpack");
+/// ```
+///
+/// Other media types do not compile:
+///
+/// ```compile_fail
+/// axum_msgpack::msgpack_content_type!(Json = "application/json");
+/// ```
+///
+/// Neither do values that cannot be sent in a header:
+///
+/// ```compile_fail
+/// axum_msgpack::msgpack_content_type!(Typo = "application/msgpack\n");
+/// ```
+#[macro_export]
+macro_rules! msgpack_content_type {
+    ($(#[$attr:meta])* $vis:vis $name:ident = $content_type:literal $(;)?) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default)]
+        $vis struct $name;
+
+        const _: () = assert!(
+            $crate::__private::is_message_pack_content_type($content_type),
+            concat!(
+                "`",
+                $content_type,
+                "` is not a valid MessagePack content type"
+            ),
+        );
+
+        impl $crate::ContentType for $name {
+            const CONTENT_TYPE: &'static str = $content_type;
+        }
+    };
+}
+
+/// Returns `true` if `content_type` is a valid header value naming a MessagePack media type,
+/// see [`msgpack_content_type!`](crate::msgpack_content_type).
+#[doc(hidden)]
+pub const fn is_message_pack_content_type(content_type: &str) -> bool {
+    let bytes = content_type.as_bytes();
+
+    // The essence, `type/subtype`, ends at the first parameter.
+    let mut end = 0;
+    let mut slash = None;
+    while end < bytes.len() && bytes[end] != b';' {
+        match bytes[end] {
+            b'/' if slash.is_none() => slash = Some(end),
+            byte if is_restricted_name_char(byte) => {}
+            _ => return false,
+        }
+        end += 1;
+    }
+    // Parameters may contain any visible ASCII, spaces and tabs.
+    let mut index = end;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if byte != b'\t' && !(byte >= b' ' && byte < 0x7f) {
+            return false;
+        }
+        index += 1;
+    }
+
+    let Some(slash) = slash else {
+        return false;
+    };
+    if !eq_ignore_case(bytes, 0, slash, b"application") {
+        return false;
+    }
+    let subtype = slash + 1;
+    eq_ignore_case(bytes, subtype, end, b"msgpack")
+        || eq_ignore_case(bytes, subtype, end, b"x-msgpack")
+        || eq_ignore_case(bytes, subtype, end, b"vnd.msgpack")
+        || (end - subtype > b"+msgpack".len()
+            && eq_ignore_case(bytes, end - b"+msgpack".len(), end, b"+msgpack"))
+}
+
+/// Characters allowed in the type and subtype of a media type, see RFC 6838, section 4.2.
+const fn is_restricted_name_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+        || matches!(
+            byte,
+            b'!' | b'#' | b'$' | b'&' | b'-' | b'^' | b'_' | b'.' | b'+'
+        )
+}
+
+/// Compares `bytes[start..end]` with `expected`, ignoring ASCII case.
+const fn eq_ignore_case(bytes: &[u8], start: usize, end: usize, expected: &[u8]) -> bool {
+    if end - start != expected.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < expected.len() {
+        if !bytes[start + index].eq_ignore_ascii_case(&expected[index]) {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// MessagePack Response with a content type chosen at compile time.
+///
+/// Serializes like [`MsgPack`](crate::MsgPack), but sets the `Content-Type` header to
+/// [`CT::CONTENT_TYPE`](ContentType::CONTENT_TYPE). The default `CT` is
+/// [`ApplicationMsgPack`], which matches [`MsgPack`](crate::MsgPack).
+///
+/// ```no_run
+/// use axum::{routing::get, Router};
+/// use axum_msgpack::MsgPackX;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     username: String,
+/// }
+///
+/// async fn get_user() -> MsgPackX<User> {
+///     // responds with `Content-Type: application/x-msgpack`
+///     MsgPackX::new(User { username: "steve".into() })
+/// }
+///
+/// let app: Router = Router::new().route("/user", get(get_user));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackAs<T, CT = ApplicationMsgPack>(pub T, PhantomData<CT>);
+
+/// MessagePack Response with `Content-Type: application/x-msgpack`.
+pub type MsgPackX<T> = MsgPackAs<T, XMsgPack>;
+
+impl<T, CT> MsgPackAs<T, CT> {
+    /// Wraps `value` for a response with the content type `CT`.
+    pub fn new(value: T) -> Self {
+        Self(value, PhantomData)
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, CT> Deref for MsgPackAs<T, CT> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, CT> DerefMut for MsgPackAs<T, CT> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, CT> From<T> for MsgPackAs<T, CT> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T, CT> MsgPackAs<T, CT>
+where
+    T: Serialize,
+    CT: ContentType,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            rmp_serde::encode::to_vec_named(&self.0),
+            CT::CONTENT_TYPE,
+            failure_format,
+        )
+    }
+}
+
+impl<T, CT> IntoResponse for MsgPackAs<T, CT>
+where
+    T: Serialize,
+    CT: ContentType,
+{
+    fn into_response(self) -> Response {
+        self.into_response_with(FailureFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{http::header, response::IntoResponse};
+    use serde::Serialize;
+
+    use super::{
+        is_message_pack_content_type, ApplicationMsgPack, ContentType, MsgPackAs, MsgPackX,
+        VndMsgPack, XMsgPack,
+    };
+
+    #[derive(Serialize)]
+    struct Output {
+        foo: String,
+    }
+
+    fn output() -> Output {
+        Output { foo: "bar".into() }
+    }
+
+    #[test]
+    fn sets_content_type_of_marker() {
+        let res = MsgPackAs::<_>::new(output()).into_response();
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        let res = MsgPackX::new(output()).into_response();
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/x-msgpack");
+
+        let res = MsgPackAs::<_, VndMsgPack>::new(output()).into_response();
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            "application/vnd.msgpack"
+        );
+    }
+
+    #[test]
+    fn supports_custom_markers() {
+        struct Problem;
+
+        impl ContentType for Problem {
+            const CONTENT_TYPE: &'static str = "application/problem+msgpack";
+        }
+
+        let res = MsgPackAs::<_, Problem>::new(output()).into_response();
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            "application/problem+msgpack"
+        );
+    }
+
+    #[test]
+    fn declares_checked_markers() {
+        crate::msgpack_content_type!(Problem = "application/problem+msgpack");
+
+        let res = MsgPackAs::<_, Problem>::new(output()).into_response();
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            "application/problem+msgpack"
+        );
+    }
+
+    #[test]
+    fn checks_message_pack_content_types() {
+        for content_type in [
+            ApplicationMsgPack::CONTENT_TYPE,
+            XMsgPack::CONTENT_TYPE,
+            VndMsgPack::CONTENT_TYPE,
+            "application/vnd.myapi.v2+msgpack",
+            "Application/MsgPack",
+            "application/msgpack; charset=binary",
+        ] {
+            assert!(
+                is_message_pack_content_type(content_type),
+                "{}",
+                content_type
+            );
+        }
+
+        for content_type in [
+            "",
+            "application",
+            "application/",
+            "application/json",
+            "text/msgpack",
+            "application/+msgpack",
+            "application/msgpack/x",
+            "application/msg pack",
+            "application/msgpack\n",
+            "application/msgpack; name=\u{e9}",
+        ] {
+            assert!(
+                !is_message_pack_content_type(content_type),
+                "{:?}",
+                content_type
+            );
+        }
+    }
+}
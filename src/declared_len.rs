@@ -0,0 +1,331 @@
+use bytes::Buf;
+use rmp::Marker;
+
+use crate::{
+    rejection::{
+        CollectionTooLong, DeclaredLengthTooLarge, MsgPackBodyTooLarge, MsgPackRejection,
+        NestingTooDeep, ValueTooLong,
+    },
+    MsgPackConfig,
+};
+
+/// Checks the declared lengths in the first MessagePack value of `buf` before it is decoded.
+///
+/// `rmp-serde` passes the declared length of arrays and maps on as the
+/// [`size_hint`](serde::de::SeqAccess::size_hint) of the collection, without comparing it with
+/// the length of the input. The collections of `std` cap what they allocate up front, but other
+/// `Deserialize` impls may trust the hint, so a header claiming billions of elements could make
+/// them allocate far more memory than the body is worth. Every element takes at least one byte,
+/// so values declaring more elements or bytes than the rest of the body holds can never be
+/// decoded anyway, and are rejected with [`DeclaredLengthTooLarge`].
+///
+/// The [`MsgPackLimits`](crate::MsgPackLimits) of `config` are checked on the way, so bodies
+/// exceeding them are rejected before any of their values is decoded.
+///
+/// Other malformed input, e.g. a truncated header, is left for the decoder to report.
+pub(crate) fn check_declared_lengths(
+    mut buf: impl Buf,
+    config: &MsgPackConfig,
+) -> Result<(), MsgPackRejection> {
+    let limits = config.decode_limits();
+    if let Some(limit) = limits.max_body_len.filter(|limit| buf.remaining() > *limit) {
+        return Err(MsgPackBodyTooLarge::new(buf.remaining(), limit).into());
+    }
+
+    // The number of values left in the top level and in each enclosing array or map.
+    let mut pending: Vec<usize> = vec![1];
+    while let Some(left) = pending.last_mut() {
+        if *left == 0 {
+            pending.pop();
+            continue;
+        }
+        *left -= 1;
+        if !buf.has_remaining() {
+            return Ok(());
+        }
+
+        let item = match Marker::from_u8(buf.get_u8()) {
+            Marker::FixArray(len) => Item::Values(usize::from(len), 1),
+            Marker::Array16 => values(read_len(&mut buf, 2), 1),
+            Marker::Array32 => values(read_len(&mut buf, 4), 1),
+            Marker::FixMap(len) => Item::Values(usize::from(len), 2),
+            Marker::Map16 => values(read_len(&mut buf, 2), 2),
+            Marker::Map32 => values(read_len(&mut buf, 4), 2),
+            Marker::Null | Marker::True | Marker::False | Marker::FixPos(_) | Marker::FixNeg(_) => {
+                Item::Skip(0)
+            }
+            Marker::U8 | Marker::I8 => Item::Skip(1),
+            Marker::U16 | Marker::I16 => Item::Skip(2),
+            Marker::U32 | Marker::I32 | Marker::F32 => Item::Skip(4),
+            Marker::U64 | Marker::I64 | Marker::F64 => Item::Skip(8),
+            Marker::FixStr(len) => Item::Str(usize::from(len)),
+            Marker::Str8 => read_len(&mut buf, 1).map_or(Item::Stop, Item::Str),
+            Marker::Str16 => read_len(&mut buf, 2).map_or(Item::Stop, Item::Str),
+            Marker::Str32 => read_len(&mut buf, 4).map_or(Item::Stop, Item::Str),
+            Marker::Bin8 => read_len(&mut buf, 1).map_or(Item::Stop, Item::Bin),
+            Marker::Bin16 => read_len(&mut buf, 2).map_or(Item::Stop, Item::Bin),
+            Marker::Bin32 => read_len(&mut buf, 4).map_or(Item::Stop, Item::Bin),
+            Marker::FixExt1 => Item::Skip(2),
+            Marker::FixExt2 => Item::Skip(3),
+            Marker::FixExt4 => Item::Skip(5),
+            Marker::FixExt8 => Item::Skip(9),
+            Marker::FixExt16 => Item::Skip(17),
+            // The extension type follows the length.
+            Marker::Ext8 => {
+                read_len(&mut buf, 1).map_or(Item::Stop, |len| Item::Skip(len.saturating_add(1)))
+            }
+            Marker::Ext16 => {
+                read_len(&mut buf, 2).map_or(Item::Stop, |len| Item::Skip(len.saturating_add(1)))
+            }
+            Marker::Ext32 => {
+                read_len(&mut buf, 4).map_or(Item::Stop, |len| Item::Skip(len.saturating_add(1)))
+            }
+            Marker::Reserved => Item::Stop,
+        };
+
+        let remaining = buf.remaining();
+        let skip = match item {
+            Item::Values(len, per_element) => {
+                // `pending` holds the top level and the enclosing collections, so its length is
+                // the depth of this collection.
+                if let Some(limit) = limits.max_depth.filter(|limit| pending.len() > *limit) {
+                    return Err(NestingTooDeep::new(limit).into());
+                }
+                let map = per_element == 2;
+                let limit = [
+                    limits.max_collection_len,
+                    limits.max_map_entries.filter(|_| map),
+                ]
+                .into_iter()
+                .flatten()
+                .min();
+                if let Some(limit) = limit.filter(|limit| len > *limit) {
+                    return Err(CollectionTooLong::new(map, len, limit).into());
+                }
+                let needed = len.saturating_mul(per_element);
+                if needed > remaining {
+                    return Err(DeclaredLengthTooLarge::new(len, remaining).into());
+                }
+                pending.push(needed);
+                0
+            }
+            Item::Str(len) => {
+                check_limit(len, limits.max_str_len, false)?;
+                len
+            }
+            Item::Bin(len) => {
+                check_limit(len, limits.max_bin_len, true)?;
+                len
+            }
+            Item::Skip(len) => len,
+            Item::Stop => return Ok(()),
+        };
+        if skip > remaining {
+            return Err(DeclaredLengthTooLarge::new(skip, remaining).into());
+        }
+        buf.advance(skip);
+    }
+    Ok(())
+}
+
+/// What follows a marker.
+enum Item {
+    /// An array or map of the given length, with the given number of values per element.
+    Values(usize, usize),
+    /// A string of the given length.
+    Str(usize),
+    /// A binary value of the given length.
+    Bin(usize),
+    /// The given number of bytes to skip.
+    Skip(usize),
+    /// Malformed input, left for the decoder.
+    Stop,
+}
+
+fn values(len: Option<usize>, per_element: usize) -> Item {
+    len.map_or(Item::Stop, |len| Item::Values(len, per_element))
+}
+
+fn check_limit(len: usize, limit: Option<usize>, binary: bool) -> Result<(), ValueTooLong> {
+    match limit {
+        Some(limit) if len > limit => Err(ValueTooLong::new(binary, len, limit)),
+        _ => Ok(()),
+    }
+}
+
+/// Reads a big-endian length of `width` bytes, or `None` if the body ends first.
+fn read_len(buf: &mut impl Buf, width: usize) -> Option<usize> {
+    if buf.remaining() < width {
+        return None;
+    }
+    let len = match width {
+        1 => u64::from(buf.get_u8()),
+        2 => u64::from(buf.get_u16()),
+        _ => u64::from(buf.get_u32()),
+    };
+    usize::try_from(len).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_declared_lengths;
+    use crate::{rejection::MsgPackRejection, MsgPackConfig, MsgPackLimits};
+
+    fn check(bytes: &[u8], config: &MsgPackConfig) -> Result<(), MsgPackRejection> {
+        check_declared_lengths(bytes, config)
+    }
+
+    fn declared(bytes: &[u8]) -> (usize, usize) {
+        match check(bytes, &MsgPackConfig::default()) {
+            Err(MsgPackRejection::DeclaredLengthTooLarge(rejection)) => {
+                (rejection.declared_len(), rejection.remaining())
+            }
+            other => unreachable!("Expected declared length rejection, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn accepts_well_formed_values() {
+        let value = (vec![1u32, 2, 3], "text", [("a", 1.5f64)], (), true);
+        let bytes = rmp_serde::encode::to_vec_named(&value).unwrap();
+        assert!(check(&bytes, &MsgPackConfig::default()).is_ok());
+
+        // An empty array of empty arrays, and a truncated header left for the decoder.
+        assert!(check(&[0x91, 0x90], &MsgPackConfig::default()).is_ok());
+        assert!(check(&[0xdc, 0x00], &MsgPackConfig::default()).is_ok());
+        assert!(check(&[], &MsgPackConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        // array 32 claiming u32::MAX elements, followed by three bytes.
+        assert_eq!(
+            declared(&[0xdd, 0xff, 0xff, 0xff, 0xff, 1, 2, 3]),
+            (u32::MAX as usize, 3)
+        );
+
+        // A map of two entries needs at least four bytes.
+        assert_eq!(declared(&[0x82, 0xa1, b'a', 1]), (2, 3));
+
+        // Nested inside an array, after a string.
+        assert_eq!(
+            declared(&[0x92, 0xa2, b'h', b'i', 0xdc, 0x10, 0x00]),
+            (0x1000, 0)
+        );
+
+        // str 32 claiming 4 GiB, and bin 8 claiming more than is left.
+        assert_eq!(
+            declared(&[0xdb, 0xff, 0xff, 0xff, 0xff, b'a']),
+            (u32::MAX as usize, 1)
+        );
+        assert_eq!(declared(&[0xc4, 0x03, 0x00]), (3, 1));
+    }
+
+    #[test]
+    fn enforces_configured_limits() {
+        let config = MsgPackConfig::new().max_str_len(4).max_bin_len(2);
+
+        assert!(check(
+            &[0x92, 0xa4, b'a', b'b', b'c', b'd', 0xc4, 0x02, 0, 0],
+            &config
+        )
+        .is_ok());
+
+        // A huge string on a short body is rejected by the limit first.
+        match check(&[0xdb, 0xff, 0xff, 0xff, 0xff, b'a'], &config) {
+            Err(MsgPackRejection::ValueTooLong(rejection)) => {
+                assert!(!rejection.is_binary());
+                assert_eq!(rejection.declared_len(), u32::MAX as usize);
+                assert_eq!(rejection.limit(), 4);
+            }
+            other => unreachable!("Expected value too long rejection, got: {:?}", other),
+        }
+
+        match check(&[0x91, 0xc4, 0x03, 0, 0, 0], &config) {
+            Err(MsgPackRejection::ValueTooLong(rejection)) => {
+                assert!(rejection.is_binary());
+                assert_eq!(rejection.declared_len(), 3);
+                assert_eq!(rejection.limit(), 2);
+            }
+            other => unreachable!("Expected value too long rejection, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn enforces_depth_and_collection_limits() {
+        let limits = MsgPackLimits::unlimited()
+            .max_depth(2)
+            .max_collection_len(2);
+        let config = MsgPackConfig::new().limits(limits);
+
+        // `[[1, 2], {}]` and a scalar are within the limits, empty collections count too.
+        assert!(check(&[0x92, 0x92, 1, 2, 0x80], &config).is_ok());
+        assert!(check(&[0x01], &config).is_ok());
+        match check(&[0x91, 0x91, 0x90], &config) {
+            Err(MsgPackRejection::NestingTooDeep(rejection)) => assert_eq!(rejection.limit(), 2),
+            other => unreachable!("Expected nesting too deep rejection, got: {:?}", other),
+        }
+
+        // Checked after the values before them.
+        match check(&[0x92, 0x90, 0x83, 1, 1, 2, 2, 3, 3], &config) {
+            Err(MsgPackRejection::CollectionTooLong(rejection)) => {
+                assert!(rejection.is_map());
+                assert_eq!(rejection.declared_len(), 3);
+            }
+            other => unreachable!("Expected collection too long rejection, got: {:?}", other),
+        }
+
+        // A huge array on a short body is rejected by the limit first.
+        match check(&[0xdd, 0xff, 0xff, 0xff, 0xff, 1], &config) {
+            Err(MsgPackRejection::CollectionTooLong(rejection)) => {
+                assert!(!rejection.is_map());
+                assert_eq!(rejection.declared_len(), u32::MAX as usize);
+            }
+            other => unreachable!("Expected collection too long rejection, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn enforces_map_entry_limit() {
+        let limits = MsgPackLimits::unlimited()
+            .max_collection_len(4)
+            .max_map_entries(2);
+        let config = MsgPackConfig::new().limits(limits);
+
+        // Arrays may be longer than maps.
+        assert!(check(&[0x94, 1, 2, 3, 4], &config).is_ok());
+        assert!(check(&[0x82, 1, 1, 2, 2], &config).is_ok());
+
+        // A map32 header declaring 2^32 - 1 entries, followed by a single entry.
+        match check(&[0xdf, 0xff, 0xff, 0xff, 0xff, 1, 1], &config) {
+            Err(MsgPackRejection::CollectionTooLong(rejection)) => {
+                assert!(rejection.is_map());
+                assert_eq!(rejection.declared_len(), u32::MAX as usize);
+                assert_eq!(rejection.limit(), 2);
+            }
+            other => unreachable!("Expected collection too long rejection, got: {:?}", other),
+        }
+
+        // The smaller of both limits applies to maps.
+        let limits = MsgPackLimits::unlimited()
+            .max_collection_len(1)
+            .max_map_entries(2);
+        match check(&[0x82, 1, 1, 2, 2], &MsgPackConfig::new().limits(limits)) {
+            Err(MsgPackRejection::CollectionTooLong(rejection)) => assert_eq!(rejection.limit(), 1),
+            other => unreachable!("Expected collection too long rejection, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn enforces_body_limit() {
+        let config = MsgPackConfig::new().limits(MsgPackLimits::unlimited().max_body_len(2));
+        assert!(check(&[0x91, 0x01], &config).is_ok());
+        match check(&[0x92, 0x01, 0x02], &config) {
+            Err(MsgPackRejection::MsgPackBodyTooLarge(rejection)) => {
+                assert_eq!(rejection.body_len(), 3);
+                assert_eq!(rejection.limit(), 2);
+            }
+            other => unreachable!("Expected body too large rejection, got: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::{borrow::Cow, collections::HashSet, io::Read};
+
+use serde::de::Error as _;
+
+use crate::{value::Value, MsgPackConfig};
+
+/// What to do with maps that contain the same key more than once.
+///
+/// MessagePack does not forbid duplicate keys, and serde leaves them to the decoded type:
+/// derived structs reject duplicate fields, while `HashMap` and `BTreeMap` silently keep the last
+/// value. Set a policy with [`MsgPackConfig::duplicate_keys`] to handle them the same way for
+/// every type, e.g. for bodies from encoders known to repeat keys.
+///
+/// Every policy except [`DuplicateKeys::Allow`] decodes the body into an untyped value first and
+/// encodes it again before the actual decoding, which roughly doubles the decoding cost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicateKeys {
+    /// Leaves duplicate keys to the decoded type.
+    #[default]
+    Allow,
+    /// Rejects bodies with duplicate keys with
+    /// [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody).
+    Reject,
+    /// Keeps the first entry of each key.
+    KeepFirst,
+    /// Keeps the last entry of each key.
+    KeepLast,
+}
+
+/// Applies the duplicate key policy of `config` to the first value of `bytes`, returning the
+/// bytes unchanged if the policy is [`DuplicateKeys::Allow`].
+///
+/// Bytes after the first value are kept as they are, so trailing bytes are still detected.
+pub(crate) fn dedupe_keys<'a>(
+    bytes: &'a [u8],
+    config: &MsgPackConfig,
+) -> Result<Cow<'a, [u8]>, rmp_serde::decode::Error> {
+    let mut rd = bytes;
+    match dedupe_next(&mut rd, config)? {
+        Some(mut deduped) => {
+            deduped.extend_from_slice(rd);
+            Ok(Cow::Owned(deduped))
+        }
+        None => Ok(Cow::Borrowed(bytes)),
+    }
+}
+
+/// Reads the next value from `rd` and applies the duplicate key policy of `config` to it,
+/// returning its new encoding, or `None` without reading anything if the policy is
+/// [`DuplicateKeys::Allow`].
+pub(crate) fn dedupe_next<R>(
+    rd: R,
+    config: &MsgPackConfig,
+) -> Result<Option<Vec<u8>>, rmp_serde::decode::Error>
+where
+    R: Read,
+{
+    let policy = config.duplicate_key_policy();
+    if policy == DuplicateKeys::Allow {
+        return Ok(None);
+    }
+
+    let mut value: Value = serde::Deserialize::deserialize(&mut rmp_serde::Deserializer::new(rd))?;
+    dedupe(&mut value, policy)?;
+
+    rmp_serde::encode::to_vec(&value)
+        .map(Some)
+        .map_err(|err| rmp_serde::decode::Error::custom(err.to_string()))
+}
+
+fn dedupe(value: &mut Value, policy: DuplicateKeys) -> Result<(), rmp_serde::decode::Error> {
+    match value {
+        Value::Map(entries) => {
+            // Keys are compared by their encoding, which also tells apart e.g. `1` and `1.0`.
+            let keys = entries
+                .iter()
+                .map(|(key, _)| rmp_serde::encode::to_vec(key))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| rmp_serde::decode::Error::custom(err.to_string()))?;
+
+            let mut seen = HashSet::with_capacity(keys.len());
+            let mut keep = vec![true; keys.len()];
+            for index in 0..keys.len() {
+                // To keep the last entry, walk the entries backwards.
+                let index = match policy {
+                    DuplicateKeys::KeepLast => keys.len() - 1 - index,
+                    _ => index,
+                };
+                if !seen.insert(&keys[index]) {
+                    if policy == DuplicateKeys::Reject {
+                        return Err(rmp_serde::decode::Error::custom(format_args!(
+                            "duplicate map key {}",
+                            DisplayKey(&entries[index].0)
+                        )));
+                    }
+                    keep[index] = false;
+                }
+            }
+
+            let mut keep = keep.into_iter();
+            entries.retain(|_| keep.next().unwrap_or(true));
+            for (key, value) in entries {
+                dedupe(key, policy)?;
+                dedupe(value, policy)?;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                dedupe(value, policy)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Names a key in an error, quoting strings.
+struct DisplayKey<'a>(&'a Value);
+
+impl std::fmt::Display for DisplayKey<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Value::Str(key) => write!(f, "`{}`", key),
+            Value::U64(key) => write!(f, "{}", key),
+            Value::I64(key) => write!(f, "{}", key),
+            key => write!(f, "{:?}", key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{de::IgnoredAny, Deserialize};
+
+    use super::DuplicateKeys;
+    use crate::{rejection::MsgPackRejection, MsgPack, MsgPackConfig};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    /// `{"name": "steve", "age": 1, "name": "bob", "age": 2}`
+    fn duplicated() -> Vec<u8> {
+        let mut body = vec![0x84];
+        for (name, age) in [("steve", 1), ("bob", 2)] {
+            rmp::encode::write_str(&mut body, "name").unwrap();
+            rmp::encode::write_str(&mut body, name).unwrap();
+            rmp::encode::write_str(&mut body, "age").unwrap();
+            rmp::encode::write_uint(&mut body, age).unwrap();
+        }
+        body
+    }
+
+    async fn extract<T>(policy: DuplicateKeys) -> Result<T, MsgPackRejection>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut request = Request::new(Body::from(duplicated()));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let config = MsgPackConfig::new().duplicate_keys(policy).strict(true);
+        request.extensions_mut().insert(config);
+        MsgPack::<T>::from_request(request, &())
+            .await
+            .map(|MsgPack(value)| value)
+    }
+
+    #[tokio::test]
+    async fn leaves_duplicates_to_the_type_by_default() {
+        assert!(matches!(
+            extract::<User>(DuplicateKeys::Allow).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+
+        let map = extract::<BTreeMap<String, IgnoredAny>>(DuplicateKeys::Allow).await;
+        assert_eq!(map.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicates() {
+        match extract::<User>(DuplicateKeys::Reject).await {
+            Err(MsgPackRejection::InvalidMsgPackBody(rejection)) => {
+                let source = std::error::Error::source(&rejection).unwrap();
+                assert!(
+                    source.to_string().ends_with("duplicate map key `name`"),
+                    "{}",
+                    source
+                );
+            }
+            other => unreachable!("Expected invalid body rejection, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_first_or_last_entry() {
+        let user = extract::<User>(DuplicateKeys::KeepFirst).await.unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "steve".into(),
+                age: 1
+            }
+        );
+
+        let user = extract::<User>(DuplicateKeys::KeepLast).await.unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "bob".into(),
+                age: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    empty::is_empty, failure::FailureFormat, msgpack_response, ApplicationMsgPack, ContentType,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// A value that can be encoded as MessagePack behind a trait object.
+///
+/// [`Serialize`] cannot be used as a trait object, as its methods are generic over the
+/// serializer. This trait only encodes with the serializer of [`MsgPack`](crate::MsgPack), which
+/// makes it object safe, and is implemented for every type that implements [`Serialize`]. Box
+/// values as `Box<dyn MsgPackSerialize + Send>` to return them in a [`MsgPackDyn`].
+pub trait MsgPackSerialize {
+    /// Encodes the value like a [`MsgPack`](crate::MsgPack) response, with structs as maps of
+    /// field names.
+    fn to_vec_named(&self) -> Result<Vec<u8>, rmp_serde::encode::Error>;
+
+    /// Converts the value into a response like
+    /// [`MsgPack::into_response_with`](crate::MsgPack::into_response_with).
+    fn to_response_with(&self, failure_format: FailureFormat) -> Response;
+}
+
+impl<T> MsgPackSerialize for T
+where
+    T: Serialize,
+{
+    fn to_vec_named(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        rmp_serde::encode::to_vec_named(self)
+    }
+
+    fn to_response_with(&self, failure_format: FailureFormat) -> Response {
+        if is_empty(self) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            self.to_vec_named(),
+            ApplicationMsgPack::CONTENT_TYPE,
+            failure_format,
+        )
+    }
+}
+
+/// MessagePack Response for a value whose type is only known at runtime.
+///
+/// Responds exactly like [`MsgPack`](crate::MsgPack) would for the boxed value, e.g. for
+/// handlers of a plugin system that return values of types they cannot name:
+///
+/// ```no_run
+/// use axum::{extract::Path, routing::get, Router};
+/// use axum_msgpack::MsgPackDyn;
+///
+/// async fn run_plugin(Path(name): Path<String>) -> MsgPackDyn {
+///     match name.as_str() {
+///         "count" => MsgPackDyn::new(42),
+///         _ => MsgPackDyn::new(format!("unknown plugin: {}", name)),
+///     }
+/// }
+///
+/// let app: Router = Router::new().route("/plugins/:name", get(run_plugin));
+/// ```
+pub struct MsgPackDyn(pub Box<dyn MsgPackSerialize + Send>);
+
+impl MsgPackDyn {
+    /// Boxes `value` for a response.
+    pub fn new<T>(value: T) -> Self
+    where
+        T: Serialize + Send + 'static,
+    {
+        Self(Box::new(value))
+    }
+
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        self.0.to_response_with(failure_format)
+    }
+}
+
+impl std::fmt::Debug for MsgPackDyn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MsgPackDyn").finish_non_exhaustive()
+    }
+}
+
+impl IntoResponse for MsgPackDyn {
+    fn into_response(self) -> Response {
+        self.into_response_with(FailureFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use serde::Serialize;
+
+    use super::{MsgPackDyn, MsgPackSerialize};
+
+    #[derive(Clone, Serialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Health;
+
+    /// Values of two different types behind the erased interface, with their direct encoding.
+    fn plugins() -> Vec<(Box<dyn MsgPackSerialize + Send>, Vec<u8>)> {
+        let user = User {
+            name: "steve".into(),
+            age: 42,
+        };
+        let scores = BTreeMap::from([("ann", 3), ("bob", 5)]);
+        vec![
+            (
+                Box::new(user.clone()),
+                rmp_serde::encode::to_vec_named(&user).unwrap(),
+            ),
+            (
+                Box::new(scores.clone()),
+                rmp_serde::encode::to_vec_named(&scores).unwrap(),
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn encodes_like_the_concrete_type() {
+        for (value, expected) in plugins() {
+            assert_eq!(value.to_vec_named().unwrap(), expected);
+
+            let res = MsgPackDyn(value).into_response();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(body, expected);
+        }
+    }
+
+    #[test]
+    fn responds_to_empty_values_with_no_content() {
+        let res = MsgPackDyn::new(Health).into_response();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    }
+}
@@ -0,0 +1,198 @@
+use std::fmt;
+
+use serde::ser::{self, Impossible, Serialize, Serializer};
+
+/// Returns `true` if `value` serializes as a unit or a unit struct, i.e. a value that carries no
+/// content.
+///
+/// Structs are never empty, even if they serialize no fields: a struct whose fields are all
+/// skipped, e.g. with `skip_serializing_if`, is still an empty map the client can decode.
+pub(crate) fn is_empty<T>(value: &T) -> bool
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(EmptyProbe).is_ok()
+}
+
+#[derive(Debug)]
+struct NotEmpty;
+
+impl fmt::Display for NotEmpty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value is not empty")
+    }
+}
+
+impl std::error::Error for NotEmpty {}
+
+impl ser::Error for NotEmpty {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        NotEmpty
+    }
+}
+
+struct EmptyProbe;
+
+impl Serializer for EmptyProbe {
+    type Ok = ();
+    type Error = NotEmpty;
+    type SerializeSeq = Impossible<(), NotEmpty>;
+    type SerializeTuple = Impossible<(), NotEmpty>;
+    type SerializeTupleStruct = Impossible<(), NotEmpty>;
+    type SerializeTupleVariant = Impossible<(), NotEmpty>;
+    type SerializeMap = Impossible<(), NotEmpty>;
+    type SerializeStruct = Impossible<(), NotEmpty>;
+    type SerializeStructVariant = Impossible<(), NotEmpty>;
+
+    fn serialize_unit(self) -> Result<(), NotEmpty> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), NotEmpty> {
+        Ok(())
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_none(self) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<(), NotEmpty>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(NotEmpty)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<(), NotEmpty>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(NotEmpty)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), NotEmpty>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(NotEmpty)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NotEmpty> {
+        Err(NotEmpty)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NotEmpty> {
+        Err(NotEmpty)
+    }
+}
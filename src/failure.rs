@@ -0,0 +1,132 @@
+use std::fmt::Write;
+
+use axum::{
+    body::Body,
+    http::{header, StatusCode},
+    response::Response,
+};
+use serde::Serialize;
+
+/// Format of the `500 Internal Server Error` response sent when a value cannot be serialized.
+///
+/// The default is [`FailureFormat::Text`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FailureFormat {
+    /// The error message as `text/plain`.
+    #[default]
+    Text,
+    /// A `{ "error": message }` map encoded as `application/msgpack`.
+    MsgPack,
+    /// A `{ "error": message }` object encoded as `application/json`.
+    Json,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+pub(crate) fn failure_response(format: FailureFormat, message: &str) -> Response {
+    let (content_type, body) = match format {
+        FailureFormat::Text => ("text/plain", Body::from(message.to_owned())),
+        FailureFormat::MsgPack => {
+            match rmp_serde::encode::to_vec_named(&ErrorBody { error: message }) {
+                Ok(bytes) => ("application/msgpack", Body::from(bytes)),
+                Err(_) => ("text/plain", Body::from(message.to_owned())),
+            }
+        }
+        FailureFormat::Json => ("application/json", Body::from(json_error_body(message))),
+    };
+
+    Response::builder()
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body)
+        .unwrap()
+}
+
+fn json_error_body(message: &str) -> String {
+    let mut out = String::with_capacity(message.len() + 12);
+    out.push_str("{\"error\":\"");
+    for c in message.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push_str("\"}");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use serde::{ser::Error, Deserialize, Serialize, Serializer};
+
+    use super::{json_error_body, FailureFormat};
+    use crate::{MsgPack, MsgPackRaw};
+
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(S::Error::custom("boom \"quoted\""))
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct ErrorBody {
+        error: String,
+    }
+
+    #[tokio::test]
+    async fn text_is_the_default() {
+        let res = MsgPack(Unserializable).into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "text/plain");
+
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"boom \"quoted\"");
+    }
+
+    #[tokio::test]
+    async fn msgpack_failure_body() {
+        let res = MsgPackRaw(Unserializable).into_response_with(FailureFormat::MsgPack);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: ErrorBody = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(body.error, "boom \"quoted\"");
+    }
+
+    #[tokio::test]
+    async fn json_failure_body() {
+        let res = MsgPack(Unserializable).into_response_with(FailureFormat::Json);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/json");
+
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], br#"{"error":"boom \"quoted\""}"#);
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(
+            json_error_body("a\\b\n\u{1}"),
+            r#"{"error":"a\\b\n\u0001"}"#
+        );
+    }
+}
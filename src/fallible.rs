@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use crate::{
+    failure::{failure_response, FailureFormat},
+    MsgPack,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// MessagePack Response that hands serialization errors on to the layers around the handler.
+///
+/// Serializes like [`MsgPack`], and also responds with `500 Internal Server Error` if the value
+/// cannot be serialized, but stores the error in the response extensions as a
+/// [`MsgPackEncodeError`]. A layer such as [`map_response`](axum::middleware::map_response)
+/// can then handle encode errors of all handlers in one place:
+///
+/// ```no_run
+/// use axum::{
+///     http::StatusCode,
+///     middleware,
+///     response::{IntoResponse, Response},
+///     routing::get,
+///     Router,
+/// };
+/// use axum_msgpack::{FallibleMsgPack, MsgPackEncodeError};
+///
+/// async fn get_user() -> FallibleMsgPack<String> {
+///     FallibleMsgPack("steve".to_owned())
+/// }
+///
+/// async fn report_encode_errors(res: Response) -> Response {
+///     match res.extensions().get::<MsgPackEncodeError>() {
+///         Some(err) => {
+///             let message = format!("could not encode the response: {}", err);
+///             (StatusCode::INTERNAL_SERVER_ERROR, message).into_response()
+///         }
+///         None => res,
+///     }
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/user", get(get_user))
+///     .layer(middleware::map_response(report_encode_errors));
+/// ```
+///
+/// Handlers that want to handle the error themselves can use [`MsgPack::try_into_response`]
+/// instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FallibleMsgPack<T>(pub T);
+
+impl<T> IntoResponse for FallibleMsgPack<T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        match MsgPack(self.0).try_into_response() {
+            Ok(res) => res,
+            Err(err) => {
+                let mut res = failure_response(FailureFormat::default(), &err.to_string());
+                res.extensions_mut().insert(MsgPackEncodeError::from(err));
+                res
+            }
+        }
+    }
+}
+
+/// The error a [`FallibleMsgPack`] response failed to serialize its value with, stored in the
+/// response extensions.
+///
+/// Also a variant of [`MsgPackRejection`](crate::rejection::MsgPackRejection), so handlers
+/// returning a `Result<_, MsgPackRejection>` can propagate encode errors with `?`, which respond
+/// with `500 Internal Server Error` like a failing [`MsgPack`] response.
+#[derive(Debug, Clone)]
+pub struct MsgPackEncodeError(Arc<rmp_serde::encode::Error>);
+
+impl MsgPackEncodeError {
+    /// The error returned by `rmp-serde`.
+    pub fn error(&self) -> &rmp_serde::encode::Error {
+        &self.0
+    }
+
+    /// The status code of the response sent for this error.
+    pub fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    /// The body of the response sent for this error.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<rmp_serde::encode::Error> for MsgPackEncodeError {
+    fn from(err: rmp_serde::encode::Error) -> Self {
+        Self(Arc::new(err))
+    }
+}
+
+impl IntoResponse for MsgPackEncodeError {
+    fn into_response(self) -> Response {
+        failure_response(FailureFormat::default(), &self.body_text())
+    }
+}
+
+impl std::fmt::Display for MsgPackEncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to serialize the response as MsgPack: {}", self.0)
+    }
+}
+
+impl std::error::Error for MsgPackEncodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Request, StatusCode},
+        middleware,
+        response::{IntoResponse, Response},
+        routing::get,
+        Router,
+    };
+    use serde::{Serialize, Serializer};
+    use tower::ServiceExt;
+
+    use super::{FallibleMsgPack, MsgPackEncodeError};
+    use crate::MsgPack;
+
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("nope"))
+        }
+    }
+
+    #[test]
+    fn returns_encode_errors() {
+        let res = MsgPack("steve").try_into_response().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        let res = MsgPack(()).try_into_response().unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let err = MsgPack(Failing).try_into_response().unwrap_err();
+        assert_eq!(err.to_string(), "nope");
+    }
+
+    #[tokio::test]
+    async fn hands_encode_errors_to_layers() {
+        async fn rewrite(res: Response) -> Response {
+            match res.extensions().get::<MsgPackEncodeError>() {
+                Some(err) => (StatusCode::BAD_GATEWAY, err.error().to_string()).into_response(),
+                None => res,
+            }
+        }
+
+        let app: Router = Router::new()
+            .route("/ok", get(|| async { FallibleMsgPack("steve") }))
+            .route("/failing", get(|| async { FallibleMsgPack(Failing) }))
+            .layer(middleware::map_response(rewrite));
+        let send = |uri| {
+            app.clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        };
+
+        let res = send("/ok").await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        let res = send("/failing").await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_GATEWAY);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "nope");
+    }
+
+    #[test]
+    fn responds_with_internal_server_error_without_layer() {
+        let res = FallibleMsgPack(Failing).into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let err = res.extensions().get::<MsgPackEncodeError>().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Failed to serialize the response as MsgPack: nope"
+        );
+    }
+}
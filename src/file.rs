@@ -0,0 +1,167 @@
+use std::{
+    io,
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{ApplicationMsgPack, ContentType};
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures_core::Stream;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, ReadBuf},
+};
+
+/// Size of the chunks the file is read in.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// MessagePack response streamed from a file.
+///
+/// Serves a file holding MessagePack, e.g. a large precomputed value, with
+/// `Content-Type: application/msgpack` and the size of the file as `Content-Length`. The file
+/// is read in chunks while the response is sent, so it is never held in memory as a whole. The
+/// contents are sent as they are, without checking that they are valid MessagePack.
+///
+/// ```no_run
+/// use axum::{routing::get, Router};
+/// use axum_msgpack::MsgPackFile;
+///
+/// async fn get_catalog() -> MsgPackFile {
+///     MsgPackFile::open("catalog.msgpack").await
+/// }
+///
+/// let app: Router = Router::new().route("/catalog", get(get_catalog));
+/// ```
+///
+/// If the file cannot be opened, the response is `404 Not Found` if it does not exist and
+/// `500 Internal Server Error` otherwise, with a plain text body. A read error after the
+/// response has started ends the response body with that error.
+#[derive(Debug)]
+pub struct MsgPackFile {
+    file: io::Result<(File, u64)>,
+}
+
+impl MsgPackFile {
+    /// Opens the file at `path` for the response.
+    pub async fn open(path: impl AsRef<Path>) -> Self {
+        let file = match File::open(path).await {
+            Ok(file) => Self::from_file(file).await,
+            Err(err) => Err(err),
+        };
+        Self { file }
+    }
+
+    async fn from_file(file: File) -> io::Result<(File, u64)> {
+        let metadata = file.metadata().await?;
+        if !metadata.is_file() {
+            return Err(io::Error::other("not a regular file"));
+        }
+        Ok((file, metadata.len()))
+    }
+}
+
+impl IntoResponse for MsgPackFile {
+    fn into_response(self) -> Response {
+        let (file, len) = match self.file {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return (StatusCode::NOT_FOUND, "MsgPack file not found").into_response();
+            }
+            Err(err) => {
+                let message = format!("Failed to open MsgPack file: {}", err);
+                return (StatusCode::INTERNAL_SERVER_ERROR, message).into_response();
+            }
+        };
+
+        let mut res = Body::from_stream(FileStream {
+            file,
+            buf: vec![0; CHUNK_SIZE].into_boxed_slice(),
+        })
+        .into_response();
+        res.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(ApplicationMsgPack::CONTENT_TYPE),
+        );
+        res.headers_mut()
+            .insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+        res
+    }
+}
+
+/// Reads a file in chunks of [`CHUNK_SIZE`].
+struct FileStream {
+    file: File,
+    buf: Box<[u8]>,
+}
+
+impl Stream for FileStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut buf = ReadBuf::new(&mut this.buf);
+        match Pin::new(&mut this.file).poll_read(cx, &mut buf) {
+            Poll::Ready(Ok(())) if buf.filled().is_empty() => Poll::Ready(None),
+            Poll::Ready(Ok(())) => Poll::Ready(Some(Ok(Bytes::copy_from_slice(buf.filled())))),
+            Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+
+    use super::{MsgPackFile, CHUNK_SIZE};
+
+    #[tokio::test]
+    async fn streams_file_contents() {
+        // A few chunks of strings, so the file is read more than once.
+        let value: Vec<String> = (0..3 * CHUNK_SIZE / 100)
+            .map(|i| format!("{:0100}", i))
+            .collect();
+        let bytes = rmp_serde::encode::to_vec(&value).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("axum-msgpack-file-{}.msgpack", std::process::id()));
+        tokio::fs::write(&path, &bytes).await.unwrap();
+
+        let res = MsgPackFile::open(&path).await.into_response();
+        tokio::fs::remove_file(&path).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        assert_eq!(
+            res.headers()[header::CONTENT_LENGTH],
+            bytes.len().to_string()
+        );
+
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, bytes);
+        assert_eq!(rmp_serde::from_slice::<Vec<String>>(&body).unwrap(), value);
+    }
+
+    #[tokio::test]
+    async fn responds_to_missing_files_with_not_found() {
+        let path = std::env::temp_dir().join("axum-msgpack-file-missing.msgpack");
+        let res = MsgPackFile::open(path).await.into_response();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+
+        let res = MsgPackFile::open(std::env::temp_dir())
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
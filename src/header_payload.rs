@@ -0,0 +1,238 @@
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    decode,
+    rejection::{InvalidBase64Header, MissingMsgPackHeader, MsgPackRejection},
+    MsgPackConfig,
+};
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde::de::DeserializeOwned;
+
+/// The header a [`MsgPackHeader`] reads, chosen at compile time.
+///
+/// ```
+/// use axum_msgpack::MsgPackHeaderName;
+///
+/// /// Marker for the `X-Webhook-Payload` header.
+/// struct WebhookPayload;
+///
+/// impl MsgPackHeaderName for WebhookPayload {
+///     const NAME: &'static str = "x-webhook-payload";
+/// }
+/// ```
+pub trait MsgPackHeaderName {
+    /// Name of the header, matched case-insensitively.
+    const NAME: &'static str;
+}
+
+/// MessagePack Extractor for a base64-encoded payload in a request header.
+///
+/// For integrations that send MessagePack in a header instead of the body, e.g. webhooks that
+/// attach a payload to an otherwise unrelated request. Reads the header named by `N`, decodes
+/// it from base64, in the standard or the URL-safe alphabet with or without padding, and decodes
+/// the bytes like [`MsgPack`](crate::MsgPack) decodes a body, with the [`MsgPackConfig`] of the
+/// request. The body is left alone, so it can be extracted as well.
+///
+/// Each stage has its own rejection: a missing header, or one that is not a string, is rejected
+/// with [`MissingMsgPackHeader`], a header that is not valid base64 with
+/// [`InvalidBase64Header`], and a payload that cannot be decoded with the same rejections as a
+/// body, e.g. [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody).
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::{MsgPackHeader, MsgPackHeaderName};
+/// use serde::Deserialize;
+///
+/// struct WebhookPayload;
+///
+/// impl MsgPackHeaderName for WebhookPayload {
+///     const NAME: &'static str = "x-webhook-payload";
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     kind: String,
+/// }
+///
+/// async fn webhook(event: MsgPackHeader<Event, WebhookPayload>) {
+///     let event = event.into_inner();
+/// }
+///
+/// let app: Router = Router::new().route("/webhook", post(webhook));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackHeader<T, N>(pub T, PhantomData<N>);
+
+impl<T, N> MsgPackHeader<T, N> {
+    /// Returns the decoded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, N> Deref for MsgPackHeader<T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, N> DerefMut for MsgPackHeader<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[async_trait]
+impl<T, N, S> FromRequestParts<S> for MsgPackHeader<T, N>
+where
+    T: DeserializeOwned,
+    N: MsgPackHeaderName,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(N::NAME)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| MissingMsgPackHeader::new(N::NAME))?;
+        let bytes =
+            decode_base64(header.trim()).ok_or_else(|| InvalidBase64Header::new(N::NAME))?;
+        let value = decode(&bytes, &MsgPackConfig::of_extensions(&parts.extensions))?;
+        Ok(Self(value, PhantomData))
+    }
+}
+
+/// Decodes base64 in the standard or the URL-safe alphabet, with or without padding.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.as_bytes();
+    let unpadded = match input {
+        [rest @ .., b'=', b'='] | [rest @ .., b'='] if input.len().is_multiple_of(4) => rest,
+        _ => input,
+    };
+    // A single character left over carries less than a byte.
+    if unpadded.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        let mut bits: u32 = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            bits |= u32::from(sextet(c)?) << (18 - 6 * i);
+        }
+        let [_, a, b, c] = bits.to_be_bytes();
+        bytes.extend_from_slice(&[a, b, c][..chunk.len() - 1]);
+    }
+    Some(bytes)
+}
+
+fn sextet(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::FromRequestParts, http::Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::{decode_base64, MsgPackHeader, MsgPackHeaderName};
+    use crate::rejection::MsgPackRejection;
+
+    struct Payload;
+
+    impl MsgPackHeaderName for Payload {
+        const NAME: &'static str = "x-payload";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        kind: String,
+        id: u32,
+    }
+
+    async fn extract(header: Option<&str>) -> Result<Event, MsgPackRejection> {
+        let mut request = Request::builder();
+        if let Some(header) = header {
+            request = request.header("X-Payload", header);
+        }
+        let (mut parts, ()) = request.body(()).unwrap().into_parts();
+        MsgPackHeader::<Event, Payload>::from_request_parts(&mut parts, &())
+            .await
+            .map(MsgPackHeader::into_inner)
+    }
+
+    #[test]
+    fn decodes_base64() {
+        for (encoded, decoded) in [
+            ("", &b""[..]),
+            ("Zg==", b"f"),
+            ("Zg", b"f"),
+            ("Zm8=", b"fo"),
+            ("Zm9v", b"foo"),
+            ("Zm9vYmFy", b"foobar"),
+            ("+/8=", &[0xfb, 0xff]),
+            ("-_8", &[0xfb, 0xff]),
+        ] {
+            assert_eq!(
+                decode_base64(encoded).as_deref(),
+                Some(decoded),
+                "{}",
+                encoded
+            );
+        }
+
+        for invalid in ["Z", "Zm9vY", "Zm 9v", "Zg=", "=Zg=", "Zm9v!"] {
+            assert_eq!(decode_base64(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[tokio::test]
+    async fn extracts_payload_from_header() {
+        // `{"kind": "push", "id": 7}`
+        let event = extract(Some("gqRraW5kpHB1c2iiaWQH")).await.unwrap();
+        assert_eq!(
+            event,
+            Event {
+                kind: "push".into(),
+                id: 7
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_each_stage() {
+        match extract(None).await {
+            Err(MsgPackRejection::MissingMsgPackHeader(rejection)) => {
+                assert_eq!(rejection.name(), "x-payload");
+            }
+            other => unreachable!("Expected missing header rejection, got: {:?}", other),
+        }
+
+        match extract(Some("not base64!")).await {
+            Err(MsgPackRejection::InvalidBase64Header(rejection)) => {
+                assert_eq!(rejection.name(), "x-payload");
+            }
+            other => unreachable!("Expected invalid base64 rejection, got: {:?}", other),
+        }
+
+        // `"push"`, a valid value of the wrong type.
+        assert!(matches!(
+            extract(Some("pHB1c2g=")).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}
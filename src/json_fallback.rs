@@ -0,0 +1,299 @@
+use crate::{parse_message_pack_mime, transcode::FormatQualities, vary::append_vary_accept};
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Size up to which responses are transcoded, the default body limit of axum.
+const MAX_TRANSCODED_LEN: u64 = 2 * 1024 * 1024;
+
+/// Middleware that sends MessagePack responses as JSON to clients that only accept JSON.
+///
+/// Clients that cannot parse MessagePack are better served with JSON, so a handler responding
+/// with [`MsgPack`](crate::MsgPack) can serve both kinds of clients. Add it with
+/// [`from_fn`](axum::middleware::from_fn):
+///
+/// ```no_run
+/// use axum::{middleware, routing::get, Router};
+/// use axum_msgpack::{json_fallback, MsgPack};
+///
+/// async fn get_user() -> MsgPack<String> {
+///     MsgPack("steve".to_owned())
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/user", get(get_user))
+///     .layer(middleware::from_fn(json_fallback));
+/// ```
+///
+/// A MessagePack response is transcoded if the `Accept` header of the request gives
+/// `application/json` or an `application/*+json` type a higher quality than MessagePack. Each
+/// format gets the quality of the most specific media range matching it, so
+/// `application/json, application/msgpack;q=0.5` picks JSON, while
+/// `application/json;q=0.9, application/msgpack` and `application/json, */*` keep MessagePack.
+/// Ties, malformed qualities such as `q=high`, and requests without an `Accept` header get
+/// MessagePack as before. Responses that
+/// cannot be represented as JSON, e.g. because they contain binary data or non-string map keys,
+/// or because they hold more than one value, are replaced with `406 Not Acceptable`.
+/// MessagePack responses get a `Vary: Accept` header.
+///
+/// The response is buffered to transcode it, so only responses of a known length of up to
+/// 2 MiB are transcoded. Streaming responses such as
+/// [`MsgPackStreamResponse`](crate::MsgPackStreamResponse), and larger responses, are passed on
+/// as MessagePack.
+pub async fn json_fallback(req: Request, next: Next) -> Response {
+    let wants_json = prefers_json(req.headers());
+    let mut res = next.run(req).await;
+
+    let is_message_pack = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| parse_message_pack_mime(content_type).is_some());
+    if !is_message_pack {
+        return res;
+    }
+
+    append_vary_accept(res.headers_mut());
+    if !wants_json || !is_transcodable_len(&res) {
+        return res;
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let json = match to_bytes(body, MAX_TRANSCODED_LEN as usize).await {
+        Ok(bytes) => to_json(&bytes),
+        Err(err) => Err(err.to_string()),
+    };
+    let json = match json {
+        Ok(json) => json,
+        Err(err) => {
+            let message = format!("Response cannot be represented as JSON: {}", err);
+            let mut res = (StatusCode::NOT_ACCEPTABLE, message).into_response();
+            append_vary_accept(res.headers_mut());
+            return res;
+        }
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(parts, Body::from(json))
+}
+
+/// Returns `true` if the length of the body is known and at most [`MAX_TRANSCODED_LEN`].
+fn is_transcodable_len(res: &Response) -> bool {
+    let len = res
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok()?.parse().ok())
+        .or_else(|| res.body().size_hint().exact());
+    len.is_some_and(|len| len <= MAX_TRANSCODED_LEN)
+}
+
+/// Transcodes a body holding a single MessagePack value.
+fn to_json(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut rd = bytes;
+    let value: serde_json::Value =
+        serde::Deserialize::deserialize(&mut rmp_serde::Deserializer::new(&mut rd))
+            .map_err(|err| err.to_string())?;
+    if !rd.is_empty() {
+        return Err(format!("{} bytes after the first value", rd.len()));
+    }
+    serde_json::to_vec(&value).map_err(|err| err.to_string())
+}
+
+/// Returns `true` if the `Accept` header prefers JSON to MessagePack.
+fn prefers_json(headers: &HeaderMap) -> bool {
+    !FormatQualities::from_headers(headers).prefers_message_pack()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Request, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use serde::Serialize;
+    use tower::ServiceExt;
+
+    use super::json_fallback;
+    use crate::{MsgPack, MsgPackStreamResponse};
+
+    #[derive(Serialize)]
+    struct User {
+        name: String,
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/user",
+                get(|| async {
+                    MsgPack(User {
+                        name: "steve".into(),
+                    })
+                }),
+            )
+            .route("/list", get(|| async { MsgPack(vec![1, 2, 3]) }))
+            .route(
+                "/private",
+                get(|| async { ([(header::VARY, "Cookie")], MsgPack(vec![1, 2, 3])) }),
+            )
+            .route(
+                "/numbered",
+                get(|| async { MsgPack(BTreeMap::from([(1, "one")])) }),
+            )
+            .route(
+                "/stream",
+                get(|| async {
+                    MsgPackStreamResponse(futures_util::stream::iter(
+                        (0..3).map(Ok::<_, std::io::Error>),
+                    ))
+                }),
+            )
+            .route(
+                "/concatenated",
+                get(|| async {
+                    (
+                        [(header::CONTENT_TYPE, "application/msgpack")],
+                        vec![0x00, 0x01, 0x02],
+                    )
+                }),
+            )
+            .route(
+                "/large",
+                get(|| async { MsgPack(vec![0u8; 3 * 1024 * 1024]) }),
+            )
+            .layer(middleware::from_fn(json_fallback))
+    }
+
+    async fn send(uri: &str, accept: Option<&'static str>) -> (StatusCode, String, Vec<u8>) {
+        let mut request = Request::get(uri);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        let res = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let content_type = res
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|content_type| content_type.to_str().unwrap().to_owned())
+            .unwrap_or_default();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn keeps_message_pack_for_accepting_clients() {
+        let msgpack = rmp_serde::encode::to_vec_named(&User {
+            name: "steve".into(),
+        })
+        .unwrap();
+
+        for accept in [
+            None,
+            Some("application/msgpack"),
+            Some("application/json;q=0.9, application/msgpack;q=1.0"),
+            Some("application/json, application/x-msgpack"),
+            Some("application/json, */*"),
+            Some("application/json;q=0.5, application/*;q=0.8"),
+            Some("application/json;q=high, application/msgpack;q=0.5"),
+            Some("application/json;q=1.5, application/msgpack;q=0.5"),
+        ] {
+            let (status, content_type, body) = send("/user", accept).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type, "application/msgpack", "{:?}", accept);
+            assert_eq!(body, msgpack);
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_json_to_json_only_clients() {
+        for accept in [
+            "application/json",
+            "text/html, application/json;q=0.9",
+            "application/json, application/msgpack;q=0",
+            "application/json, application/x-msgpack;q=0.5",
+            "application/json, */*;q=0.1",
+            "application/msgpack;q=0.2, application/json;q=0.3, */*",
+        ] {
+            let (status, content_type, body) = send("/user", Some(accept)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type, "application/json", "{}", accept);
+            assert_eq!(body, br#"{"name":"steve"}"#);
+        }
+
+        let (status, content_type, body) = send("/list", Some("application/json")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body, b"[1,2,3]");
+    }
+
+    #[tokio::test]
+    async fn rejects_json_only_clients_if_not_representable() {
+        let (status, _, _) = send("/numbered", Some("application/json")).await;
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+
+        let (status, content_type, _) = send("/numbered", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/msgpack");
+    }
+
+    #[tokio::test]
+    async fn adds_vary_accept_to_negotiated_responses() {
+        for accept in ["application/json", "application/msgpack"] {
+            let request = Request::get("/user")
+                .header(header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap();
+            let res = app().oneshot(request).await.unwrap();
+            assert_eq!(res.headers()[header::VARY], "accept", "{}", accept);
+        }
+
+        for (uri, accept, expected) in [
+            ("/private", "application/json", "Cookie, accept"),
+            ("/private", "application/msgpack", "Cookie, accept"),
+            ("/numbered", "application/json", "accept"),
+        ] {
+            let request = Request::get(uri)
+                .header(header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap();
+            let res = app().oneshot(request).await.unwrap();
+            let vary: Vec<_> = res.headers().get_all(header::VARY).iter().collect();
+            assert_eq!(vary, [expected], "{} {}", uri, accept);
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_on_responses_it_cannot_buffer() {
+        let (status, content_type, body) = send("/stream", Some("application/json")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/msgpack");
+        assert_eq!(body, [0x00, 0x01, 0x02]);
+
+        let (status, content_type, body) = send("/large", Some("application/json")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/msgpack");
+        assert_eq!(body.len(), 3 * 1024 * 1024 + 5);
+    }
+
+    #[tokio::test]
+    async fn rejects_trailing_values() {
+        let (status, _, _) = send("/concatenated", Some("application/json")).await;
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+    }
+}
@@ -0,0 +1,560 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+};
+use serde_json::{Map, Number};
+
+use crate::{
+    body_len::read_body,
+    check_content_type, decode,
+    rejection::{InvalidJsonBody, InvalidMsgPackBody, MissingMsgPackContentType, MsgPackRejection},
+    trace,
+    value::Value,
+    MsgPackConfig,
+};
+
+/// MessagePack extractor that converts the body into a [`serde_json::Value`].
+///
+/// Handy for logging and other tooling that works on any body, without a type to decode into.
+/// The body is checked and decoded like [`MsgPack`](crate::MsgPack), with the same content
+/// type check, limits and [`MsgPackConfig`], and malformed bodies are rejected with
+/// [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody). The conversion is the one of
+/// [`decode_json_value`], so it is lossy for types JSON lacks: binary data becomes an array of
+/// integers, extension types become `{"ext": <type>, "data": [<bytes>]}` objects, non-string
+/// map keys become their JSON text, and NaN and infinities become `null`.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackToJsonValue;
+///
+/// async fn log_event(MsgPackToJsonValue(event): MsgPackToJsonValue) {
+///     println!("received {}", event);
+/// }
+///
+/// let app: Router = Router::new().route("/events", post(log_event));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MsgPackToJsonValue(pub serde_json::Value);
+
+#[async_trait]
+impl<S> FromRequest<S> for MsgPackToJsonValue
+where
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of(&req);
+        let found = check_content_type(&req, &config)?;
+        let bytes = read_body(req, state).await?;
+        let value = decode::<Value>(&bytes, &config).map_err(|err| {
+            if found.is_match() {
+                err
+            } else {
+                MissingMsgPackContentType::new(found).into()
+            }
+        })?;
+        Ok(MsgPackToJsonValue(to_json(value)))
+    }
+}
+
+/// Decodes a MessagePack value from `bytes` into a [`serde_json::Value`], e.g. to hand a
+/// request body to tooling that only understands JSON.
+///
+/// Decoding straight into `serde_json::Value` with `rmp-serde` fails for binary data and
+/// extension types, which JSON has no counterpart for. This function maps every MessagePack
+/// type instead:
+///
+/// | MessagePack              | JSON                                       |
+/// |--------------------------|--------------------------------------------|
+/// | nil                      | `null`                                     |
+/// | boolean, integer, string | the same                                   |
+/// | float                    | a number, or `null` for NaN and infinities |
+/// | bin                      | an array of the bytes, e.g. `[0, 255]`     |
+/// | array                    | an array                                   |
+/// | map                      | an object, see below                       |
+/// | ext                      | `{"ext": <type>, "data": [<bytes>]}`       |
+///
+/// Objects only have string keys, so other map keys are replaced with their JSON text, e.g.
+/// the integer key `1` becomes `"1"`. If keys collide, the last entry wins. Bytes after the
+/// first value are ignored.
+///
+/// Extension types survive a round trip through [`encode_json_value`]. Binary data comes back
+/// as an array of integers and non-string keys as strings, which JSON cannot tell apart from
+/// the originals.
+///
+/// ```
+/// use serde_json::json;
+///
+/// // `{"name": "steve", 1: <bin 0x00ff>}`
+/// let mut bytes = Vec::new();
+/// rmp::encode::write_map_len(&mut bytes, 2).unwrap();
+/// rmp::encode::write_str(&mut bytes, "name").unwrap();
+/// rmp::encode::write_str(&mut bytes, "steve").unwrap();
+/// rmp::encode::write_uint(&mut bytes, 1).unwrap();
+/// rmp::encode::write_bin(&mut bytes, &[0x00, 0xff]).unwrap();
+///
+/// let value = axum_msgpack::decode_json_value(&bytes).unwrap();
+/// assert_eq!(value, json!({"name": "steve", "1": [0, 255]}));
+/// ```
+pub fn decode_json_value(bytes: &[u8]) -> Result<serde_json::Value, rmp_serde::decode::Error> {
+    trace::decoding::<serde_json::Value>(bytes.len());
+    let value = rmp_serde::from_slice::<Value>(bytes)
+        .inspect_err(trace::decode_failed::<serde_json::Value, _>)?;
+    Ok(to_json(value))
+}
+
+/// Encodes a [`serde_json::Value`] as MessagePack.
+///
+/// Every value maps to its obvious counterpart, with integers encoded as integers, other numbers
+/// as 64-bit floats and objects as maps with string keys. Use [`encode_json_value_with_ext`] to
+/// restore the extension types decoded by [`decode_json_value`].
+///
+/// ```
+/// use serde_json::json;
+///
+/// let value = json!({"name": "steve", "tags": ["admin"]});
+/// let bytes = axum_msgpack::encode_json_value(&value).unwrap();
+/// assert_eq!(axum_msgpack::decode_json_value(&bytes).unwrap(), value);
+/// ```
+pub fn encode_json_value(value: &serde_json::Value) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::encode::to_vec(&from_json(value, false))
+}
+
+/// Encodes a [`serde_json::Value`] as MessagePack, the inverse of [`decode_json_value`].
+///
+/// Like [`encode_json_value`], except that objects of the form
+/// `{"ext": <type>, "data": [<bytes>]}`, with a type from -128 to 127 and bytes from 0 to 255,
+/// are encoded as extension types, so extension types decoded by `decode_json_value` are
+/// restored. Only use it for values that came from `decode_json_value`, as any other object of
+/// that form, e.g. from a client, becomes an extension type too.
+///
+/// ```
+/// use serde_json::json;
+///
+/// let value = json!({"name": "steve", "timestamp": {"ext": -1, "data": [0, 0, 0, 1]}});
+/// let bytes = axum_msgpack::encode_json_value_with_ext(&value).unwrap();
+/// assert_eq!(axum_msgpack::decode_json_value(&bytes).unwrap(), value);
+/// ```
+pub fn encode_json_value_with_ext(
+    value: &serde_json::Value,
+) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::encode::to_vec(&from_json(value, true))
+}
+
+/// Transcodes a JSON body into MessagePack, e.g. for a gateway that accepts JSON and forwards
+/// MessagePack, without a type to decode into.
+///
+/// The body goes through a [`serde_json::Value`] and is encoded like [`encode_json_value`], so
+/// every object becomes a map, even one of the form `decode_json_value` gives extension types,
+/// as a client may send any object. Bodies that are not valid JSON are rejected with
+/// [`InvalidJsonBody`](crate::rejection::InvalidJsonBody). Only the body is transcoded, so check
+/// the `Content-Type` of the request before, as axum's [`Json`](axum::Json) does:
+///
+/// ```no_run
+/// use axum::{
+///     body::Bytes,
+///     http::{header, HeaderMap, StatusCode},
+///     routing::post,
+///     Router,
+/// };
+/// use axum_msgpack::{rejection::MsgPackRejection, transcode_json_to_msgpack};
+///
+/// async fn forward(headers: HeaderMap, body: Bytes) -> Result<StatusCode, MsgPackRejection> {
+///     let is_json = headers
+///         .get(header::CONTENT_TYPE)
+///         .is_some_and(|content_type| content_type == "application/json");
+///     if !is_json {
+///         return Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+///     }
+///     let message_pack = transcode_json_to_msgpack(body)?;
+///     // forward `message_pack` to the internal service
+///     Ok(StatusCode::ACCEPTED)
+/// }
+///
+/// let app: Router = Router::new().route("/events", post(forward));
+/// ```
+pub fn transcode_json_to_msgpack(body: Bytes) -> Result<Bytes, MsgPackRejection> {
+    let value =
+        serde_json::from_slice::<serde_json::Value>(&body).map_err(InvalidJsonBody::from_err)?;
+    let bytes = encode_json_value(&value).map_err(InvalidJsonBody::from_err)?;
+    Ok(Bytes::from(bytes))
+}
+
+/// Transcodes a MessagePack body into JSON, the inverse of [`transcode_json_to_msgpack`].
+///
+/// The body is checked and decoded like [`MsgPack`](crate::MsgPack) does with the default
+/// [`MsgPackConfig`], and converted like [`decode_json_value`], so malformed bodies are
+/// rejected with [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody) and the same
+/// rejections as the extractors for bodies declaring more data than they hold. Use
+/// [`MsgPackToJsonValue`] to transcode request bodies with the content type checked as well.
+pub fn transcode_msgpack_to_json(body: Bytes) -> Result<Bytes, MsgPackRejection> {
+    let value = decode::<Value>(&body, &MsgPackConfig::default())?;
+    let json = serde_json::to_vec(&to_json(value)).map_err(InvalidMsgPackBody::from_err)?;
+    Ok(Bytes::from(json))
+}
+
+fn to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Nil => serde_json::Value::Null,
+        Value::Bool(v) => serde_json::Value::Bool(v),
+        Value::U64(v) => v.into(),
+        Value::I64(v) => v.into(),
+        // Going through the shortest text of the `f32` keeps e.g. `0.1` from becoming
+        // `0.10000000149011612`.
+        Value::F32(v) => float(v.to_string().parse().unwrap_or(f64::NAN)),
+        Value::F64(v) => float(v),
+        Value::Str(v) => serde_json::Value::String(v),
+        Value::Bin(v) => bytes(v),
+        Value::Array(values) => values.into_iter().map(to_json).collect(),
+        Value::Map(entries) => {
+            let mut object = Map::with_capacity(entries.len());
+            for (key, value) in entries {
+                let key = match to_json(key) {
+                    serde_json::Value::String(key) => key,
+                    key => key.to_string(),
+                };
+                object.insert(key, to_json(value));
+            }
+            serde_json::Value::Object(object)
+        }
+        Value::Ext(tag, data) => {
+            let mut object = Map::with_capacity(2);
+            object.insert("ext".to_owned(), tag.into());
+            object.insert("data".to_owned(), bytes(data));
+            serde_json::Value::Object(object)
+        }
+    }
+}
+
+/// Converts `value`, restoring extension types from their objects if `ext` is set.
+fn from_json(value: &serde_json::Value, ext: bool) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Nil,
+        serde_json::Value::Bool(v) => Value::Bool(*v),
+        serde_json::Value::Number(v) => match (v.as_u64(), v.as_i64()) {
+            (Some(v), _) => Value::U64(v),
+            (_, Some(v)) => Value::I64(v),
+            _ => Value::F64(v.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(v) => Value::Str(v.clone()),
+        serde_json::Value::Array(values) => {
+            Value::Array(values.iter().map(|value| from_json(value, ext)).collect())
+        }
+        serde_json::Value::Object(object) => match ext.then(|| ext_object(object)).flatten() {
+            Some((tag, data)) => Value::Ext(tag, data),
+            None => Value::Map(
+                object
+                    .iter()
+                    .map(|(key, value)| (Value::Str(key.clone()), from_json(value, ext)))
+                    .collect(),
+            ),
+        },
+    }
+}
+
+/// Returns the type and data of an object that stands for an extension type.
+fn ext_object(object: &Map<String, serde_json::Value>) -> Option<(i8, Vec<u8>)> {
+    if object.len() != 2 {
+        return None;
+    }
+    let tag = i8::try_from(object.get("ext")?.as_i64()?).ok()?;
+    let data = object
+        .get("data")?
+        .as_array()?
+        .iter()
+        .map(|byte| u8::try_from(byte.as_u64()?).ok())
+        .collect::<Option<_>>()?;
+    Some((tag, data))
+}
+
+fn float(v: f64) -> serde_json::Value {
+    Number::from_f64(v).map_or(serde_json::Value::Null, serde_json::Value::Number)
+}
+
+fn bytes(v: Vec<u8>) -> serde_json::Value {
+    v.into_iter().map(serde_json::Value::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{
+        body::{Body, Bytes},
+        extract::FromRequest,
+        http::HeaderValue,
+    };
+    use hyper::{header, Request};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::{
+        decode_json_value, encode_json_value, encode_json_value_with_ext,
+        transcode_json_to_msgpack, transcode_msgpack_to_json, MsgPackToJsonValue,
+    };
+    use crate::rejection::MsgPackRejection;
+
+    fn encode(write: impl FnOnce(&mut Vec<u8>)) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn decodes_scalars() {
+        let bytes =
+            rmp_serde::encode::to_vec(&(u64::MAX, -3i64, 1.5f64, 0.1f32, "steve", ())).unwrap();
+        assert_eq!(
+            decode_json_value(&bytes).unwrap(),
+            json!([u64::MAX, -3, 1.5, 0.1, "steve", null])
+        );
+
+        let bytes = rmp_serde::encode::to_vec(&(f64::NAN, f32::INFINITY)).unwrap();
+        assert_eq!(decode_json_value(&bytes).unwrap(), json!([null, null]));
+    }
+
+    #[test]
+    fn decodes_binary_and_extensions() {
+        let bytes = encode(|buf| {
+            rmp::encode::write_array_len(buf, 2).unwrap();
+            rmp::encode::write_bin(buf, &[0, 1, 255]).unwrap();
+            rmp::encode::write_ext_meta(buf, 2, 5).unwrap();
+            buf.extend_from_slice(&[7, 8]);
+        });
+        assert_eq!(
+            decode_json_value(&bytes).unwrap(),
+            json!([[0, 1, 255], {"ext": 5, "data": [7, 8]}])
+        );
+    }
+
+    #[test]
+    fn decodes_nested_maps() {
+        let bytes = encode(|buf| {
+            rmp::encode::write_map_len(buf, 3).unwrap();
+            rmp::encode::write_str(buf, "user").unwrap();
+            rmp::encode::write_map_len(buf, 2).unwrap();
+            rmp::encode::write_str(buf, "name").unwrap();
+            rmp::encode::write_str(buf, "steve").unwrap();
+            rmp::encode::write_str(buf, "tags").unwrap();
+            rmp::encode::write_array_len(buf, 1).unwrap();
+            rmp::encode::write_str(buf, "admin").unwrap();
+            rmp::encode::write_uint(buf, 1).unwrap();
+            rmp::encode::write_bool(buf, true).unwrap();
+            rmp::encode::write_bool(buf, false).unwrap();
+            rmp::encode::write_nil(buf).unwrap();
+        });
+        assert_eq!(
+            decode_json_value(&bytes).unwrap(),
+            json!({
+                "user": {"name": "steve", "tags": ["admin"]},
+                "1": true,
+                "false": null,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_bodies() {
+        assert!(decode_json_value(&[0x92, 0x01]).is_err());
+        assert!(decode_json_value(&[0xc1]).is_err());
+    }
+
+    /// Decodes `bytes`, encodes the result and decodes it again, returning both decoded values.
+    fn round_trip(bytes: &[u8]) -> (serde_json::Value, serde_json::Value) {
+        let value = decode_json_value(bytes).unwrap();
+        let encoded = encode_json_value_with_ext(&value).unwrap();
+        (value, decode_json_value(&encoded).unwrap())
+    }
+
+    #[test]
+    fn round_trips_non_string_keys() {
+        let bytes = encode(|buf| {
+            rmp::encode::write_map_len(buf, 3).unwrap();
+            rmp::encode::write_sint(buf, -1).unwrap();
+            rmp::encode::write_str(buf, "negative").unwrap();
+            rmp::encode::write_array_len(buf, 2).unwrap();
+            rmp::encode::write_uint(buf, 1).unwrap();
+            rmp::encode::write_uint(buf, 2).unwrap();
+            rmp::encode::write_str(buf, "array").unwrap();
+            rmp::encode::write_nil(buf).unwrap();
+            rmp::encode::write_map_len(buf, 1).unwrap();
+            rmp::encode::write_uint(buf, 7).unwrap();
+            rmp::encode::write_bool(buf, true).unwrap();
+        });
+        let (value, round_tripped) = round_trip(&bytes);
+        assert_eq!(
+            value,
+            json!({"-1": "negative", "[1,2]": "array", "null": {"7": true}})
+        );
+        assert_eq!(round_tripped, value);
+    }
+
+    #[test]
+    fn round_trips_binary_and_extensions() {
+        let bytes = encode(|buf| {
+            rmp::encode::write_map_len(buf, 2).unwrap();
+            rmp::encode::write_str(buf, "bin").unwrap();
+            rmp::encode::write_bin(buf, &[0, 128, 255]).unwrap();
+            rmp::encode::write_str(buf, "ext").unwrap();
+            rmp::encode::write_ext_meta(buf, 1, -128).unwrap();
+            buf.push(42);
+        });
+        let (value, round_tripped) = round_trip(&bytes);
+        assert_eq!(
+            value,
+            json!({"bin": [0, 128, 255], "ext": {"ext": -128, "data": [42]}})
+        );
+        assert_eq!(round_tripped, value);
+
+        // Extension types are restored as such, not as maps.
+        let ext = encode_json_value_with_ext(&json!({"ext": 5, "data": [7, 8]})).unwrap();
+        assert_eq!(ext, [0xd5, 5, 7, 8]);
+
+        // Unless asked to, objects of any form stay maps.
+        let map = encode_json_value(&value).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<serde_json::Value>(&map).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn encodes_other_objects_as_maps() {
+        for value in [
+            json!({"ext": 128, "data": [1]}),
+            json!({"ext": 1, "data": [256]}),
+            json!({"ext": 1, "data": "x"}),
+            json!({"ext": 1, "data": [1], "more": true}),
+        ] {
+            let bytes = encode_json_value_with_ext(&value).unwrap();
+            assert_eq!(
+                rmp_serde::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                value
+            );
+        }
+
+        let bytes = encode_json_value(&json!([1, -1, 1.5, u64::MAX])).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<(u8, i8, f64, u64)>(&bytes).unwrap(),
+            (1, -1, 1.5, u64::MAX)
+        );
+    }
+
+    async fn extract(body: Vec<u8>) -> Result<MsgPackToJsonValue, MsgPackRejection> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        MsgPackToJsonValue::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn extracts_json_values() {
+        let bytes = encode(|buf| {
+            rmp::encode::write_map_len(buf, 2).unwrap();
+            rmp::encode::write_str(buf, "name").unwrap();
+            rmp::encode::write_str(buf, "steve").unwrap();
+            rmp::encode::write_str(buf, "avatar").unwrap();
+            rmp::encode::write_bin(buf, &[1, 2]).unwrap();
+        });
+        let MsgPackToJsonValue(value) = extract(bytes).await.unwrap();
+        assert_eq!(value, json!({"name": "steve", "avatar": [1, 2]}));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_bodies() {
+        assert!(matches!(
+            extract(vec![0xc1]).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+        assert!(matches!(
+            extract(vec![0x91, 0xc1]).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+
+    #[test]
+    fn transcodes_nested_objects_both_ways() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Order {
+            id: u64,
+            customer: Customer,
+            items: Vec<Item>,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Customer {
+            name: String,
+            vip: bool,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Item {
+            sku: String,
+            price: f64,
+            note: Option<String>,
+        }
+
+        let json = br#"{"id":7,"customer":{"name":"steve","vip":true},"items":[{"sku":"a-1","price":9.5,"note":null},{"sku":"b-2","price":-1.25,"note":"gift"}]}"#;
+        let message_pack = transcode_json_to_msgpack(Bytes::from_static(json)).unwrap();
+        let order: Order = rmp_serde::from_slice(&message_pack).unwrap();
+        assert_eq!(
+            order,
+            Order {
+                id: 7,
+                customer: Customer {
+                    name: "steve".into(),
+                    vip: true,
+                },
+                items: vec![
+                    Item {
+                        sku: "a-1".into(),
+                        price: 9.5,
+                        note: None,
+                    },
+                    Item {
+                        sku: "b-2".into(),
+                        price: -1.25,
+                        note: Some("gift".into()),
+                    },
+                ],
+            }
+        );
+
+        // Keys may come back in a different order, so compare the parsed documents.
+        let back = transcode_msgpack_to_json(message_pack).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&back).unwrap(),
+            serde_json::from_slice::<serde_json::Value>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn transcodes_extension_like_objects_as_maps() {
+        let json = br#"{"ext":5,"data":[7,8]}"#;
+        let message_pack = transcode_json_to_msgpack(Bytes::from_static(json)).unwrap();
+        let map: BTreeMap<String, serde_json::Value> =
+            rmp_serde::from_slice(&message_pack).unwrap();
+        assert_eq!(map["ext"], 5);
+        assert_eq!(map["data"], json!([7, 8]));
+    }
+
+    #[test]
+    fn rejects_malformed_transcoding_input() {
+        assert!(matches!(
+            transcode_json_to_msgpack(Bytes::from_static(b"{\"id\": ")),
+            Err(MsgPackRejection::InvalidJsonBody(_))
+        ));
+        assert!(matches!(
+            transcode_msgpack_to_json(Bytes::from_static(&[0xc1])),
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+        assert!(matches!(
+            transcode_msgpack_to_json(Bytes::from_static(&[0xdd, 0xff, 0xff, 0xff, 0xff])),
+            Err(MsgPackRejection::DeclaredLengthTooLarge(_))
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    convert::Infallible,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::MsgPack;
+use axum::{
+    async_trait,
+    body::Body,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+/// Extractor for the `If-Modified-Since` header of a conditional request.
+///
+/// Pass it to [`LastModified::if_modified_since`] to answer with `304 Not Modified` if the
+/// resource has not changed since the date in the header. The header is ignored, as
+/// [RFC 7232](https://www.rfc-editor.org/rfc/rfc7232#section-3.3) requires, if it is not a valid
+/// HTTP date, if the date lies in the future, or if the request method is neither `GET` nor
+/// `HEAD`. It is also ignored if the request has an `If-None-Match` header, so a handler that
+/// checks ETags as well leaves the decision to them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IfModifiedSince(Option<SystemTime>);
+
+impl IfModifiedSince {
+    /// Reads the header of a request with the given method and headers.
+    pub fn from_headers(method: &Method, headers: &HeaderMap) -> Self {
+        if method != Method::GET && method != Method::HEAD {
+            return Self(None);
+        }
+        if headers.contains_key(header::IF_NONE_MATCH) {
+            return Self(None);
+        }
+
+        let date = headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| httpdate::parse_http_date(value.trim()).ok())
+            .filter(|date| *date <= SystemTime::now());
+        Self(date)
+    }
+
+    /// The date of the header, or `None` if it is missing or ignored.
+    pub fn date(&self) -> Option<SystemTime> {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfModifiedSince
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.method, &parts.headers))
+    }
+}
+
+/// Response wrapper that adds a `Last-Modified` header to the wrapped response, and answers
+/// conditional requests with `304 Not Modified`.
+///
+/// Created with [`MsgPack::last_modified`]. Answers with `304 Not Modified` and an empty body
+/// instead of the wrapped response if the [`IfModifiedSince`] given to
+/// [`LastModified::if_modified_since`] is no older than the modification time. HTTP dates have a
+/// resolution of one second, so the modification time is truncated to whole seconds both in the
+/// header and in the comparison.
+///
+/// ```no_run
+/// use std::time::SystemTime;
+///
+/// use axum::{response::IntoResponse, routing::get, Router};
+/// use axum_msgpack::{IfModifiedSince, MsgPack};
+///
+/// async fn get_countries(since: IfModifiedSince) -> impl IntoResponse {
+///     let modified = SystemTime::UNIX_EPOCH;
+///     MsgPack(vec!["de", "fr"])
+///         .last_modified(modified)
+///         .if_modified_since(since)
+/// }
+///
+/// let app: Router = Router::new().route("/countries", get(get_countries));
+/// ```
+///
+/// The `304` response keeps the headers of the wrapped response except `Content-Type` and
+/// `Content-Length`. Unsuccessful responses, e.g. the `500 Internal Server Error` sent if the
+/// value cannot be serialized, are passed through without the header.
+#[derive(Debug, Clone, Copy)]
+pub struct LastModified<R> {
+    inner: R,
+    modified: SystemTime,
+    since: IfModifiedSince,
+}
+
+impl<R> LastModified<R> {
+    /// Answers with `304 Not Modified` if `since` is no older than the modification time.
+    pub fn if_modified_since(mut self, since: IfModifiedSince) -> Self {
+        self.since = since;
+        self
+    }
+}
+
+impl<T> MsgPack<T> {
+    /// Sends `modified` as the `Last-Modified` time of the response.
+    pub fn last_modified(self, modified: SystemTime) -> LastModified<Self> {
+        LastModified {
+            inner: self,
+            modified,
+            since: IfModifiedSince::default(),
+        }
+    }
+}
+
+impl<R> IntoResponse for LastModified<R>
+where
+    R: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut res = self.inner.into_response();
+        if !res.status().is_success() {
+            return res;
+        }
+
+        let modified = truncate_to_secs(self.modified);
+        res.headers_mut().insert(
+            header::LAST_MODIFIED,
+            HeaderValue::from_str(&httpdate::fmt_http_date(modified))
+                .expect("HTTP date is a valid header"),
+        );
+
+        let not_modified = self.since.date().is_some_and(|since| modified <= since);
+        if !not_modified {
+            return res;
+        }
+        let (mut parts, _) = res.into_parts();
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_TYPE);
+        parts.headers.remove(header::CONTENT_LENGTH);
+        Response::from_parts(parts, Body::empty())
+    }
+}
+
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs()),
+        Err(_) => UNIX_EPOCH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use axum::{
+        body::to_bytes,
+        http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+        response::{IntoResponse, Response},
+    };
+
+    use super::IfModifiedSince;
+    use crate::MsgPack;
+
+    /// Sun, 06 Nov 1994 08:49:37 GMT
+    const MODIFIED: u64 = 784111777;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn since(method: Method, headers: &[(header::HeaderName, &'static str)]) -> IfModifiedSince {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(name, HeaderValue::from_static(value));
+        }
+        IfModifiedSince::from_headers(&method, &map)
+    }
+
+    fn get_since(date: &'static str) -> IfModifiedSince {
+        since(Method::GET, &[(header::IF_MODIFIED_SINCE, date)])
+    }
+
+    fn respond(modified: SystemTime, since: IfModifiedSince) -> Response {
+        MsgPack("steve")
+            .last_modified(modified)
+            .if_modified_since(since)
+            .into_response()
+    }
+
+    #[test]
+    fn parses_every_http_date_format() {
+        for date in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ] {
+            assert_eq!(get_since(date).date(), Some(at(MODIFIED)), "{}", date);
+        }
+    }
+
+    #[test]
+    fn ignores_unusable_headers() {
+        for date in [
+            "yesterday",
+            "Sun, 06 Nov 1994 08:49:37 CET",
+            "Sun, 32 Nov 1994 08:49:37 GMT",
+            "",
+            // Later than now.
+            "Fri, 31 Dec 9999 23:59:59 GMT",
+        ] {
+            assert_eq!(get_since(date).date(), None, "{:?}", date);
+        }
+
+        let date = "Sun, 06 Nov 1994 08:49:37 GMT";
+        assert_eq!(
+            since(Method::POST, &[(header::IF_MODIFIED_SINCE, date)]).date(),
+            None
+        );
+        assert_eq!(
+            since(
+                Method::HEAD,
+                &[
+                    (header::IF_MODIFIED_SINCE, date),
+                    (header::IF_NONE_MATCH, "\"v1\"")
+                ]
+            )
+            .date(),
+            None
+        );
+        assert_eq!(since(Method::GET, &[]).date(), None);
+    }
+
+    #[tokio::test]
+    async fn answers_not_modified() {
+        for modified in [
+            at(MODIFIED - 60),
+            at(MODIFIED),
+            // Truncated to the second of the header.
+            at(MODIFIED) + Duration::from_millis(999),
+        ] {
+            let res = respond(modified, get_since("Sun, 06 Nov 1994 08:49:37 GMT"));
+            assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+            assert!(res.headers().get(header::CONTENT_TYPE).is_none());
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert!(body.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_modified_responses() {
+        let res = respond(
+            at(MODIFIED + 1) + Duration::from_millis(500),
+            get_since("Sun, 06 Nov 1994 08:49:37 GMT"),
+        );
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()[header::LAST_MODIFIED],
+            "Sun, 06 Nov 1994 08:49:38 GMT"
+        );
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(rmp_serde::from_slice::<String>(&body).unwrap(), "steve");
+
+        let res = respond(at(MODIFIED), IfModifiedSince::default());
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()[header::LAST_MODIFIED],
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+    }
+
+    #[test]
+    fn skips_failed_responses() {
+        struct Failing;
+
+        impl serde::Serialize for Failing {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("nope"))
+            }
+        }
+
+        let res = MsgPack(Failing)
+            .last_modified(at(MODIFIED))
+            .if_modified_since(get_since("Sun, 06 Nov 1994 08:49:37 GMT"))
+            .into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(header::LAST_MODIFIED).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+use std::marker::PhantomData;
+
+use crate::{
+    body_len::read_body,
+    check_content_type, decode,
+    rejection::{ContentTypeMatch, MissingMsgPackContentType, MsgPackRejection},
+    MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+};
+use serde::de::DeserializeOwned;
+
+/// MessagePack Extractor that reads the body right away, but decodes it only on demand.
+///
+/// Useful when the decoded value is only needed on some paths through a handler, e.g. after a
+/// permission check that may short-circuit. The body is read during extraction, so it is not
+/// left for anything else to consume, and the content type is checked as well, but decoding
+/// waits until [`MsgPackLazy::decode`] is called, with the same checks and rejections as
+/// [`MsgPack`](crate::MsgPack).
+///
+/// ```no_run
+/// use axum::{http::StatusCode, routing::post, Router};
+/// use axum_msgpack::MsgPackLazy;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     theme: String,
+/// }
+///
+/// async fn update_settings(settings: MsgPackLazy<Settings>) -> Result<(), StatusCode> {
+///     if !is_allowed() {
+///         // the body is never decoded
+///         return Err(StatusCode::FORBIDDEN);
+///     }
+///     let settings = settings.decode().map_err(|_| StatusCode::BAD_REQUEST)?;
+///     // ...
+///     Ok(())
+/// }
+///
+/// fn is_allowed() -> bool {
+///     // ...
+///     # true
+/// }
+///
+/// let app: Router = Router::new().route("/settings", post(update_settings));
+/// ```
+#[derive(Debug)]
+pub struct MsgPackLazy<T> {
+    bytes: Bytes,
+    config: MsgPackConfig,
+    found: ContentTypeMatch,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> MsgPackLazy<T>
+where
+    T: DeserializeOwned,
+{
+    /// Decodes the body. Each call decodes the body again.
+    pub fn decode(&self) -> Result<T, MsgPackRejection> {
+        decode(&self.bytes, &self.config).map_err(|err| {
+            if self.found.is_match() {
+                err
+            } else {
+                MissingMsgPackContentType::new(self.found).into()
+            }
+        })
+    }
+}
+
+impl<T> MsgPackLazy<T> {
+    /// The body of the request.
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Returns the body of the request without decoding it.
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackLazy<T>
+where
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of(&req);
+        let found = check_content_type(&req, &config)?;
+        let bytes = read_body(req, state).await?;
+        Ok(MsgPackLazy {
+            bytes,
+            config,
+            found,
+            _value: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use futures_util::StreamExt;
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackLazy;
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        theme: String,
+    }
+
+    async fn extract(
+        body: Body,
+        content_type: &'static str,
+    ) -> Result<MsgPackLazy<Settings>, MsgPackRejection> {
+        let mut request = Request::new(body);
+        request
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        MsgPackLazy::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn decodes_on_demand() {
+        let settings = Settings {
+            theme: "dark".into(),
+        };
+        let body = rmp_serde::encode::to_vec_named(&settings).unwrap();
+
+        // Count the chunks taken from the body, to tell whether it is read again.
+        let reads = Arc::new(AtomicUsize::new(0));
+        let counted = reads.clone();
+        let chunks = futures_util::stream::iter([body.clone()]).map(move |chunk| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, std::io::Error>(chunk)
+        });
+
+        let lazy = extract(Body::from_stream(chunks), "application/msgpack")
+            .await
+            .unwrap();
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+        assert_eq!(lazy.bytes(), &body[..]);
+
+        assert_eq!(lazy.decode().unwrap(), settings);
+        assert_eq!(lazy.decode().unwrap(), settings);
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_on_decode() {
+        let lazy = extract(Body::from(vec![0xc1]), "application/msgpack")
+            .await
+            .unwrap();
+        assert!(matches!(
+            lazy.decode(),
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+        assert_eq!(lazy.into_bytes(), &[0xc1][..]);
+    }
+
+    #[tokio::test]
+    async fn checks_content_type_on_extraction() {
+        assert!(matches!(
+            extract(Body::from(vec![0xc0]), "application/json").await,
+            Err(MsgPackRejection::MissingMsgPackContentType(_))
+        ));
+    }
+}
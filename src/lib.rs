@@ -1,20 +1,159 @@
 #![forbid(unsafe_code)]
 
-use crate::rejection::{InvalidMsgPackBody, MissingMsgPackContentType};
+use crate::{
+    body_len::read_body,
+    check::{Checked, Checks},
+    declared_len::check_declared_lengths,
+    duplicate::dedupe_keys,
+    empty::is_empty,
+    failure::failure_response,
+    rejection::{
+        ContentTypeMatch, InvalidMsgPackBody, MissingMsgPackContentType, MsgPackRejection,
+        TrailingMsgPackBytes, UnsupportedContentEncoding,
+    },
+    response_content_type::DefaultContentType,
+};
 use axum::{
-    body::{Bytes, Body},
     extract::{FromRequest, Request},
     response::{IntoResponse, Response},
-    http::{header::HeaderValue, StatusCode},
+    http::{header::HeaderValue, HeaderMap, StatusCode},
     async_trait,
 };
 use hyper::header;
-use rejection::MsgPackRejection;
-use serde::{de::DeserializeOwned, Serialize};
-use std::ops::{Deref, DerefMut};
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny},
+    Deserialize, Serialize,
+};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
 
+mod accept;
+mod any;
+#[cfg(feature = "tokio")]
+mod async_read;
+mod batch;
+#[cfg(feature = "tokio")]
+mod blocking;
+mod body_len;
+#[cfg(feature = "buf")]
+mod buf;
+mod by_ref;
+mod cache_control;
+mod cached;
+mod check;
+mod codec;
+mod compact;
+mod config;
+mod content_type;
+mod declared_len;
+mod duplicate;
+mod dyn_response;
+mod empty;
 mod error;
-mod rejection;
+mod failure;
+mod fallible;
+#[cfg(feature = "tokio")]
+mod file;
+mod header_payload;
+#[cfg(feature = "json")]
+mod json_fallback;
+#[cfg(feature = "json")]
+mod json_value;
+mod last_modified;
+mod lazy;
+mod limits;
+mod matched;
+mod merge;
+mod part;
+mod partial;
+pub mod rejection;
+mod rejection_statuses;
+mod response_content_type;
+mod result;
+#[cfg(test)]
+mod round_trip;
+mod seed;
+mod seq;
+#[cfg(feature = "tokio")]
+mod serialize_limit;
+mod signed;
+mod sorted;
+#[cfg(feature = "tokio")]
+mod stream;
+mod stream_response;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod timestamp;
+mod trace;
+#[cfg(feature = "json")]
+mod transcode;
+mod value;
+mod vary;
+mod version;
+mod with_bytes;
+
+pub use accept::{AcceptableMsgPackMime, AcceptedMsgPackMime};
+pub use any::MsgPackAny;
+#[cfg(feature = "tokio")]
+pub use async_read::decode_from_async_read;
+pub use batch::MsgPackBatch;
+pub use body_len::MsgPackBodyLen;
+#[cfg(feature = "buf")]
+pub use buf::MsgPackBuf;
+pub use by_ref::MsgPackRef;
+pub use cache_control::CacheFor;
+pub use cached::CachedMsgPack;
+pub use codec::MsgPackCodec;
+pub use compact::MsgPackCompactNulls;
+pub use config::MsgPackConfig;
+pub use content_type::{
+    ApplicationMsgPack, ContentType, MsgPackAs, MsgPackX, VndMsgPack, XMsgPack,
+};
+pub use duplicate::DuplicateKeys;
+pub use dyn_response::{MsgPackDyn, MsgPackSerialize};
+pub use failure::FailureFormat;
+pub use fallible::{FallibleMsgPack, MsgPackEncodeError};
+#[cfg(feature = "tokio")]
+pub use file::MsgPackFile;
+pub use header_payload::{MsgPackHeader, MsgPackHeaderName};
+#[cfg(feature = "json")]
+pub use json_fallback::json_fallback;
+#[cfg(feature = "json")]
+pub use json_value::{
+    decode_json_value, encode_json_value, encode_json_value_with_ext, transcode_json_to_msgpack,
+    transcode_msgpack_to_json, MsgPackToJsonValue,
+};
+pub use last_modified::{IfModifiedSince, LastModified};
+pub use lazy::MsgPackLazy;
+pub use limits::MsgPackLimits;
+pub use matched::{MatchedMsgPackMime, MsgPackWithContentType};
+pub use merge::MsgPackMergePatch;
+pub use part::from_part;
+pub use partial::{decode_all, MsgPackPartial};
+pub use rejection_statuses::apply_rejection_statuses;
+pub use response_content_type::apply_response_content_type;
+pub use result::{IntoStatusCode, MsgPackResult};
+pub use seed::from_request_seed;
+pub use seq::MsgPackSeq;
+#[cfg(feature = "tokio")]
+pub use serialize_limit::MsgPackSerializeLimit;
+pub use signed::{constant_time_eq, MsgPackSignature, SignedMsgPack};
+pub use sorted::MsgPackSorted;
+#[cfg(feature = "tokio")]
+pub use stream::MsgPackStream;
+pub use stream_response::MsgPackStreamResponse;
+#[cfg(feature = "json")]
+pub use transcode::msgpack_transcode;
+pub use vary::VaryAccept;
+pub use version::{Versioned, VersionedMsgPack, SCHEMA_VERSION_HEADER, X_SCHEMA_VERSION};
+pub use with_bytes::MsgPackWithBytes;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::content_type::is_message_pack_content_type;
+}
 
 /// MessagePack Extractor / Response.
 ///
@@ -23,6 +162,42 @@ mod rejection;
 /// `Content-Type` header does not match any of the `application/msgpack`, `application/x-msgpack`
 /// or `application/*+msgpack` it will reject the request and return a `400 Bad Request` response.
 ///
+/// Structs are decoded from both MessagePack maps (as produced by [`MsgPack`]) and arrays (as
+/// produced by [`MsgPackRaw`]), so either encoding is accepted by both extractors. Tuples, such
+/// as the positional arguments of a MessagePack-RPC call, are always encoded as arrays and can
+/// be extracted directly with `MsgPack<(A, B, C)>`.
+///
+/// Fields marked `#[serde(default)]`, as well as `Option` fields, may be left out of a map, which
+/// makes partial updates work as expected. Arrays are stricter: serde only fills in missing
+/// trailing elements if the whole struct is marked `#[serde(default)]`.
+///
+/// All of serde's enum representations work with the named encoding of [`MsgPack`]. The
+/// positional encoding of [`MsgPackRaw`] drops field names, so it is only safe for externally
+/// tagged enums (the default) and internally tagged enums (`#[serde(tag = "...")]`), which
+/// store the tag as the first element. Adjacently tagged enums fail to decode unit and struct
+/// variants from positional bodies, and untagged enums may fail, or pick the first variant of
+/// the same shape, since the variants can only be told apart by their field names.
+///
+/// Map entries are decoded in the order they appear in the body, so map types that keep the
+/// insertion order, such as `indexmap::IndexMap` with its `serde` feature, preserve the key
+/// order of the request, and encode their entries in that order again when used in a response.
+///
+/// Integers are encoded exactly, so unlike in JSON, `u64` and `i64` values beyond 2^53 keep
+/// their precision, in every encoding mode including human-readable.
+///
+/// Only the first value of the body is decoded, and any bytes after it are ignored. Enable
+/// [strict mode](MsgPackConfig::strict) to reject such bodies instead.
+///
+/// With the `tracing` feature enabled, every decoded body emits a `DEBUG` event carrying the
+/// `type_name` of the target type and the `body_len` in bytes, e.g. for payload size metrics.
+/// Bodies that fail to decode, and responses that fail to encode, emit a `WARN` event carrying
+/// the `type_name` and the `error`. The `pretty-debug` feature additionally emits a `DEBUG`
+/// event carrying the `body` transcoded to pretty-printed JSON, which is only meant for
+/// development, as it decodes every body twice. The bytes sent and received are unaffected.
+///
+/// With the `path-to-error` feature enabled, the error of a body that fails to decode names
+/// the path of the failing field, like `items[3].price: wrong msgpack marker FixStr(5)`.
+///
 /// # Extractor example
 ///
 /// ```no_run
@@ -49,8 +224,31 @@ mod rejection;
 /// # };
 /// ```
 ///
+/// # Borrowed and shared fields
+///
+/// The body is dropped once the value is decoded, so `T` must be [`DeserializeOwned`] and
+/// cannot borrow from it: fields of type `&str` or `&[u8]` do not compile. Use owned types, or
+/// `Cow<'static, str>`, which is always decoded as [`Cow::Owned`](std::borrow::Cow::Owned).
+/// `Box<str>` and `Box<[u8]>` work as well, and so do `Arc<T>` and `Rc<T>` with serde's `rc`
+/// feature enabled, each decoding into a new allocation.
+///
+/// # `Send` bounds
+///
+/// `T` does not need to be `Send`, as the value is only created once the body has been read.
+/// The `Send + Sync` bound on the state comes from axum's [`FromRequest`], whose futures must be
+/// `Send` so handlers can run on multi-threaded executors, and the request body is axum's
+/// [`Body`](axum::body::Body), which is always `Send`. A `!Send` variant of the extractor could
+/// not be used in a handler, so there is none, also for single-threaded runtimes such as tokio's
+/// current-thread runtime. To decode a body outside of a handler, e.g. one read from a `!Send`
+/// source, collect it and decode it with [`MsgPackCodec`].
+///
 /// When used as a response, it can serialize any type that implements [`serde::Serialize`] to
-/// `MsgPack`, and will automatically set `Content-Type: application/msgpack` header.
+/// `MsgPack`, and will automatically set `Content-Type: application/msgpack` header. Values that
+/// carry no content, `()` and unit structs, produce an empty `204 No Content` response instead.
+///
+/// Like any response, it can be returned in a tuple after a status code and a `HeaderMap`, e.g.
+/// `(StatusCode::CREATED, headers, MsgPack(user))`. The headers are added to the response, and
+/// a `Content-Type` in the `HeaderMap` replaces `application/msgpack`.
 ///
 /// # Response example
 ///
@@ -102,11 +300,16 @@ where
     type Rejection = MsgPackRejection;
 
     async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
-        if !message_pack_content_type(&req) {
-            return Err(MissingMsgPackContentType.into())
-        }
-        let bytes = Bytes::from_request(req, state).await?;
-        let value = rmp_serde::from_slice(&bytes).map_err(InvalidMsgPackBody::from_err)?;
+        let config = MsgPackConfig::of(&req);
+        let found = check_content_type(&req, &config)?;
+        let bytes = read_body(req, state).await?;
+        let value = decode(&bytes, &config).map_err(|err| {
+            if found.is_match() {
+                err
+            } else {
+                MissingMsgPackContentType::new(found).into()
+            }
+        })?;
         Ok(MsgPack(value))
     }
 }
@@ -131,29 +334,61 @@ impl<T> From<T> for MsgPack<T> {
     }
 }
 
-impl<T> IntoResponse for MsgPack<T>
+impl<T> MsgPack<T>
 where
     T: Serialize,
 {
-    fn into_response(self) -> Response {
-        let bytes = match rmp_serde::encode::to_vec_named(&self.0) {
-            Ok(res) => res,
-            Err(err) => {
-                return Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .header(header::CONTENT_TYPE, "text/plain")
-                    .body(Body::new(err.to_string()))
-                    .unwrap();
-            }
-        };
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
 
-        let mut res = bytes.into_response();
+        msgpack_response::<T, _>(
+            rmp_serde::encode::to_vec_named(&self.0),
+            ApplicationMsgPack::CONTENT_TYPE,
+            failure_format,
+        )
+    }
 
-        res.headers_mut().insert(
-            header::CONTENT_TYPE,
-            HeaderValue::from_static("application/msgpack"),
-        );
-        res
+    /// Converts into a response like [`IntoResponse::into_response`], but returns the error
+    /// instead of a `500 Internal Server Error` response if the value cannot be serialized.
+    pub fn try_into_response(self) -> Result<Response, rmp_serde::encode::Error> {
+        if is_empty(&self.0) {
+            return Ok(StatusCode::NO_CONTENT.into_response());
+        }
+
+        let bytes =
+            rmp_serde::encode::to_vec_named(&self.0).inspect_err(trace::encode_failed::<T, _>)?;
+        Ok(msgpack_response::<T, _>(
+            Ok(bytes),
+            ApplicationMsgPack::CONTENT_TYPE,
+            FailureFormat::default(),
+        ))
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> From<axum::Json<T>> for MsgPack<T> {
+    fn from(axum::Json(inner): axum::Json<T>) -> Self {
+        Self(inner)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> From<MsgPack<T>> for axum::Json<T> {
+    fn from(MsgPack(inner): MsgPack<T>) -> Self {
+        Self(inner)
+    }
+}
+
+impl<T> IntoResponse for MsgPack<T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        self.into_response_with(FailureFormat::default())
     }
 }
 
@@ -191,7 +426,8 @@ where
 /// ```
 ///
 /// When used as a response, it can serialize any type that implements [`serde::Serialize`] to
-/// `MsgPackRaw`, and will automatically set `Content-Type: application/msgpack` header.
+/// `MsgPackRaw`, and will automatically set `Content-Type: application/msgpack` header. Values that
+/// carry no content, `()` and unit structs, produce an empty `204 No Content` response instead.
 ///
 /// # Response example
 ///
@@ -243,11 +479,16 @@ where
     type Rejection = MsgPackRejection;
 
     async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
-        if !message_pack_content_type(&req) {
-            return Err(MissingMsgPackContentType.into())
-        } 
-        let bytes = Bytes::from_request(req, state).await?;
-        let value = rmp_serde::from_slice(&bytes).map_err(InvalidMsgPackBody::from_err)?;
+        let config = MsgPackConfig::of(&req);
+        let found = check_content_type(&req, &config)?;
+        let bytes = read_body(req, state).await?;
+        let value = decode(&bytes, &config).map_err(|err| {
+            if found.is_match() {
+                err
+            } else {
+                MissingMsgPackContentType::new(found).into()
+            }
+        })?;
         Ok(MsgPackRaw(value))
     }
 }
@@ -272,50 +513,301 @@ impl<T> From<T> for MsgPackRaw<T> {
     }
 }
 
+impl<T> MsgPackRaw<T>
+where
+    T: Serialize,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            rmp_serde::encode::to_vec(&self.0),
+            ApplicationMsgPack::CONTENT_TYPE,
+            failure_format,
+        )
+    }
+}
+
 impl<T> IntoResponse for MsgPackRaw<T>
 where
     T: Serialize,
 {
     fn into_response(self) -> Response {
-        let bytes = match rmp_serde::encode::to_vec(&self.0) {
-            Ok(res) => res,
-            Err(err) => {
-                return Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .header(header::CONTENT_TYPE, "text/plain")
-                    .body(Body::new(err.to_string()))
-                    .unwrap();
+        self.into_response_with(FailureFormat::default())
+    }
+}
+
+fn decode<T>(bytes: &[u8], config: &MsgPackConfig) -> Result<T, MsgPackRejection>
+where
+    T: DeserializeOwned,
+{
+    trace::decoding::<T>(bytes.len());
+    trace::body::<T>(bytes);
+    check_declared_lengths(bytes, config)?;
+    let bytes = &*dedupe_keys(bytes, config).map_err(InvalidMsgPackBody::from_err)?;
+    let mut rd = bytes;
+    let deserializer = &mut rmp_serde::Deserializer::new(&mut rd);
+    let value = deserialize_seed(PhantomData::<T>, deserializer, config)
+        .inspect_err(trace::decode_failed::<T, _>)
+        .map_err(|err| InvalidMsgPackBody::from_err(err).with_body(bytes))?;
+    check_trailing(rd.len(), config)?;
+    Ok(value)
+}
+
+/// Decodes the value at the start of `rd`, one of several values in `body`, and advances `rd`
+/// past it.
+///
+/// Applies the same checks as [`decode`], except for the one for trailing bytes, which are the
+/// values after it.
+fn decode_next<T>(
+    rd: &mut &[u8],
+    body: &[u8],
+    config: &MsgPackConfig,
+) -> Result<T, MsgPackRejection>
+where
+    T: DeserializeOwned,
+{
+    check_declared_lengths(*rd, config)?;
+    let invalid = |err: rmp_serde::decode::Error| -> MsgPackRejection {
+        trace::decode_failed::<T, _>(&err);
+        InvalidMsgPackBody::from_err(err).with_body(body).into()
+    };
+    // The duplicate key policy applies to a single value, so find where it ends first.
+    let start = *rd;
+    IgnoredAny::deserialize(&mut rmp_serde::Deserializer::new(&mut *rd)).map_err(invalid)?;
+    let value = &start[..start.len() - rd.len()];
+    let value = &*dedupe_keys(value, config).map_err(invalid)?;
+    deserialize_seed(PhantomData::<T>, &mut rmp_serde::Deserializer::new(value), config)
+        .map_err(invalid)
+}
+
+fn deserialize_seed<'de, S, D>(
+    seed: S,
+    deserializer: D,
+    config: &MsgPackConfig,
+) -> Result<S::Value, D::Error>
+where
+    S: DeserializeSeed<'de>,
+    D: Deserializer<'de>,
+{
+    #[cfg(feature = "path-to-error")]
+    {
+        let mut track = serde_path_to_error::Track::new();
+        let deserializer = serde_path_to_error::Deserializer::new(deserializer, &mut track);
+        deserialize_checked(seed, deserializer, config).map_err(|err| {
+            let path = track.path();
+            if path.iter().len() == 0 {
+                return err;
             }
-        };
+            serde::de::Error::custom(format_args!("{}: {}", path, err))
+        })
+    }
+
+    #[cfg(not(feature = "path-to-error"))]
+    deserialize_checked(seed, deserializer, config)
+}
 
-        let mut res = bytes.into_response();
+fn deserialize_checked<'de, S, D>(
+    seed: S,
+    deserializer: D,
+    config: &MsgPackConfig,
+) -> Result<S::Value, D::Error>
+where
+    S: DeserializeSeed<'de>,
+    D: Deserializer<'de>,
+{
+    if let Some(checks) = Checks::of(config) {
+        seed.deserialize(Checked::new(deserializer, checks))
+    } else {
+        seed.deserialize(deserializer)
+    }
+}
 
-        res.headers_mut().insert(
-            header::CONTENT_TYPE,
-            HeaderValue::from_static("application/msgpack"),
-        );
-        res
+fn check_trailing(trailing: usize, config: &MsgPackConfig) -> Result<(), TrailingMsgPackBytes> {
+    if config.is_strict() && trailing > 0 {
+        return Err(TrailingMsgPackBytes::new(trailing));
     }
+    Ok(())
 }
 
-fn message_pack_content_type<B>(req: &Request<B>) -> bool {
-    let Some(content_type) = req.headers().get(header::CONTENT_TYPE) else {
-        return false;
+fn msgpack_response<T, B>(
+    encoded: Result<B, rmp_serde::encode::Error>,
+    content_type: &'static str,
+    failure_format: FailureFormat,
+) -> Response
+where
+    T: ?Sized,
+    B: IntoResponse,
+{
+    let bytes = match encoded {
+        Ok(res) => res,
+        Err(err) => {
+            trace::encode_failed::<T, _>(&err);
+            return failure_response(failure_format, &err.to_string());
+        }
     };
-    let  Ok(content_type) = content_type.to_str() else {
-        return false;
+
+    let mut res = bytes.into_response();
+
+    res.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(content_type),
+    );
+    if content_type == ApplicationMsgPack::CONTENT_TYPE {
+        res.extensions_mut().insert(DefaultContentType);
+    }
+    res
+}
+
+/// Checks the `Content-Type` header of `req`.
+///
+/// Returns [`ContentTypeMatch::Match`] if the header names a MessagePack type. In lenient mode,
+/// a [`ContentTypeMatch::MissingHeader`] or [`ContentTypeMatch::Unparseable`] header is returned
+/// as well, meaning the body should only be decoded speculatively: if that decode fails, the
+/// caller sends a [`MissingMsgPackContentType`] with the returned match instead of the decode
+/// error. Any other header, including every non-matching one outside of lenient mode, is
+/// rejected with a [`MissingMsgPackContentType`] right away.
+fn check_content_type<B>(
+    req: &Request<B>,
+    config: &MsgPackConfig,
+) -> Result<ContentTypeMatch, MissingMsgPackContentType> {
+    match configured_content_type_match(req.headers(), config) {
+        ContentTypeMatch::Match => Ok(ContentTypeMatch::Match),
+        found @ (ContentTypeMatch::MissingHeader | ContentTypeMatch::Unparseable)
+            if config.is_lenient() =>
+        {
+            Ok(found)
+        }
+        found => Err(MissingMsgPackContentType::new(found)),
+    }
+}
+
+/// Encodings of request bodies the extractors can decode.
+const SUPPORTED_CONTENT_ENCODINGS: &[&str] = &["identity"];
+
+/// Checks that the body is not encoded with an unsupported `Content-Encoding`.
+///
+/// Compressed bodies have to be decompressed by a middleware first, which removes the header.
+fn check_content_encoding(headers: &HeaderMap) -> Result<(), UnsupportedContentEncoding> {
+    let unsupported = headers
+        .get_all(header::CONTENT_ENCODING)
+        .iter()
+        .flat_map(|encoding| {
+            encoding
+                .to_str()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+        })
+        .filter(|encoding| !encoding.is_empty())
+        .find(|encoding| {
+            !SUPPORTED_CONTENT_ENCODINGS
+                .iter()
+                .any(|supported| supported.eq_ignore_ascii_case(encoding))
+        });
+    match unsupported {
+        Some(encoding) => Err(UnsupportedContentEncoding::new(
+            encoding.to_owned(),
+            SUPPORTED_CONTENT_ENCODINGS,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the `Content-Type` header of `req` names a MessagePack type, or another type the
+/// [`MsgPackConfig`] of `req` accepts, and returns the matched type.
+fn message_pack_content_type<B>(
+    req: &Request<B>,
+) -> Result<mime::Mime, MissingMsgPackContentType> {
+    matched_content_type(req.headers(), &MsgPackConfig::of(req))
+}
+
+/// Like [`configured_content_type_match`], but returns the parsed type on a match.
+fn matched_content_type(
+    headers: &HeaderMap,
+    config: &MsgPackConfig,
+) -> Result<mime::Mime, MissingMsgPackContentType> {
+    match configured_content_type_match(headers, config) {
+        ContentTypeMatch::Match => headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .and_then(|content_type| content_type.parse().ok())
+            .ok_or_else(|| MissingMsgPackContentType::new(ContentTypeMatch::Unparseable)),
+        found => Err(MissingMsgPackContentType::new(found)),
+    }
+}
+
+/// Like [`content_type_match`], but also matches `application/octet-stream` if `config` accepts
+/// it.
+fn configured_content_type_match(headers: &HeaderMap, config: &MsgPackConfig) -> ContentTypeMatch {
+    match content_type_match(headers) {
+        ContentTypeMatch::WrongSubtype
+            if config.accepts_octet_stream() && is_octet_stream(headers) =>
+        {
+            ContentTypeMatch::Match
+        }
+        found => found,
+    }
+}
+
+fn is_octet_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| {
+            mime.essence_str()
+                .eq_ignore_ascii_case(mime::APPLICATION_OCTET_STREAM.essence_str())
+        })
+}
+
+fn content_type_match(headers: &HeaderMap) -> ContentTypeMatch {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE) else {
+        return ContentTypeMatch::MissingHeader;
     };
+
+    content_type
+        .to_str()
+        .map_or(ContentTypeMatch::Unparseable, mime_match)
+}
+
+fn mime_match(content_type: &str) -> ContentTypeMatch {
+    if content_type.parse::<mime::Mime>().is_err() {
+        ContentTypeMatch::Unparseable
+    } else if is_message_pack_mime(content_type) {
+        ContentTypeMatch::Match
+    } else {
+        ContentTypeMatch::WrongSubtype
+    }
+}
+
+fn is_message_pack_mime(content_type: &str) -> bool {
+    parse_message_pack_mime(content_type).is_some()
+}
+
+fn parse_message_pack_mime(content_type: &str) -> Option<mime::Mime> {
     let Ok(mime) = content_type.parse::<mime::Mime>() else {
-        return false;
+        return None;
     };
 
-    let is_message_pack = mime.type_() == "application"
+    is_message_pack_type(&mime).then_some(mime)
+}
+
+/// Returns `true` for `application/msgpack`, `application/x-msgpack` and
+/// `application/*+msgpack`, in any case. Shared by the `Content-Type` check of requests and the
+/// `Accept` negotiation of responses.
+fn is_message_pack_type(mime: &mime::Mime) -> bool {
+    mime.type_() == "application"
         && (["msgpack", "x-msgpack"]
             .iter()
-            .any(|subtype| *subtype == mime.subtype())
-            || mime.suffix().map_or(false, |suffix| suffix == "msgpack"));
-
-    is_message_pack
+            .any(|subtype| subtype.eq_ignore_ascii_case(mime.subtype().as_str()))
+            || mime
+                .suffix()
+                .is_some_and(|suffix| suffix.as_str().eq_ignore_ascii_case("msgpack")))
 }
 
 #[cfg(test)]
@@ -323,12 +815,15 @@ mod tests {
     use axum::{
         body::Body,
         extract::FromRequest,
-        http::HeaderValue,
+        http::{HeaderMap, HeaderValue, StatusCode},
         response::IntoResponse,
     };
     use futures_util::StreamExt;
 
-    use crate::{MsgPack, MsgPackRaw, MsgPackRejection};
+    use crate::{
+        rejection::{ContentTypeMatch, MsgPackRejection},
+        MsgPack, MsgPackRaw,
+    };
     use hyper::{header, Request};
     use serde::{Deserialize, Serialize};
 
@@ -366,6 +861,115 @@ mod tests {
         assert_eq!(serialized, bytes);
     }
     
+    #[tokio::test]
+    async fn unit_responds_with_no_content() {
+        #[derive(Serialize)]
+        struct Empty;
+
+        #[derive(Serialize)]
+        struct Skipped {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            foo: Option<String>,
+        }
+
+        let res = MsgPack(()).into_response();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert!(res.headers().get(header::CONTENT_TYPE).is_none());
+        assert!(to_bytes(res.into_body()).await.is_empty());
+
+        let res = MsgPackRaw(Empty).into_response();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert!(to_bytes(res.into_body()).await.is_empty());
+
+        // All fields are skipped, but the client still expects a map.
+        let res = MsgPack(Skipped { foo: None }).into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(to_bytes(res.into_body()).await, [0x80]);
+
+        let res = MsgPack(Input { foo: "bar".into() }).into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = MsgPack(None::<Input>).into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn composes_with_header_map() {
+        let input = Input { foo: "bar".into() };
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("42"));
+        let res = (headers.clone(), MsgPack(&input)).into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["x-request-id"], "42");
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/vnd.myapi+msgpack"),
+        );
+        let res = (StatusCode::CREATED, headers, MsgPack(&input)).into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.headers()["x-request-id"], "42");
+        let content_types: Vec<_> = res.headers().get_all(header::CONTENT_TYPE).iter().collect();
+        assert_eq!(content_types, ["application/vnd.myapi+msgpack"]);
+        assert_eq!(
+            to_bytes(res.into_body()).await,
+            rmp_serde::encode::to_vec_named(&input).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn extracts_smart_pointer_fields() {
+        use std::{borrow::Cow, sync::Arc};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Pointers {
+            cow: Cow<'static, str>,
+            boxed: Box<str>,
+            bytes: Box<[u8]>,
+            shared: Arc<Input>,
+            list: Arc<[u32]>,
+        }
+
+        let input = Pointers {
+            cow: Cow::Borrowed("bar"),
+            boxed: "baz".into(),
+            bytes: vec![1, 2, 3].into(),
+            shared: Arc::new(Input { foo: "qux".into() }),
+            list: vec![4, 5].into(),
+        };
+        for mut request in [into_request(&input), into_request_raw(&input)] {
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            let MsgPack(output) = MsgPack::<Pointers>::from_request(request, &())
+                .await
+                .unwrap();
+            assert_eq!(output, input);
+            assert!(matches!(output.cow, Cow::Owned(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn extracts_non_send_values() {
+        #[derive(Deserialize)]
+        struct Local {
+            foo: String,
+            #[serde(skip)]
+            _local: std::marker::PhantomData<std::rc::Rc<()>>,
+        }
+
+        let mut request = into_request(&Input { foo: "bar".into() });
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let MsgPack(local) = MsgPack::<Local>::from_request(request, &()).await.unwrap();
+        assert_eq!(local.foo, "bar");
+    }
+
     #[tokio::test]
     async fn deserializes_named() {
         let input = Input { foo: "bar".into() };
@@ -414,6 +1018,104 @@ mod tests {
         assert_eq!(input, outcome.0);
     }
 
+    #[tokio::test]
+    async fn deserializes_positional_arrays() {
+        let args = ("add".to_string(), 1u32, true);
+        let mut request = into_request(&args);
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        let outcome =
+            <MsgPack<(String, u32, bool)> as FromRequest<_, _>>::from_request(request, &||{})
+                .await;
+        assert_eq!(outcome.unwrap().0, args);
+
+        let input = Input { foo: "bar".into() };
+        let mut request = into_request_raw(&input);
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        let outcome =
+            <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &||{}).await;
+        assert_eq!(outcome.unwrap().0, input);
+    }
+
+    #[tokio::test]
+    async fn missing_fields_use_defaults() {
+        #[derive(Debug, Serialize)]
+        struct Patch {
+            name: String,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct User {
+            name: String,
+            #[serde(default)]
+            age: u32,
+            #[serde(default = "default_role")]
+            role: String,
+            nickname: Option<String>,
+        }
+
+        fn default_role() -> String {
+            "member".into()
+        }
+
+        let patch = Patch {
+            name: "steve".into(),
+        };
+        let expected = User {
+            name: "steve".into(),
+            age: 0,
+            role: "member".into(),
+            nickname: None,
+        };
+
+        let mut request = into_request(&patch);
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let outcome =
+            <MsgPack<User> as FromRequest<_, _>>::from_request(request, &||{}).await;
+        assert_eq!(outcome.unwrap().0, expected);
+
+        let mut request = into_request_raw(&patch);
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let outcome =
+            <MsgPackRaw<User> as FromRequest<_, _>>::from_request(request, &||{}).await;
+        assert!(outcome.is_err());
+
+        #[derive(Debug, Default, Deserialize, PartialEq)]
+        #[serde(default)]
+        struct Settings {
+            name: String,
+            age: u32,
+        }
+
+        let mut request = into_request_raw(&patch);
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let outcome =
+            <MsgPackRaw<Settings> as FromRequest<_, _>>::from_request(request, &||{}).await;
+        assert_eq!(
+            outcome.unwrap().0,
+            Settings {
+                name: "steve".into(),
+                age: 0
+            }
+        );
+    }
+
     #[tokio::test]
     async fn supported_content_type() {
         let input = Input { foo: "bar".into() };
@@ -460,12 +1162,492 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn supported_content_type_is_case_insensitive() {
+        let input = Input { foo: "bar".into() };
+
+        for content_type in [
+            "Application/MsgPack",
+            "APPLICATION/MSGPACK",
+            "application/X-MsgPack",
+            "Application/CloudEvents+MsgPack",
+            "application/vnd.api+MSGPACK",
+        ] {
+            let mut request = into_request(&input);
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(content_type),
+            );
+
+            let outcome =
+                <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &||{}).await;
+            assert!(outcome.is_ok(), "Expected {} to be accepted", content_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn tells_why_content_type_was_rejected() {
+        let input = Input { foo: "bar".into() };
+
+        for (content_type, found, reason) in [
+            (None, ContentTypeMatch::MissingHeader, "the header is missing"),
+            (
+                Some("msgpack"),
+                ContentTypeMatch::Unparseable,
+                "the header is not a valid media type",
+            ),
+            (
+                Some("application/json"),
+                ContentTypeMatch::WrongSubtype,
+                "the header names another media type",
+            ),
+        ] {
+            let mut request = into_request(&input);
+            if let Some(content_type) = content_type {
+                request.headers_mut().insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static(content_type),
+                );
+            }
+
+            let outcome =
+                <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+            let res = match outcome {
+                Err(MsgPackRejection::MissingMsgPackContentType(rejection)) => {
+                    assert_eq!(rejection.found(), found);
+                    rejection.into_response()
+                }
+                other => unreachable!(
+                    "Expected missing MsgPack content type rejection, got: {:?}",
+                    other
+                ),
+            };
+            let message = String::from_utf8(to_bytes(res.into_body()).await).unwrap();
+            assert_eq!(
+                message,
+                format!(
+                    "Expected request with `Content-Type: application/msgpack`, but {}",
+                    reason
+                )
+            );
+        }
+    }
+
+    async fn round_trip<T>(value: T, named: bool) -> Result<T, MsgPackRejection>
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+    {
+        let res = if named {
+            MsgPack(value).into_response()
+        } else {
+            MsgPackRaw(value).into_response()
+        };
+        let mut request = Request::new(Body::from(to_bytes(res.into_body()).await));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        <MsgPack<T> as FromRequest<_, _>>::from_request(request, &||{})
+            .await
+            .map(|outcome| outcome.0)
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[tokio::test]
+    async fn round_trips_externally_and_internally_tagged_enums() {
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+        enum External {
+            Unit,
+            Newtype(u32),
+            Tuple(u32, String),
+            Struct { a: u32, b: String },
+        }
+
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+        #[serde(tag = "type")]
+        enum Internal {
+            Unit,
+            Newtype(Point),
+            Struct { a: u32, b: String },
+        }
+
+        for named in [true, false] {
+            for value in [
+                External::Unit,
+                External::Newtype(1),
+                External::Tuple(1, "b".into()),
+                External::Struct { a: 1, b: "b".into() },
+            ] {
+                assert_eq!(round_trip(value.clone(), named).await.unwrap(), value);
+            }
+
+            for value in [
+                Internal::Unit,
+                Internal::Newtype(Point { x: 1, y: 2 }),
+                Internal::Struct { a: 1, b: "b".into() },
+            ] {
+                assert_eq!(round_trip(value.clone(), named).await.unwrap(), value);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn round_trips_adjacently_tagged_and_untagged_enums_only_named() {
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+        #[serde(tag = "t", content = "c")]
+        enum Adjacent {
+            Unit,
+            Newtype(u32),
+            Struct { a: u32, b: String },
+        }
+
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+        #[serde(untagged)]
+        enum Untagged {
+            Number(u32),
+            Point(Point),
+            Struct { a: u32, b: String },
+        }
+
+        for value in [
+            Adjacent::Unit,
+            Adjacent::Newtype(1),
+            Adjacent::Struct { a: 1, b: "b".into() },
+        ] {
+            assert_eq!(round_trip(value.clone(), true).await.unwrap(), value);
+        }
+        assert!(round_trip(Adjacent::Unit, false).await.is_err());
+        assert!(round_trip(Adjacent::Struct { a: 1, b: "b".into() }, false)
+            .await
+            .is_err());
+
+        for value in [
+            Untagged::Number(1),
+            Untagged::Point(Point { x: 1, y: 2 }),
+            Untagged::Struct { a: 1, b: "b".into() },
+        ] {
+            assert_eq!(round_trip(value.clone(), true).await.unwrap(), value);
+        }
+        assert!(round_trip(Untagged::Struct { a: 1, b: "b".into() }, false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn round_trips_64_bit_integers_exactly() {
+        const TWO_POW_53: u64 = 1 << 53;
+
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+        struct Ids {
+            unsigned: u64,
+            signed: i64,
+        }
+
+        for (unsigned, signed) in [
+            (u64::MAX, i64::MIN),
+            (u64::MAX - 1, i64::MAX),
+            (TWO_POW_53 - 1, -(TWO_POW_53 as i64) + 1),
+            (TWO_POW_53, -(TWO_POW_53 as i64)),
+            (TWO_POW_53 + 1, -(TWO_POW_53 as i64) - 1),
+        ] {
+            let ids = Ids { unsigned, signed };
+            for named in [true, false] {
+                assert_eq!(round_trip(ids.clone(), named).await.unwrap(), ids);
+            }
+
+            let codec = crate::MsgPackCodec::new().human_readable(true);
+            let bytes = codec.encode(&ids).unwrap();
+            assert_eq!(codec.decode::<Ids>(&bytes).unwrap(), ids);
+        }
+    }
+
+    #[tokio::test]
+    async fn preserves_map_key_order() {
+        use serde::{
+            de::{MapAccess, Visitor},
+            ser::SerializeMap,
+        };
+
+        /// A map keeping its entries in insertion order, like `IndexMap`.
+        #[derive(Debug, Clone, PartialEq)]
+        struct OrderedMap(Vec<(String, u32)>);
+
+        impl Serialize for OrderedMap {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(Some(self.0.len()))?;
+                for (key, value) in &self.0 {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+
+        impl<'de> Deserialize<'de> for OrderedMap {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct OrderedMapVisitor;
+
+                impl<'de> Visitor<'de> for OrderedMapVisitor {
+                    type Value = OrderedMap;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(f, "a map")
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedMap, A::Error> {
+                        let mut entries = Vec::new();
+                        while let Some(entry) = map.next_entry()? {
+                            entries.push(entry);
+                        }
+                        Ok(OrderedMap(entries))
+                    }
+                }
+
+                deserializer.deserialize_map(OrderedMapVisitor)
+            }
+        }
+
+        let map = OrderedMap(
+            ["zebra", "apple", "mango", "banana"]
+                .into_iter()
+                .zip(0..)
+                .map(|(key, value)| (key.to_owned(), value))
+                .collect(),
+        );
+        let bytes = rmp_serde::encode::to_vec_named(&map).unwrap();
+
+        let decoded = round_trip(map.clone(), true).await.unwrap();
+        assert_eq!(decoded, map);
+
+        let res = MsgPack(decoded).into_response();
+        assert_eq!(to_bytes(res.into_body()).await, bytes);
+    }
+
+    fn failing_body(kind: std::io::ErrorKind) -> Request<Body> {
+        let chunks: Vec<Result<_, std::io::Error>> = vec![
+            Ok(rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap()),
+            Err(kind.into()),
+        ];
+        let mut request = Request::new(Body::from_stream(futures_util::stream::iter(chunks)));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn rejects_body_failing_midway() {
+        let request = failing_body(std::io::ErrorKind::ConnectionReset);
+        let outcome = <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        match outcome {
+            Err(MsgPackRejection::BodyReadError(rejection)) => {
+                assert!(!rejection.is_timeout());
+                assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+            }
+            other => unreachable!("Expected body read error, got: {:?}", other),
+        }
+
+        let request = failing_body(std::io::ErrorKind::TimedOut);
+        let outcome = <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        match outcome {
+            Err(MsgPackRejection::BodyReadError(rejection)) => {
+                assert!(rejection.is_timeout());
+                assert_eq!(
+                    rejection.into_response().status(),
+                    StatusCode::REQUEST_TIMEOUT
+                );
+            }
+            other => unreachable!("Expected body read error, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_content_encoding() {
+        let input = Input { foo: "bar".into() };
+
+        for encoding in ["identity", "Identity", ""] {
+            let mut request = into_request(&input);
+            let headers = request.headers_mut();
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding));
+
+            let outcome =
+                <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+            assert_eq!(outcome.unwrap().0, input, "{:?}", encoding);
+        }
+
+        for (encoding, unsupported) in [("zstd", "zstd"), ("identity, gzip", "gzip")] {
+            let mut request = into_request(&input);
+            let headers = request.headers_mut();
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding));
+
+            let outcome =
+                <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+            let res = match outcome {
+                Err(MsgPackRejection::UnsupportedContentEncoding(rejection)) => {
+                    assert_eq!(rejection.encoding(), unsupported);
+                    rejection.into_response()
+                }
+                other => unreachable!("Expected unsupported encoding rejection, got: {:?}", other),
+            };
+            assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+            let message = String::from_utf8(to_bytes(res.into_body()).await).unwrap();
+            assert_eq!(
+                message,
+                format!(
+                    "Unsupported `Content-Encoding: {}`, expected one of: identity",
+                    unsupported
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_array_headers() {
+        // array 32 claiming u32::MAX elements, with a single element following.
+        let mut request = Request::new(Body::from(vec![0xdd, 0xff, 0xff, 0xff, 0xff, 0x01]));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        let outcome =
+            <MsgPack<Vec<u32>> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        match outcome {
+            Err(MsgPackRejection::DeclaredLengthTooLarge(rejection)) => {
+                assert_eq!(rejection.declared_len(), u32::MAX as usize);
+                assert_eq!(rejection.remaining(), 1);
+                let res = rejection.into_response();
+                assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+            }
+            other => unreachable!("Expected declared length rejection, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn hints_at_json_bodies() {
+        for (body, looks_like_json) in [
+            (&b"{\"foo\": \"bar\"}"[..], true),
+            (b" \n[1, 2]", true),
+            (b"\"bar\"", true),
+            (&[0xc1], false),
+        ] {
+            let mut request = Request::new(Body::from(body));
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+
+            let outcome =
+                <MsgPack<Input> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+            let res = match outcome {
+                Err(MsgPackRejection::InvalidMsgPackBody(rejection)) => {
+                    assert_eq!(rejection.looks_like_json(), looks_like_json);
+                    rejection.into_response()
+                }
+                other => unreachable!("Expected invalid body rejection, got: {:?}", other),
+            };
+            let message = String::from_utf8(to_bytes(res.into_body()).await).unwrap();
+            assert_eq!(
+                message.ends_with(" (body looks like JSON, not MessagePack)"),
+                looks_like_json,
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[cfg(feature = "path-to-error")]
+    #[tokio::test]
+    async fn names_path_of_failing_field() {
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        enum SentPrice {
+            Number(u32),
+            Text(&'static str),
+        }
+
+        #[derive(Serialize)]
+        struct SentItem {
+            price: SentPrice,
+        }
+
+        #[derive(Serialize)]
+        struct SentOrder {
+            items: Vec<SentItem>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Order {
+            #[allow(dead_code)]
+            items: Vec<Item>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Item {
+            #[allow(dead_code)]
+            price: u32,
+        }
+
+        let items = [
+            SentPrice::Number(1),
+            SentPrice::Number(2),
+            SentPrice::Number(3),
+            SentPrice::Text("cheap"),
+        ]
+        .into_iter()
+        .map(|price| SentItem { price })
+        .collect();
+        let mut request = into_request(&SentOrder { items });
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+
+        let outcome = <MsgPack<Order> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        let res = match outcome {
+            Err(rejection @ MsgPackRejection::InvalidMsgPackBody(_)) => rejection.into_response(),
+            other => unreachable!("Expected invalid body rejection, got: {:?}", other),
+        };
+        let message = String::from_utf8(to_bytes(res.into_body()).await).unwrap();
+        assert!(
+            message.ends_with("MsgPack: items[3].price: wrong msgpack marker FixStr(5)"),
+            "{}",
+            message
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn converts_to_and_from_json() {
+        let input = Input { foo: "bar".into() };
+
+        let json: axum::Json<Input> = MsgPack(input).into();
+        assert_eq!(json.0, Input { foo: "bar".into() });
+
+        let msgpack: MsgPack<Input> = json.into();
+        assert_eq!(msgpack.0, Input { foo: "bar".into() });
+    }
+
     async fn to_bytes(body: Body) -> Vec<u8> {
         let mut buffer = Vec::new();
         let mut stream = body.into_data_stream();
 
         while let Some(bytes) = stream.next().await {
-            buffer.extend(bytes.unwrap().into_iter());
+            buffer.extend(bytes.unwrap());
         }
 
         buffer
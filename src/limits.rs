@@ -0,0 +1,289 @@
+/// Limits on the size and shape of request bodies, set together with
+/// [`MsgPackConfig::limits`](crate::MsgPackConfig::limits).
+///
+/// [`max_body_len`](Self::max_body_len) is checked while the body is read, so a longer body is
+/// rejected before it is buffered. The other limits are checked before the body is decoded, by
+/// walking the headers of the values in the body, so a body exceeding them is rejected before
+/// any memory is allocated for its values:
+///
+/// | Limit                                            | Rejection               |
+/// |--------------------------------------------------|-------------------------|
+/// | [`max_body_len`](Self::max_body_len)             | [`MsgPackBodyTooLarge`] |
+/// | [`max_depth`](Self::max_depth)                   | [`NestingTooDeep`]      |
+/// | [`max_collection_len`](Self::max_collection_len) | [`CollectionTooLong`]   |
+/// | [`max_map_entries`](Self::max_map_entries)       | [`CollectionTooLong`]   |
+/// | [`max_str_len`](Self::max_str_len)               | [`ValueTooLong`]        |
+/// | [`max_bin_len`](Self::max_bin_len)               | [`ValueTooLong`]        |
+///
+/// [`MsgPackLimits::new`] starts from defaults suited to typical API payloads, which make
+/// hardening an endpoint a one-liner, while [`MsgPackLimits::unlimited`] starts without any
+/// limit. A [`MsgPackConfig`](crate::MsgPackConfig) without limits applies none, so the
+/// defaults have to be set explicitly:
+///
+/// ```no_run
+/// use axum::{routing::post, Extension, Router};
+/// use axum_msgpack::{MsgPack, MsgPackConfig, MsgPackLimits};
+///
+/// async fn create_user(MsgPack(payload): MsgPack<String>) {}
+///
+/// let app: Router = Router::new()
+///     .route("/users", post(create_user))
+///     .layer(Extension(MsgPackConfig::new().limits(MsgPackLimits::new())));
+/// ```
+///
+/// The limits apply wherever [`MsgPackConfig`](crate::MsgPackConfig) checks the lengths
+/// declared in the body, i.e. for [`MsgPack`](crate::MsgPack),
+/// [`MsgPackRaw`](crate::MsgPackRaw), [`MsgPackCodec`](crate::MsgPackCodec),
+/// [`from_request_seed`](crate::from_request_seed) and the extractors built on them.
+///
+/// [`MsgPackBodyTooLarge`]: crate::rejection::MsgPackBodyTooLarge
+/// [`NestingTooDeep`]: crate::rejection::NestingTooDeep
+/// [`CollectionTooLong`]: crate::rejection::CollectionTooLong
+/// [`ValueTooLong`]: crate::rejection::ValueTooLong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MsgPackLimits {
+    pub(crate) max_body_len: Option<usize>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_collection_len: Option<usize>,
+    pub(crate) max_map_entries: Option<usize>,
+    pub(crate) max_str_len: Option<usize>,
+    pub(crate) max_bin_len: Option<usize>,
+}
+
+impl MsgPackLimits {
+    /// Creates the default limits: bodies of up to 2 MiB, the default body limit of axum,
+    /// nested up to 32 levels deep, with arrays and maps of up to 65 536 elements, strings of up
+    /// to 64 KiB and binary values of up to 1 MiB.
+    pub fn new() -> Self {
+        Self {
+            max_body_len: Some(2 * 1024 * 1024),
+            max_depth: Some(32),
+            max_collection_len: Some(65_536),
+            max_map_entries: None,
+            max_str_len: Some(64 * 1024),
+            max_bin_len: Some(1024 * 1024),
+        }
+    }
+
+    /// Creates limits that allow any body.
+    pub fn unlimited() -> Self {
+        Self {
+            max_body_len: None,
+            max_depth: None,
+            max_collection_len: None,
+            max_map_entries: None,
+            max_str_len: None,
+            max_bin_len: None,
+        }
+    }
+
+    /// Rejects bodies longer than `len` bytes.
+    ///
+    /// Bodies declaring a longer `Content-Length` are rejected before they are read, and other
+    /// bodies as soon as more than `len` bytes arrive, with the `413 Payload Too Large`
+    /// [`BytesRejection`](crate::rejection::MsgPackRejection::BytesRejection) of axum's own
+    /// body limit.
+    pub fn max_body_len(mut self, len: usize) -> Self {
+        self.max_body_len = Some(len);
+        self
+    }
+
+    /// Rejects arrays and maps nested more than `depth` levels deep. A top-level array is one
+    /// level deep.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Rejects arrays with more than `len` elements and maps with more than `len` entries.
+    pub fn max_collection_len(mut self, len: usize) -> Self {
+        self.max_collection_len = Some(len);
+        self
+    }
+
+    /// Rejects maps with more than `len` entries, in addition to
+    /// [`max_collection_len`](Self::max_collection_len), e.g. to allow long arrays but only
+    /// small maps, which are more expensive to decode into a `HashMap`. No limit other than
+    /// `max_collection_len` by default.
+    pub fn max_map_entries(mut self, len: usize) -> Self {
+        self.max_map_entries = Some(len);
+        self
+    }
+
+    /// Rejects strings longer than `len` bytes.
+    pub fn max_str_len(mut self, len: usize) -> Self {
+        self.max_str_len = Some(len);
+        self
+    }
+
+    /// Rejects binary values longer than `len` bytes.
+    pub fn max_bin_len(mut self, len: usize) -> Self {
+        self.max_bin_len = Some(len);
+        self
+    }
+}
+
+impl Default for MsgPackLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackLimits;
+    use crate::{rejection::MsgPackRejection, MsgPack, MsgPackConfig};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Post {
+        title: String,
+        #[serde(with = "serde_bytes_vec")]
+        image: Vec<u8>,
+        tags: Vec<Vec<String>>,
+    }
+
+    /// Encodes `Vec<u8>` as a binary value.
+    mod serde_bytes_vec {
+        pub fn serialize<S: serde::Serializer>(
+            bytes: &[u8],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(bytes)
+        }
+
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<u8>, D::Error> {
+            serde::Deserialize::deserialize(deserializer)
+        }
+    }
+
+    fn post() -> Post {
+        Post {
+            title: "hello".into(),
+            image: vec![0; 4],
+            tags: vec![vec!["a".into(), "b".into()]],
+        }
+    }
+
+    /// Limits that `post()` is exactly within.
+    fn limits() -> MsgPackLimits {
+        let len = rmp_serde::encode::to_vec_named(&post()).unwrap().len();
+        MsgPackLimits::unlimited()
+            .max_body_len(len)
+            .max_depth(3)
+            .max_collection_len(3)
+            .max_str_len(5)
+            .max_bin_len(4)
+    }
+
+    async fn extract(limits: MsgPackLimits) -> Result<Post, MsgPackRejection> {
+        let body = rmp_serde::encode::to_vec_named(&post()).unwrap();
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request
+            .extensions_mut()
+            .insert(MsgPackConfig::new().limits(limits));
+        MsgPack::<Post>::from_request(request, &())
+            .await
+            .map(|MsgPack(post)| post)
+    }
+
+    #[tokio::test]
+    async fn accepts_bodies_within_all_limits() {
+        assert_eq!(extract(limits()).await.unwrap(), post());
+        assert_eq!(extract(MsgPackLimits::new()).await.unwrap(), post());
+        assert_eq!(extract(MsgPackLimits::unlimited()).await.unwrap(), post());
+    }
+
+    #[tokio::test]
+    async fn trips_each_limit() {
+        let len = rmp_serde::encode::to_vec_named(&post()).unwrap().len();
+        match extract(limits().max_body_len(len - 1)).await {
+            Err(MsgPackRejection::MsgPackBodyTooLarge(rejection)) => {
+                assert_eq!(rejection.body_len(), len);
+                assert_eq!(rejection.limit(), len - 1);
+            }
+            other => unreachable!("Expected body too large rejection, got: {:?}", other),
+        }
+
+        match extract(limits().max_depth(2)).await {
+            Err(MsgPackRejection::NestingTooDeep(rejection)) => assert_eq!(rejection.limit(), 2),
+            other => unreachable!("Expected nesting too deep rejection, got: {:?}", other),
+        }
+
+        // The struct is a map of three entries.
+        match extract(limits().max_collection_len(2)).await {
+            Err(MsgPackRejection::CollectionTooLong(rejection)) => {
+                assert_eq!(rejection.declared_len(), 3);
+                assert_eq!(rejection.limit(), 2);
+            }
+            other => unreachable!("Expected collection too long rejection, got: {:?}", other),
+        }
+
+        match extract(limits().max_str_len(4)).await {
+            Err(MsgPackRejection::ValueTooLong(rejection)) => assert!(!rejection.is_binary()),
+            other => unreachable!("Expected value too long rejection, got: {:?}", other),
+        }
+
+        match extract(limits().max_bin_len(3)).await {
+            Err(MsgPackRejection::ValueTooLong(rejection)) => assert!(rejection.is_binary()),
+            other => unreachable!("Expected value too long rejection, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_body_len_while_reading() {
+        use axum::{body::Bytes, http::StatusCode};
+        use futures_util::stream;
+
+        let body = rmp_serde::encode::to_vec_named(&post()).unwrap();
+        let len = body.len();
+        // Fails once the given chunks are read, so only a body that is read to the end fails.
+        let request = |chunks: Vec<Bytes>, content_length: Option<usize>| {
+            let chunks = chunks
+                .into_iter()
+                .map(Ok)
+                .chain([Err(std::io::Error::other("read past the limit"))]);
+            let mut request = Request::new(Body::from_stream(stream::iter(chunks)));
+            request.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/msgpack"),
+            );
+            if let Some(content_length) = content_length {
+                request
+                    .headers_mut()
+                    .insert(header::CONTENT_LENGTH, content_length.into());
+            }
+            let limits = limits().max_body_len(len - 1);
+            request
+                .extensions_mut()
+                .insert(MsgPackConfig::new().limits(limits));
+            request
+        };
+
+        let declared = request(vec![Bytes::copy_from_slice(&body[..2])], Some(len));
+        match MsgPack::<Post>::from_request(declared, &()).await {
+            Err(MsgPackRejection::MsgPackBodyTooLarge(rejection)) => {
+                assert_eq!(rejection.body_len(), len);
+                assert_eq!(rejection.limit(), len - 1);
+            }
+            other => unreachable!("Expected body too large, got: {:?}", other),
+        }
+
+        let streamed = request(vec![Bytes::from(body)], None);
+        match MsgPack::<Post>::from_request(streamed, &()).await {
+            Err(rejection @ MsgPackRejection::BytesRejection(_)) => {
+                assert_eq!(rejection.status(), StatusCode::PAYLOAD_TOO_LARGE);
+            }
+            other => unreachable!("Expected body limit rejection, got: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    body_len::read_body, decode, matched_content_type, rejection::MsgPackRejection, MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+
+/// Extractor for the MessagePack media type of the request.
+///
+/// Parses the `Content-Type` header the same way [`MsgPack`](crate::MsgPack) checks it, and
+/// hands the parsed [`mime::Mime`] to the handler, e.g. to echo the exact media type back.
+/// Requests without a MessagePack content type, or another type the [`MsgPackConfig`] accepts,
+/// are rejected with [`MissingMsgPackContentType`]. As it does not touch the body, it can be
+/// used together with a body extractor.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::{MatchedMsgPackMime, MsgPack};
+///
+/// async fn echo(
+///     MatchedMsgPackMime(mime): MatchedMsgPackMime,
+///     MsgPack(payload): MsgPack<Vec<u32>>,
+/// ) {
+///     // mime is e.g. `application/cloudevents+msgpack`
+/// }
+///
+/// let app: Router = Router::new().route("/echo", post(echo));
+/// ```
+///
+/// [`MissingMsgPackContentType`]: crate::rejection::MissingMsgPackContentType
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchedMsgPackMime(pub mime::Mime);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for MatchedMsgPackMime
+where
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of_extensions(&parts.extensions);
+        let mime = matched_content_type(&parts.headers, &config)?;
+        Ok(MatchedMsgPackMime(mime))
+    }
+}
+
+/// MessagePack extractor that keeps the matched content type next to the decoded value.
+///
+/// Decodes the body like [`MsgPack`](crate::MsgPack), and hands out the parsed `Content-Type`
+/// the body was accepted with, e.g. to record in an audit log whether a client sent
+/// `application/msgpack` or `application/x-msgpack`. Unlike [`MsgPack`](crate::MsgPack), it
+/// requires a matching content type even if the [`MsgPackConfig`] is lenient, since there is
+/// no type to hand out otherwise.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackWithContentType;
+///
+/// async fn create_event(MsgPackWithContentType(event, mime): MsgPackWithContentType<String>) {
+///     // log `mime`, e.g. `application/x-msgpack`, along with the event
+/// }
+///
+/// let app: Router = Router::new().route("/events", post(create_event));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MsgPackWithContentType<T>(pub T, pub mime::Mime);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackWithContentType<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of(&req);
+        let mime = matched_content_type(req.headers(), &config)?;
+        let bytes = read_body(req, state).await?;
+        let value = decode(&bytes, &config)?;
+        Ok(MsgPackWithContentType(value, mime))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::{FromRequest, FromRequestParts},
+        http::HeaderValue,
+    };
+    use hyper::{header, Request};
+
+    use super::{MatchedMsgPackMime, MsgPackWithContentType};
+    use crate::{rejection::MsgPackRejection, MsgPackConfig};
+
+    async fn extract(
+        content_type: Option<&'static str>,
+    ) -> Result<MatchedMsgPackMime, MsgPackRejection> {
+        let mut request = Request::new(());
+        if let Some(content_type) = content_type {
+            request
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+        let (mut parts, ()) = request.into_parts();
+        MatchedMsgPackMime::from_request_parts(&mut parts, &()).await
+    }
+
+    async fn extract_with_content_type(
+        content_type: &'static str,
+        config: MsgPackConfig,
+    ) -> Result<MsgPackWithContentType<String>, MsgPackRejection> {
+        let body = rmp_serde::to_vec("steve").unwrap();
+        let mut request = Request::new(Body::from(body));
+        request
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        request.extensions_mut().insert(config);
+        MsgPackWithContentType::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn exposes_matched_mime() {
+        let MatchedMsgPackMime(mime) =
+            extract(Some("application/cloudevents+msgpack; charset=utf-8"))
+                .await
+                .unwrap();
+
+        assert_eq!(mime.subtype(), "cloudevents");
+        assert_eq!(mime.suffix().unwrap(), "msgpack");
+        assert_eq!(mime.get_param(mime::CHARSET).unwrap(), "utf-8");
+    }
+
+    #[tokio::test]
+    async fn rejects_other_or_missing_content_type() {
+        for content_type in [Some("application/json"), Some("not a mime"), None] {
+            assert!(matches!(
+                extract(content_type).await,
+                Err(MsgPackRejection::MissingMsgPackContentType(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn captures_each_recognized_content_type() {
+        let config = MsgPackConfig::new().accept_octet_stream(true);
+        for content_type in [
+            "application/msgpack",
+            "application/x-msgpack",
+            "application/vnd.api+msgpack",
+            "Application/X-MsgPack; charset=utf-8",
+            "application/octet-stream",
+        ] {
+            let MsgPackWithContentType(value, mime) =
+                extract_with_content_type(content_type, config.clone())
+                    .await
+                    .unwrap();
+            assert_eq!(value, "steve");
+            assert_eq!(mime, content_type.parse::<mime::Mime>().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn requires_content_type_when_lenient() {
+        let config = MsgPackConfig::new().lenient(true);
+        for content_type in ["not a mime", "application/octet-stream"] {
+            assert!(matches!(
+                extract_with_content_type(content_type, config.clone()).await,
+                Err(MsgPackRejection::MissingMsgPackContentType(_))
+            ));
+        }
+    }
+}
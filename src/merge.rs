@@ -0,0 +1,253 @@
+use crate::{
+    rejection::{InvalidMsgPackBody, MsgPackRejection},
+    value::Value,
+    MsgPack,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// MessagePack Extractor for merge-patch updates.
+///
+/// Decodes the request body into an untyped MessagePack value, which can then be merged into an
+/// existing entity with [`apply_to`](MsgPackMergePatch::apply_to). The merge follows the rules
+/// of JSON Merge Patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)): maps are merged
+/// recursively, a `nil` value deletes the key, and any other value replaces the existing one.
+///
+/// Content-type checking and rejections are the same as for [`MsgPack`].
+///
+/// ```no_run
+/// use axum::{routing::patch, Router};
+/// use axum_msgpack::{rejection::MsgPackRejection, MsgPack, MsgPackMergePatch};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct User {
+///     email: String,
+///     nickname: Option<String>,
+/// }
+///
+/// async fn update_user(patch: MsgPackMergePatch) -> Result<MsgPack<User>, MsgPackRejection> {
+///     let user = load_user().await;
+///     let user = patch.apply_to(user)?;
+///     Ok(MsgPack(user))
+/// }
+///
+/// async fn load_user() -> User {
+///     // ...
+///     # unimplemented!()
+/// }
+///
+/// let app: Router = Router::new().route("/users/me", patch(update_user));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MsgPackMergePatch {
+    patch: Value,
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for MsgPackMergePatch
+where
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let MsgPack(patch) = MsgPack::from_request(req, state).await?;
+        Ok(MsgPackMergePatch { patch })
+    }
+}
+
+impl MsgPackMergePatch {
+    /// Applies the patch to `existing` and returns the patched value.
+    ///
+    /// `existing` is encoded with named fields, patched, and decoded again. Fails with
+    /// [`InvalidMsgPackBody`] if the patched value no longer matches `T`.
+    pub fn apply_to<T>(&self, existing: T) -> Result<T, MsgPackRejection>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let bytes =
+            rmp_serde::encode::to_vec_named(&existing).map_err(InvalidMsgPackBody::from_err)?;
+        let mut target: Value =
+            rmp_serde::from_slice(&bytes).map_err(InvalidMsgPackBody::from_err)?;
+
+        merge(&mut target, &self.patch);
+
+        let bytes = rmp_serde::encode::to_vec(&target).map_err(InvalidMsgPackBody::from_err)?;
+        let value = rmp_serde::from_slice(&bytes).map_err(InvalidMsgPackBody::from_err)?;
+        Ok(value)
+    }
+}
+
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Map(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !matches!(target, Value::Map(_)) {
+        *target = Value::Map(Vec::new());
+    }
+    let Value::Map(entries) = target else {
+        unreachable!()
+    };
+
+    for (key, value) in patch {
+        let existing = entries.iter().position(|(k, _)| k == key);
+        match (existing, value.is_nil()) {
+            (Some(index), true) => {
+                entries.remove(index);
+            }
+            (Some(index), false) => merge(&mut entries[index].1, value),
+            (None, true) => {}
+            (None, false) => {
+                let mut new = Value::Nil;
+                merge(&mut new, value);
+                entries.push((key.clone(), new));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackMergePatch;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Address {
+        city: String,
+        street: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+        nickname: Option<String>,
+        address: Address,
+    }
+
+    fn user() -> User {
+        User {
+            name: "steve".into(),
+            nickname: Some("st".into()),
+            address: Address {
+                city: "Berlin".into(),
+                street: Some("Main".into()),
+            },
+        }
+    }
+
+    async fn extract<T: Serialize>(patch: &T) -> MsgPackMergePatch {
+        let body = rmp_serde::encode::to_vec_named(patch).unwrap();
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        <MsgPackMergePatch as FromRequest<_, _>>::from_request(request, &|| {})
+            .await
+            .unwrap()
+    }
+
+    #[derive(Serialize)]
+    struct NamePatch {
+        name: String,
+    }
+
+    #[derive(Serialize)]
+    struct AddressPatch {
+        city: String,
+        street: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct NestedPatch {
+        nickname: Option<String>,
+        address: AddressPatch,
+    }
+
+    #[tokio::test]
+    async fn overwrites_keys() {
+        let patch = extract(&NamePatch { name: "bob".into() }).await;
+
+        let patched = patch.apply_to(user()).unwrap();
+        assert_eq!(
+            patched,
+            User {
+                name: "bob".into(),
+                ..user()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn deletes_nil_keys_recursively() {
+        let patch = extract(&NestedPatch {
+            nickname: None,
+            address: AddressPatch {
+                city: "Paris".into(),
+                street: None,
+            },
+        })
+        .await;
+
+        let patched = patch.apply_to(user()).unwrap();
+        assert_eq!(
+            patched,
+            User {
+                name: "steve".into(),
+                nickname: None,
+                address: Address {
+                    city: "Paris".into(),
+                    street: None,
+                },
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn adds_keys() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Sparse {
+            name: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            nickname: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct NicknamePatch {
+            nickname: String,
+        }
+
+        let patch = extract(&NicknamePatch {
+            nickname: "bobby".into(),
+        })
+        .await;
+
+        let patched = patch
+            .apply_to(Sparse {
+                name: "bob".into(),
+                nickname: None,
+            })
+            .unwrap();
+        assert_eq!(
+            patched,
+            Sparse {
+                name: "bob".into(),
+                nickname: Some("bobby".into()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_patches_that_do_not_fit() {
+        let patch = extract(&1u32).await;
+        assert!(patch.apply_to(user()).is_err());
+    }
+}
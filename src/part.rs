@@ -0,0 +1,80 @@
+use crate::{
+    decode, mime_match,
+    rejection::{ContentTypeMatch, MissingMsgPackContentType, MsgPackRejection},
+    MsgPackConfig,
+};
+use serde::de::DeserializeOwned;
+
+/// Decodes one part of a multipart request as MessagePack.
+///
+/// The part's content type is checked the same way [`MsgPack`](crate::MsgPack) checks the
+/// `Content-Type` header of a request, so `application/msgpack`, `application/x-msgpack` and
+/// `application/*+msgpack` are accepted. The function only needs the content type and the bytes
+/// of the part, so it can be used with any multipart implementation, for example with the
+/// `content_type()` and `bytes()` of an axum multipart `Field`.
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Metadata {
+///     title: String,
+/// }
+///
+/// // Encoded `{"title": "cat.png"}`.
+/// let bytes = [0x81, 0xa5, b't', b'i', b't', b'l', b'e', 0xa7, b'c', b'a', b't', b'.', b'p', b'n', b'g'];
+///
+/// let metadata: Metadata = axum_msgpack::from_part(Some("application/msgpack"), &bytes).unwrap();
+/// assert_eq!(metadata.title, "cat.png");
+/// ```
+pub fn from_part<T>(content_type: Option<&str>, bytes: &[u8]) -> Result<T, MsgPackRejection>
+where
+    T: DeserializeOwned,
+{
+    match content_type.map_or(ContentTypeMatch::MissingHeader, mime_match) {
+        ContentTypeMatch::Match => {}
+        found => return Err(MissingMsgPackContentType::new(found).into()),
+    }
+    decode(bytes, &MsgPackConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::from_part;
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Metadata {
+        title: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn decodes_msgpack_part() {
+        let metadata = Metadata {
+            title: "cat.png".into(),
+            tags: vec!["cat".into()],
+        };
+        let bytes = rmp_serde::encode::to_vec_named(&metadata).unwrap();
+
+        let decoded: Metadata = from_part(Some("application/msgpack"), &bytes).unwrap();
+        assert_eq!(decoded, metadata);
+    }
+
+    #[test]
+    fn rejects_other_content_types() {
+        let bytes = rmp_serde::encode::to_vec_named(&"cat.png").unwrap();
+
+        for content_type in [None, Some("image/png"), Some("not a mime")] {
+            match from_part::<String>(content_type, &bytes) {
+                Err(MsgPackRejection::MissingMsgPackContentType(_)) => {}
+                other => unreachable!(
+                    "Expected missing MsgPack content type rejection, got: {:?}",
+                    other
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use std::marker::PhantomData;
+
+use crate::{
+    body_len::read_body, declared_len::check_declared_lengths, deserialize_seed,
+    duplicate::dedupe_next, message_pack_content_type, rejection::MsgPackRejection, trace,
+    MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::de::{DeserializeOwned, Error as _};
+
+/// Decodes as many complete MessagePack values as possible from `bytes`, which holds values
+/// written one after another.
+///
+/// Decoding stops at the first value that fails to decode, e.g. a value cut off at the end of
+/// a truncated body or one that does not match `T`. Returns the values decoded before it, the
+/// number of bytes left from the start of the failed value, and the error. If every value is
+/// decoded, no bytes are left and there is no error.
+///
+/// ```
+/// let mut bytes = Vec::new();
+/// for value in [1u32, 2, 300] {
+///     rmp::encode::write_uint(&mut bytes, value.into()).unwrap();
+/// }
+/// // Cut off the last value, `300` takes three bytes.
+/// bytes.truncate(bytes.len() - 1);
+///
+/// let (values, remaining, error) = axum_msgpack::decode_all::<u32>(&bytes);
+/// assert_eq!(values, [1, 2]);
+/// assert_eq!(remaining, 2);
+/// assert!(error.is_some());
+/// ```
+pub fn decode_all<T>(bytes: &[u8]) -> (Vec<T>, usize, Option<rmp_serde::decode::Error>)
+where
+    T: DeserializeOwned,
+{
+    trace::decoding::<T>(bytes.len());
+    let mut values = Vec::new();
+    let mut rd = bytes;
+    while !rd.is_empty() {
+        let remaining = rd.len();
+        match T::deserialize(&mut rmp_serde::Deserializer::new(&mut rd)) {
+            Ok(value) => values.push(value),
+            Err(err) => {
+                trace::decode_failed::<T, _>(&err);
+                return (values, remaining, Some(err));
+            }
+        }
+    }
+    (values, 0, None)
+}
+
+/// Like [`decode_all`], but checks and decodes every value with the options of `config`.
+fn decode_all_checked<T>(
+    bytes: &[u8],
+    config: &MsgPackConfig,
+) -> (Vec<T>, usize, Option<rmp_serde::decode::Error>)
+where
+    T: DeserializeOwned,
+{
+    trace::decoding::<T>(bytes.len());
+    let mut values = Vec::new();
+    let mut rd = bytes;
+    while !rd.is_empty() {
+        let remaining = rd.len();
+        match decode_checked(&mut rd, config) {
+            Ok(value) => values.push(value),
+            Err(err) => {
+                trace::decode_failed::<T, _>(&err);
+                return (values, remaining, Some(err));
+            }
+        }
+    }
+    (values, 0, None)
+}
+
+fn decode_checked<T>(rd: &mut &[u8], config: &MsgPackConfig) -> Result<T, rmp_serde::decode::Error>
+where
+    T: DeserializeOwned,
+{
+    // A value over the limits fails like any other, keeping the values before it.
+    check_declared_lengths(*rd, config).map_err(rmp_serde::decode::Error::custom)?;
+    match dedupe_next(&mut *rd, config)? {
+        Some(deduped) => {
+            let deserializer = &mut rmp_serde::Deserializer::new(&deduped[..]);
+            deserialize_seed(PhantomData::<T>, deserializer, config)
+        }
+        None => deserialize_seed(
+            PhantomData::<T>,
+            &mut rmp_serde::Deserializer::new(rd),
+            config,
+        ),
+    }
+}
+
+/// MessagePack Extractor for a body of values written one after another, decoded on a best
+/// effort basis.
+///
+/// Decodes the body like [`decode_all`], so a body that is truncated or has a malformed value
+/// still yields the values before it instead of being rejected. Check [`error`] to tell
+/// complete bodies from partial ones. Content-type checking and the rejections for reading the
+/// body are the same as for [`MsgPack`](crate::MsgPack).
+///
+/// Unlike `decode_all`, every value is checked and decoded with the options of the
+/// [`MsgPackConfig`], such as its [limits](crate::MsgPackLimits) and
+/// [`deny_unknown_fields`](MsgPackConfig::deny_unknown_fields). A value that breaks them fails
+/// to decode like a malformed one.
+///
+/// [`error`]: MsgPackPartial::error
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackPartial;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Reading {
+///     sensor: u32,
+///     value: f64,
+/// }
+///
+/// async fn ingest(readings: MsgPackPartial<Reading>) {
+///     // store `readings.values`, and log `readings.error` if any
+/// }
+///
+/// let app: Router = Router::new().route("/readings", post(ingest));
+/// ```
+#[derive(Debug)]
+pub struct MsgPackPartial<T> {
+    /// The values decoded before the first value that failed to decode.
+    pub values: Vec<T>,
+    /// The number of bytes left from the start of the failed value, `0` if all were decoded.
+    pub remaining: usize,
+    /// Why the first value that failed to decode did so, `None` if all were decoded.
+    pub error: Option<rmp_serde::decode::Error>,
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackPartial<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        message_pack_content_type(&req)?;
+        let config = MsgPackConfig::of(&req);
+        let bytes = read_body(req, state).await?;
+        let (values, remaining, error) = decode_all_checked(&bytes, &config);
+        Ok(MsgPackPartial {
+            values,
+            remaining,
+            error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::{decode_all, MsgPackPartial};
+    use crate::{MsgPackConfig, MsgPackLimits};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        sensor: u32,
+        value: f64,
+    }
+
+    fn readings() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for sensor in 0..3 {
+            let reading = Reading { sensor, value: 1.5 };
+            bytes.extend(rmp_serde::encode::to_vec_named(&reading).unwrap());
+        }
+        bytes
+    }
+
+    #[test]
+    fn decodes_complete_bodies() {
+        let (values, remaining, error) = decode_all::<Reading>(&readings());
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[2].sensor, 2);
+        assert_eq!(remaining, 0);
+        assert!(error.is_none());
+
+        let (values, remaining, error) = decode_all::<Reading>(&[]);
+        assert!(values.is_empty());
+        assert_eq!(remaining, 0);
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn keeps_prefix_of_truncated_bodies() {
+        let bytes = readings();
+        let single = bytes.len() / 3;
+        for cut in 1..single {
+            let (values, remaining, error) = decode_all::<Reading>(&bytes[..bytes.len() - cut]);
+            assert_eq!(values.len(), 2, "cut {}", cut);
+            assert_eq!(remaining, single - cut, "cut {}", cut);
+            assert!(error.is_some(), "cut {}", cut);
+        }
+    }
+
+    #[test]
+    fn stops_at_mismatched_values() {
+        let mut bytes = readings();
+        let single = bytes.len() / 3;
+        bytes.splice(single..single, rmp_serde::encode::to_vec("text").unwrap());
+
+        let (values, remaining, error) = decode_all::<Reading>(&bytes);
+        assert_eq!(values.len(), 1);
+        assert_eq!(remaining, bytes.len() - single);
+        assert!(error.is_some());
+    }
+
+    async fn extract(body: Vec<u8>, config: MsgPackConfig) -> MsgPackPartial<Reading> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request.extensions_mut().insert(config);
+        MsgPackPartial::from_request(request, &()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn extracts_partial_bodies() {
+        let bytes = readings();
+        let partial = extract(bytes[..bytes.len() - 1].to_vec(), MsgPackConfig::new()).await;
+        assert_eq!(partial.values.len(), 2);
+        assert_eq!(partial.remaining, bytes.len() / 3 - 1);
+        assert!(partial.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn stops_at_values_breaking_the_config() {
+        #[derive(Serialize)]
+        struct Loose {
+            sensor: u32,
+            value: f64,
+            unit: &'static str,
+        }
+
+        let mut bytes = readings();
+        let single = bytes.len() / 3;
+        let loose = Loose {
+            sensor: 3,
+            value: f64::NAN,
+            unit: "celsius",
+        };
+        bytes.extend(rmp_serde::encode::to_vec_named(&loose).unwrap());
+
+        let partial = extract(bytes.clone(), MsgPackConfig::new()).await;
+        assert_eq!(partial.values.len(), 4);
+        assert!(partial.error.is_none());
+
+        for config in [
+            MsgPackConfig::new().deny_unknown_fields(true),
+            MsgPackConfig::new().finite_floats(true),
+            MsgPackConfig::new().limits(MsgPackLimits::new().max_str_len(6)),
+        ] {
+            let partial = extract(bytes.clone(), config).await;
+            assert_eq!(partial.values.len(), 3);
+            assert_eq!(partial.remaining, bytes.len() - 3 * single);
+            assert!(partial.error.is_some());
+        }
+    }
+}
@@ -1,119 +1,1209 @@
-use crate::error::Error;
+//! Rejection response types.
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use crate::{error::Error, MsgPackEncodeError};
 use axum::{
     body::Body,
     extract::rejection::BytesRejection,
-    http,
+    http::{self, HeaderValue},
     response::{IntoResponse, Response},
     BoxError,
 };
 
+/// Creates the plain text response of a rejection, which has no `Content-Type` header.
+fn text_response(status: http::StatusCode, body: String) -> Response {
+    let mut res = Response::new(Body::from(body));
+    *res.status_mut() = status;
+    res
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for [`MsgPack`](super::MsgPack) used if the request body
+/// could not be decoded
+pub struct InvalidMsgPackBody {
+    error: Error,
+    looks_like_json: bool,
+}
+
+impl InvalidMsgPackBody {
+    pub(crate) fn from_err<E>(err: E) -> Self
+    where
+        E: Into<BoxError>,
+    {
+        Self {
+            error: Error::new(err),
+            looks_like_json: false,
+        }
+    }
+
+    /// Notes whether `body`, which failed to decode, looks like JSON.
+    ///
+    /// JSON documents start with `{`, `[` or `"` after optional whitespace. All of these bytes
+    /// are small positive integers in MessagePack, so a body starting with them rarely is the
+    /// MessagePack a client meant to send.
+    pub(crate) fn with_body(mut self, body: &[u8]) -> Self {
+        self.looks_like_json = body
+            .iter()
+            .find(|byte| !matches!(byte, b' ' | b'\t' | b'\n' | b'\r'))
+            .is_some_and(|byte| matches!(byte, b'{' | b'[' | b'"'));
+        self
+    }
+
+    /// Returns `true` if the body looks like JSON, which is a common mistake of clients.
+    ///
+    /// The response then ends with the hint "(body looks like JSON, not MessagePack)".
+    pub fn looks_like_json(&self) -> bool {
+        self.looks_like_json
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        let hint = if self.looks_like_json {
+            " (body looks like JSON, not MessagePack)"
+        } else {
+            ""
+        };
+        format!(
+            "Failed to parse the request body as MsgPack: {}{}",
+            self.error, hint
+        )
+    }
+}
+
+impl IntoResponse for InvalidMsgPackBody {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for InvalidMsgPackBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse the request body as MsgPack")
+    }
+}
+
+impl std::error::Error for InvalidMsgPackBody {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// How the `Content-Type` header of a request relates to MessagePack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentTypeMatch {
+    /// The header names a MessagePack type.
+    Match,
+    /// The header is missing.
+    MissingHeader,
+    /// The header is not a valid media type.
+    Unparseable,
+    /// The header names a media type other than MessagePack.
+    WrongSubtype,
+}
+
+impl ContentTypeMatch {
+    /// Returns `true` for [`ContentTypeMatch::Match`].
+    pub fn is_match(self) -> bool {
+        self == Self::Match
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for [`MsgPack`](super::MsgPack) used if the `Content-Type`
+/// header is missing or does not name a MessagePack type
+pub struct MissingMsgPackContentType {
+    found: ContentTypeMatch,
+}
+
+impl MissingMsgPackContentType {
+    pub(crate) fn new(found: ContentTypeMatch) -> Self {
+        Self { found }
+    }
+
+    /// Why the `Content-Type` header was not accepted.
+    pub fn found(&self) -> ContentTypeMatch {
+        self.found
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for MissingMsgPackContentType {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::error::Error for MissingMsgPackContentType {}
+impl std::fmt::Display for MissingMsgPackContentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected request with `Content-Type: application/msgpack`"
+        )?;
+        match self.found {
+            ContentTypeMatch::Match => Ok(()),
+            ContentTypeMatch::MissingHeader => write!(f, ", but the header is missing"),
+            ContentTypeMatch::Unparseable => {
+                write!(f, ", but the header is not a valid media type")
+            }
+            ContentTypeMatch::WrongSubtype => {
+                write!(f, ", but the header names another media type")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if the request body was already taken by another
+/// extractor
+pub struct BodyAlreadyExtracted;
+
+impl BodyAlreadyExtracted {
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for BodyAlreadyExtracted {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for BodyAlreadyExtracted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Cannot have two request body extractors for a single handler"
+        )
+    }
+}
+
+impl std::error::Error for BodyAlreadyExtracted {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for [`MsgPackBatch`](super::MsgPackBatch) used if one or
+/// more elements of the batch could not be decoded
+pub struct InvalidMsgPackBatch(Vec<(usize, Error)>);
+
+impl InvalidMsgPackBatch {
+    pub(crate) fn new(errors: Vec<(usize, Error)>) -> Self {
+        Self(errors)
+    }
+
+    /// Indices of the elements that failed to decode, in ascending order.
+    pub fn failed_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().map(|(index, _)| *index)
+    }
+
+    /// The decode error of each failed element, together with its index.
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &(dyn std::error::Error + 'static))> {
+        self.0
+            .iter()
+            .map(|(index, err)| (*index, err as &(dyn std::error::Error + 'static)))
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for InvalidMsgPackBatch {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for InvalidMsgPackBatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse elements of the MsgPack batch")?;
+        for (i, (index, err)) in self.0.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{}[{}]: {}", sep, index, err)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidMsgPackBatch {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for [`VersionedMsgPack`](super::VersionedMsgPack) and
+/// [`Versioned`](super::Versioned) used if the schema version header is
+/// missing or not supported
+pub struct SchemaVersionMismatch {
+    header: &'static str,
+    expected: RangeInclusive<u32>,
+    found: Option<String>,
+}
+
+impl SchemaVersionMismatch {
+    pub(crate) fn new(
+        header: &'static str,
+        expected: RangeInclusive<u32>,
+        found: Option<&HeaderValue>,
+    ) -> Self {
+        Self {
+            header,
+            expected,
+            found: found.map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned()),
+        }
+    }
+
+    /// The value of the schema version header, if the request had one.
+    pub fn found(&self) -> Option<&str> {
+        self.found.as_deref()
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for SchemaVersionMismatch {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for SchemaVersionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (min, max) = (self.expected.start(), self.expected.end());
+        if min == max {
+            write!(f, "Expected request with `{}: {}`", self.header, min)?;
+        } else {
+            write!(
+                f,
+                "Expected request with `{}` between {} and {}",
+                self.header, min, max
+            )?;
+        }
+        match &self.found {
+            Some(found) => write!(f, ", found `{}`", found),
+            None => write!(f, ", found no such header"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaVersionMismatch {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used in [strict mode](super::MsgPackConfig::strict) if the
+/// request body has bytes left over after the decoded value
+pub struct TrailingMsgPackBytes {
+    len: usize,
+}
+
+impl TrailingMsgPackBytes {
+    pub(crate) fn new(len: usize) -> Self {
+        Self { len }
+    }
+
+    /// Number of bytes left over after the decoded value.
+    pub fn trailing_len(&self) -> usize {
+        self.len
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for TrailingMsgPackBytes {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for TrailingMsgPackBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Found {} trailing bytes after the MsgPack value in the request body",
+            self.len
+        )
+    }
+}
+
+impl std::error::Error for TrailingMsgPackBytes {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if the body exceeds the size limit set in
+/// [`MsgPackCodec::limit`](super::MsgPackCodec::limit) or
+/// [`MsgPackLimits`](super::MsgPackLimits)
+pub struct MsgPackBodyTooLarge {
+    len: usize,
+    limit: usize,
+}
+
+impl MsgPackBodyTooLarge {
+    pub(crate) fn new(len: usize, limit: usize) -> Self {
+        Self { len, limit }
+    }
+
+    /// Size of the rejected body in bytes.
+    pub fn body_len(&self) -> usize {
+        self.len
+    }
+
+    /// The configured size limit in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::PAYLOAD_TOO_LARGE
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for MsgPackBodyTooLarge {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for MsgPackBodyTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MsgPack body of {} bytes exceeds the limit of {} bytes",
+            self.len, self.limit
+        )
+    }
+}
+
+impl std::error::Error for MsgPackBodyTooLarge {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if reading the request body failed midway, e.g. because the connection
+/// broke or timed out.
+///
+/// Responds with `408 Request Timeout` if the read timed out, and with `400 Bad Request`
+/// otherwise, e.g. for a malformed chunked encoding.
+pub struct BodyReadError(BoxError);
+
+impl BodyReadError {
+    pub(crate) fn from_err<E>(err: E) -> Self
+    where
+        E: Into<BoxError>,
+    {
+        Self(err.into())
+    }
+
+    /// Returns `true` if the read timed out.
+    pub fn is_timeout(&self) -> bool {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&*self.0);
+        while let Some(err) = source {
+            let timed_out = err
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|err| err.kind() == std::io::ErrorKind::TimedOut)
+                || err
+                    .downcast_ref::<hyper::Error>()
+                    .is_some_and(hyper::Error::is_timeout);
+            if timed_out {
+                return true;
+            }
+            source = err.source();
+        }
+        false
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        if self.is_timeout() {
+            http::StatusCode::REQUEST_TIMEOUT
+        } else {
+            http::StatusCode::BAD_REQUEST
+        }
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for BodyReadError {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for BodyReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to read the request body: {}", self.0)
+    }
+}
+
+impl std::error::Error for BodyReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if an array, map, string or binary value in the request
+/// body declares a length that the rest of the body cannot hold
+pub struct DeclaredLengthTooLarge {
+    declared: usize,
+    remaining: usize,
+}
+
+impl DeclaredLengthTooLarge {
+    pub(crate) fn new(declared: usize, remaining: usize) -> Self {
+        Self {
+            declared,
+            remaining,
+        }
+    }
+
+    /// Length declared in the header of the value.
+    pub fn declared_len(&self) -> usize {
+        self.declared
+    }
+
+    /// Number of bytes left in the body after the header.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for DeclaredLengthTooLarge {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for DeclaredLengthTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MsgPack value in the request body declares a length of {}, more than the {} bytes left can hold",
+            self.declared, self.remaining
+        )
+    }
+}
+
+impl std::error::Error for DeclaredLengthTooLarge {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if the request body is encoded with a `Content-Encoding`
+/// that is not supported
+pub struct UnsupportedContentEncoding {
+    encoding: String,
+    supported: &'static [&'static str],
+}
+
+impl UnsupportedContentEncoding {
+    pub(crate) fn new(encoding: String, supported: &'static [&'static str]) -> Self {
+        Self {
+            encoding,
+            supported,
+        }
+    }
+
+    /// The unsupported encoding named by the `Content-Encoding` header.
+    pub fn encoding(&self) -> &str {
+        &self.encoding
+    }
+
+    /// The supported encodings.
+    pub fn supported(&self) -> &'static [&'static str] {
+        self.supported
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for UnsupportedContentEncoding {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for UnsupportedContentEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unsupported `Content-Encoding: {}`, expected one of: {}",
+            self.encoding,
+            self.supported.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedContentEncoding {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for [`AcceptableMsgPackMime`](super::AcceptableMsgPackMime) used if the
+/// `Accept` header of the request excludes every MessagePack type
+pub struct NotAcceptable {
+    accept: String,
+    producible: &'static [&'static str],
+}
+
+impl NotAcceptable {
+    pub(crate) fn new(accept: String, producible: &'static [&'static str]) -> Self {
+        Self { accept, producible }
+    }
+
+    /// The `Accept` header of the request.
+    pub fn accept(&self) -> &str {
+        &self.accept
+    }
+
+    /// The media types the response could have been sent as.
+    pub fn producible(&self) -> &'static [&'static str] {
+        self.producible
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::NOT_ACCEPTABLE
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for NotAcceptable {
+    fn into_response(self) -> Response {
+        let mut res = text_response(self.status(), self.body_text());
+        res.headers_mut()
+            .insert(http::header::VARY, HeaderValue::from_static("accept"));
+        res
+    }
+}
+
+impl std::fmt::Display for NotAcceptable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "None of the types in `Accept: {}` can be produced, expected one of: {}",
+            self.accept,
+            self.producible.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for NotAcceptable {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if a string or binary value in the request body is
+/// longer than the limit set in [`MsgPackConfig`](super::MsgPackConfig)
+pub struct ValueTooLong {
+    binary: bool,
+    len: usize,
+    limit: usize,
+}
+
+impl ValueTooLong {
+    pub(crate) fn new(binary: bool, len: usize, limit: usize) -> Self {
+        Self { binary, len, limit }
+    }
+
+    /// Whether the value is a binary value rather than a string.
+    pub fn is_binary(&self) -> bool {
+        self.binary
+    }
+
+    /// Length of the value in bytes, as declared in its header.
+    pub fn declared_len(&self) -> usize {
+        self.len
+    }
+
+    /// The configured limit in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::PAYLOAD_TOO_LARGE
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for ValueTooLong {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for ValueTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MsgPack {} of {} bytes in the request body exceeds the limit of {} bytes",
+            if self.binary {
+                "binary value"
+            } else {
+                "string"
+            },
+            self.len,
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for ValueTooLong {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if arrays and maps in the request body are nested
+/// deeper than the limit set in [`MsgPackLimits`](super::MsgPackLimits)
+pub struct NestingTooDeep {
+    limit: usize,
+}
+
+impl NestingTooDeep {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// The configured limit in levels.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for NestingTooDeep {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for NestingTooDeep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MsgPack values in the request body are nested deeper than the limit of {} levels",
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for NestingTooDeep {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type used if an array or map in the request body has more
+/// elements than the limit set in [`MsgPackLimits`](super::MsgPackLimits)
+pub struct CollectionTooLong {
+    map: bool,
+    len: usize,
+    limit: usize,
+}
+
+impl CollectionTooLong {
+    pub(crate) fn new(map: bool, len: usize, limit: usize) -> Self {
+        Self { map, len, limit }
+    }
+
+    /// Whether the collection is a map rather than an array.
+    pub fn is_map(&self) -> bool {
+        self.map
+    }
+
+    /// Number of elements, or entries of a map, as declared in its header.
+    pub fn declared_len(&self) -> usize {
+        self.len
+    }
+
+    /// The configured limit.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::PAYLOAD_TOO_LARGE
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for CollectionTooLong {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for CollectionTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.map {
+            write!(
+                f,
+                "MsgPack map of {} entries in the request body exceeds the limit of {} entries",
+                self.len, self.limit
+            )
+        } else {
+            write!(
+                f,
+                "MsgPack array of {} elements in the request body exceeds the limit of {} elements",
+                self.len, self.limit
+            )
+        }
+    }
+}
+
+impl std::error::Error for CollectionTooLong {}
+
 #[derive(Debug)]
 #[non_exhaustive]
-pub struct InvalidMsgPackBody(Error);
+/// Rejection type for [`MsgPackHeader`](super::MsgPackHeader) used if the
+/// request does not have the header
+pub struct MissingMsgPackHeader {
+    name: &'static str,
+}
 
-impl InvalidMsgPackBody {
+impl MissingMsgPackHeader {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// Name of the missing header.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for MissingMsgPackHeader {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for MissingMsgPackHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Missing `{}` header with a MsgPack payload", self.name)
+    }
+}
+
+impl std::error::Error for MissingMsgPackHeader {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for [`MsgPackHeader`](super::MsgPackHeader) used if the
+/// header is not valid base64
+pub struct InvalidBase64Header {
+    name: &'static str,
+}
+
+impl InvalidBase64Header {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// Name of the header.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for InvalidBase64Header {
+    fn into_response(self) -> Response {
+        text_response(self.status(), self.body_text())
+    }
+}
+
+impl std::fmt::Display for InvalidBase64Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The `{}` header is not valid base64", self.name)
+    }
+}
+
+impl std::error::Error for InvalidBase64Header {}
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Rejection type for `transcode_json_to_msgpack` used if
+/// the body is not valid JSON
+pub struct InvalidJsonBody {
+    error: Error,
+}
+
+impl InvalidJsonBody {
+    #[cfg_attr(not(feature = "json"), allow(dead_code))]
     pub(crate) fn from_err<E>(err: E) -> Self
     where
         E: Into<BoxError>,
     {
-        Self(Error::new(err))
+        Self {
+            error: Error::new(err),
+        }
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::BAD_REQUEST
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        format!("Failed to parse the request body as JSON: {}", self.error)
     }
 }
 
-impl IntoResponse for InvalidMsgPackBody {
+impl IntoResponse for InvalidJsonBody {
     fn into_response(self) -> Response {
-        let mut res = Response::new(Body::from(format!(
-            "Failed to parse the request body as MsgPack: {}",
-            self.0
-        )));
-        *res.status_mut() = http::StatusCode::BAD_REQUEST;
-        res
+        text_response(self.status(), self.body_text())
     }
 }
 
-impl std::fmt::Display for InvalidMsgPackBody {
+impl std::fmt::Display for InvalidJsonBody {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to parse the request body as MsgPack")
+        write!(f, "Failed to parse the request body as JSON")
     }
 }
 
-impl std::error::Error for InvalidMsgPackBody {
+impl std::error::Error for InvalidJsonBody {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(&self.0)
+        Some(&self.error)
     }
 }
 
 #[derive(Debug)]
 #[non_exhaustive]
-/// Rejection type for [`MsgPack`](super::MsgPack) used if the `Content-Type`
-/// header is missing
-pub struct MissingMsgPackContentType;
+/// Rejection type used if the request body was not received within the timeout set with
+/// `MsgPackConfig::body_timeout`
+pub struct BodyReadTimeout {
+    timeout: Duration,
+}
 
-impl IntoResponse for MissingMsgPackContentType {
+impl BodyReadTimeout {
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// The configured timeout.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::REQUEST_TIMEOUT
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for BodyReadTimeout {
     fn into_response(self) -> Response {
-        let mut res = Response::new(Body::from(
-            "Expected request with `Content-Type: application/msgpack`",
-        ));
-        *res.status_mut() = http::StatusCode::BAD_REQUEST;
-        res
+        text_response(self.status(), self.body_text())
     }
 }
 
-impl std::error::Error for MissingMsgPackContentType {}
-impl std::fmt::Display for MissingMsgPackContentType {
+impl std::fmt::Display for BodyReadTimeout {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Expected request with `Content-Type: application/msgpack`"
+            "The request body was not received within {} ms",
+            self.timeout.as_millis()
         )
     }
 }
 
+impl std::error::Error for BodyReadTimeout {}
+
 #[derive(Debug)]
 #[non_exhaustive]
-pub struct BodyAlreadyExtracted;
+/// Rejection type for [`SignedMsgPack`](super::SignedMsgPack) used if the signature header is
+/// missing or does not match the body
+pub struct InvalidSignature {
+    header: &'static str,
+}
 
-impl IntoResponse for BodyAlreadyExtracted {
+impl InvalidSignature {
+    pub(crate) fn new(header: &'static str) -> Self {
+        Self { header }
+    }
+
+    /// Name of the signature header.
+    pub fn header(&self) -> &'static str {
+        self.header
+    }
+
+    /// The status code of the response sent for this rejection.
+    pub fn status(&self) -> http::StatusCode {
+        http::StatusCode::UNAUTHORIZED
+    }
+
+    /// The body of the response sent for this rejection.
+    pub fn body_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoResponse for InvalidSignature {
     fn into_response(self) -> Response {
-        let mut res = Response::new(Body::from(
-            "Cannot have two request body extractors for a single handler",
-        ));
-        *res.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
-        res
+        text_response(self.status(), self.body_text())
     }
 }
 
-impl std::fmt::Display for BodyAlreadyExtracted {
+impl std::fmt::Display for InvalidSignature {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Cannot have two request body extractors for a single handler"
+            "Missing `{}` header with a valid signature of the body",
+            self.header
         )
     }
 }
 
-impl std::error::Error for BodyAlreadyExtracted {}
+impl std::error::Error for InvalidSignature {}
 
 #[derive(Debug)]
 #[non_exhaustive]
+/// Rejection used for [`MsgPack`](super::MsgPack) and the other extractors of
+/// this crate.
+///
+/// Contains one variant for each way the extraction can fail. It implements
+/// [`std::error::Error`], with [`source`](std::error::Error::source) leading
+/// to the underlying decode error where there is one.
+///
+/// To turn rejections into an application's own error type, implement `From`
+/// for it and take the extraction result in the handler, so `?` converts the
+/// rejection. New variants may be added, so the conversion needs a catch-all
+/// arm:
+///
+/// ```
+/// use axum::{
+///     http::StatusCode,
+///     response::{IntoResponse, Response},
+/// };
+/// use axum_msgpack::{rejection::MsgPackRejection, MsgPack};
+///
+/// enum AppError {
+///     UnsupportedMediaType,
+///     BadRequest(String),
+/// }
+///
+/// impl From<MsgPackRejection> for AppError {
+///     fn from(rejection: MsgPackRejection) -> Self {
+///         match rejection {
+///             MsgPackRejection::MissingMsgPackContentType(_)
+///             | MsgPackRejection::UnsupportedContentEncoding(_) => Self::UnsupportedMediaType,
+///             rejection => Self::BadRequest(rejection.to_string()),
+///         }
+///     }
+/// }
+///
+/// impl IntoResponse for AppError {
+///     fn into_response(self) -> Response {
+///         match self {
+///             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response(),
+///             Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+///         }
+///     }
+/// }
+///
+/// async fn create_user(
+///     payload: Result<MsgPack<String>, MsgPackRejection>,
+/// ) -> Result<String, AppError> {
+///     let MsgPack(name) = payload?;
+///     Ok(name)
+/// }
+/// # let _: axum::Router = axum::Router::new().route("/users", axum::routing::post(create_user));
+/// ```
 pub enum MsgPackRejection {
     InvalidMsgPackBody(InvalidMsgPackBody),
     MissingMsgPackContentType(MissingMsgPackContentType),
     BodyAlreadyExtracted(BodyAlreadyExtracted),
     BytesRejection(BytesRejection),
+    InvalidMsgPackBatch(InvalidMsgPackBatch),
+    SchemaVersionMismatch(SchemaVersionMismatch),
+    TrailingMsgPackBytes(TrailingMsgPackBytes),
+    MsgPackBodyTooLarge(MsgPackBodyTooLarge),
+    BodyReadError(BodyReadError),
+    DeclaredLengthTooLarge(DeclaredLengthTooLarge),
+    UnsupportedContentEncoding(UnsupportedContentEncoding),
+    ValueTooLong(ValueTooLong),
+    NestingTooDeep(NestingTooDeep),
+    CollectionTooLong(CollectionTooLong),
+    MissingMsgPackHeader(MissingMsgPackHeader),
+    InvalidBase64Header(InvalidBase64Header),
+    InvalidJsonBody(InvalidJsonBody),
+    BodyReadTimeout(BodyReadTimeout),
+    InvalidSignature(InvalidSignature),
+    MsgPackEncodeError(MsgPackEncodeError),
 }
 
 impl IntoResponse for MsgPackRejection {
     fn into_response(self) -> Response {
-        match self {
+        let kind = self.kind();
+        let mut res = match self {
             Self::InvalidMsgPackBody(inner) => inner.into_response(),
             Self::MissingMsgPackContentType(inner) => inner.into_response(),
             Self::BodyAlreadyExtracted(inner) => inner.into_response(),
             Self::BytesRejection(inner) => inner.into_response(),
-        }
+            Self::InvalidMsgPackBatch(inner) => inner.into_response(),
+            Self::SchemaVersionMismatch(inner) => inner.into_response(),
+            Self::TrailingMsgPackBytes(inner) => inner.into_response(),
+            Self::MsgPackBodyTooLarge(inner) => inner.into_response(),
+            Self::BodyReadError(inner) => inner.into_response(),
+            Self::DeclaredLengthTooLarge(inner) => inner.into_response(),
+            Self::UnsupportedContentEncoding(inner) => inner.into_response(),
+            Self::ValueTooLong(inner) => inner.into_response(),
+            Self::NestingTooDeep(inner) => inner.into_response(),
+            Self::CollectionTooLong(inner) => inner.into_response(),
+            Self::MissingMsgPackHeader(inner) => inner.into_response(),
+            Self::InvalidBase64Header(inner) => inner.into_response(),
+            Self::InvalidJsonBody(inner) => inner.into_response(),
+            Self::BodyReadTimeout(inner) => inner.into_response(),
+            Self::InvalidSignature(inner) => inner.into_response(),
+            Self::MsgPackEncodeError(inner) => inner.into_response(),
+        };
+        res.extensions_mut().insert(kind);
+        res
     }
 }
 
+/// The variant of a [`MsgPackRejection`], added to the extensions of its response so
+/// [`apply_rejection_statuses`](crate::apply_rejection_statuses) can recognize it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RejectionKind {
+    InvalidMsgPackBody,
+    MissingMsgPackContentType,
+    BodyAlreadyExtracted,
+    BytesRejection,
+    InvalidMsgPackBatch,
+    SchemaVersionMismatch,
+    TrailingMsgPackBytes,
+    MsgPackBodyTooLarge,
+    BodyReadError,
+    DeclaredLengthTooLarge,
+    UnsupportedContentEncoding,
+    ValueTooLong,
+    NestingTooDeep,
+    CollectionTooLong,
+    MissingMsgPackHeader,
+    InvalidBase64Header,
+    InvalidJsonBody,
+    BodyReadTimeout,
+    InvalidSignature,
+    MsgPackEncodeError,
+}
+
 impl From<InvalidMsgPackBody> for MsgPackRejection {
     fn from(inner: InvalidMsgPackBody) -> Self {
         Self::InvalidMsgPackBody(inner)
@@ -138,6 +1228,136 @@ impl From<BodyAlreadyExtracted> for MsgPackRejection {
     }
 }
 
+impl From<InvalidMsgPackBatch> for MsgPackRejection {
+    fn from(inner: InvalidMsgPackBatch) -> Self {
+        Self::InvalidMsgPackBatch(inner)
+    }
+}
+
+impl From<SchemaVersionMismatch> for MsgPackRejection {
+    fn from(inner: SchemaVersionMismatch) -> Self {
+        Self::SchemaVersionMismatch(inner)
+    }
+}
+
+impl From<TrailingMsgPackBytes> for MsgPackRejection {
+    fn from(inner: TrailingMsgPackBytes) -> Self {
+        Self::TrailingMsgPackBytes(inner)
+    }
+}
+
+impl From<MsgPackBodyTooLarge> for MsgPackRejection {
+    fn from(inner: MsgPackBodyTooLarge) -> Self {
+        Self::MsgPackBodyTooLarge(inner)
+    }
+}
+
+impl From<BodyReadError> for MsgPackRejection {
+    fn from(inner: BodyReadError) -> Self {
+        Self::BodyReadError(inner)
+    }
+}
+
+impl From<DeclaredLengthTooLarge> for MsgPackRejection {
+    fn from(inner: DeclaredLengthTooLarge) -> Self {
+        Self::DeclaredLengthTooLarge(inner)
+    }
+}
+
+impl From<UnsupportedContentEncoding> for MsgPackRejection {
+    fn from(inner: UnsupportedContentEncoding) -> Self {
+        Self::UnsupportedContentEncoding(inner)
+    }
+}
+
+impl From<ValueTooLong> for MsgPackRejection {
+    fn from(inner: ValueTooLong) -> Self {
+        Self::ValueTooLong(inner)
+    }
+}
+
+impl From<NestingTooDeep> for MsgPackRejection {
+    fn from(inner: NestingTooDeep) -> Self {
+        Self::NestingTooDeep(inner)
+    }
+}
+
+impl From<CollectionTooLong> for MsgPackRejection {
+    fn from(inner: CollectionTooLong) -> Self {
+        Self::CollectionTooLong(inner)
+    }
+}
+
+impl From<MissingMsgPackHeader> for MsgPackRejection {
+    fn from(inner: MissingMsgPackHeader) -> Self {
+        Self::MissingMsgPackHeader(inner)
+    }
+}
+
+impl From<InvalidBase64Header> for MsgPackRejection {
+    fn from(inner: InvalidBase64Header) -> Self {
+        Self::InvalidBase64Header(inner)
+    }
+}
+
+impl From<InvalidJsonBody> for MsgPackRejection {
+    fn from(inner: InvalidJsonBody) -> Self {
+        Self::InvalidJsonBody(inner)
+    }
+}
+
+impl From<BodyReadTimeout> for MsgPackRejection {
+    fn from(inner: BodyReadTimeout) -> Self {
+        Self::BodyReadTimeout(inner)
+    }
+}
+
+impl From<InvalidSignature> for MsgPackRejection {
+    fn from(inner: InvalidSignature) -> Self {
+        Self::InvalidSignature(inner)
+    }
+}
+
+impl From<MsgPackEncodeError> for MsgPackRejection {
+    fn from(inner: MsgPackEncodeError) -> Self {
+        Self::MsgPackEncodeError(inner)
+    }
+}
+
+/// Converts errors of manual decoding into an [`InvalidMsgPackBody`] rejection, so handlers can
+/// propagate them with `?`:
+///
+/// ```no_run
+/// use axum::{body::Bytes, routing::post, Router};
+/// use axum_msgpack::{rejection::MsgPackRejection, MsgPack};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     kind: String,
+/// }
+///
+/// async fn webhook(body: Bytes) -> Result<MsgPack<String>, MsgPackRejection> {
+///     let event: Event = rmp_serde::from_slice(&body)?;
+///     Ok(MsgPack(event.kind))
+/// }
+///
+/// let app: Router = Router::new().route("/webhook", post(webhook));
+/// ```
+impl From<rmp_serde::decode::Error> for MsgPackRejection {
+    fn from(err: rmp_serde::decode::Error) -> Self {
+        InvalidMsgPackBody::from_err(err).into()
+    }
+}
+
+/// Converts errors of manual encoding into a [`MsgPackEncodeError`], which responds with
+/// `500 Internal Server Error`.
+impl From<rmp_serde::encode::Error> for MsgPackRejection {
+    fn from(err: rmp_serde::encode::Error) -> Self {
+        MsgPackEncodeError::from(err).into()
+    }
+}
+
 impl std::fmt::Display for MsgPackRejection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -145,6 +1365,429 @@ impl std::fmt::Display for MsgPackRejection {
             Self::MissingMsgPackContentType(inner) => write!(f, "{}", inner),
             Self::BodyAlreadyExtracted(inner) => write!(f, "{}", inner),
             Self::BytesRejection(inner) => write!(f, "{}", inner),
+            Self::InvalidMsgPackBatch(inner) => write!(f, "{}", inner),
+            Self::SchemaVersionMismatch(inner) => write!(f, "{}", inner),
+            Self::TrailingMsgPackBytes(inner) => write!(f, "{}", inner),
+            Self::MsgPackBodyTooLarge(inner) => write!(f, "{}", inner),
+            Self::BodyReadError(inner) => write!(f, "{}", inner),
+            Self::DeclaredLengthTooLarge(inner) => write!(f, "{}", inner),
+            Self::UnsupportedContentEncoding(inner) => write!(f, "{}", inner),
+            Self::ValueTooLong(inner) => write!(f, "{}", inner),
+            Self::NestingTooDeep(inner) => write!(f, "{}", inner),
+            Self::CollectionTooLong(inner) => write!(f, "{}", inner),
+            Self::MissingMsgPackHeader(inner) => write!(f, "{}", inner),
+            Self::InvalidBase64Header(inner) => write!(f, "{}", inner),
+            Self::InvalidJsonBody(inner) => write!(f, "{}", inner),
+            Self::BodyReadTimeout(inner) => write!(f, "{}", inner),
+            Self::InvalidSignature(inner) => write!(f, "{}", inner),
+            Self::MsgPackEncodeError(inner) => write!(f, "{}", inner),
+        }
+    }
+}
+
+impl MsgPackRejection {
+    /// The status code of the response sent for this rejection.
+    ///
+    /// Together with [`body_text`](Self::body_text), lets an application turn the rejection into
+    /// its own error type without rendering and parsing the response again:
+    ///
+    /// ```
+    /// use axum::http::StatusCode;
+    /// use axum_msgpack::rejection::MsgPackRejection;
+    ///
+    /// struct ApiError {
+    ///     status: StatusCode,
+    ///     message: String,
+    /// }
+    ///
+    /// impl From<MsgPackRejection> for ApiError {
+    ///     fn from(rejection: MsgPackRejection) -> Self {
+    ///         Self {
+    ///             status: rejection.status(),
+    ///             message: rejection.body_text(),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn status(&self) -> http::StatusCode {
+        match self {
+            Self::InvalidMsgPackBody(inner) => inner.status(),
+            Self::MissingMsgPackContentType(inner) => inner.status(),
+            Self::BodyAlreadyExtracted(inner) => inner.status(),
+            Self::BytesRejection(inner) => inner.status(),
+            Self::InvalidMsgPackBatch(inner) => inner.status(),
+            Self::SchemaVersionMismatch(inner) => inner.status(),
+            Self::TrailingMsgPackBytes(inner) => inner.status(),
+            Self::MsgPackBodyTooLarge(inner) => inner.status(),
+            Self::BodyReadError(inner) => inner.status(),
+            Self::DeclaredLengthTooLarge(inner) => inner.status(),
+            Self::UnsupportedContentEncoding(inner) => inner.status(),
+            Self::ValueTooLong(inner) => inner.status(),
+            Self::NestingTooDeep(inner) => inner.status(),
+            Self::CollectionTooLong(inner) => inner.status(),
+            Self::MissingMsgPackHeader(inner) => inner.status(),
+            Self::InvalidBase64Header(inner) => inner.status(),
+            Self::InvalidJsonBody(inner) => inner.status(),
+            Self::BodyReadTimeout(inner) => inner.status(),
+            Self::InvalidSignature(inner) => inner.status(),
+            Self::MsgPackEncodeError(inner) => inner.status(),
+        }
+    }
+
+    /// The body of the response sent for this rejection.
+    ///
+    /// Differs from the [`Display`](std::fmt::Display) output for some rejections, e.g. by
+    /// adding a hint to [`InvalidMsgPackBody`].
+    pub fn body_text(&self) -> String {
+        match self {
+            Self::InvalidMsgPackBody(inner) => inner.body_text(),
+            Self::MissingMsgPackContentType(inner) => inner.body_text(),
+            Self::BodyAlreadyExtracted(inner) => inner.body_text(),
+            Self::BytesRejection(inner) => inner.body_text(),
+            Self::InvalidMsgPackBatch(inner) => inner.body_text(),
+            Self::SchemaVersionMismatch(inner) => inner.body_text(),
+            Self::TrailingMsgPackBytes(inner) => inner.body_text(),
+            Self::MsgPackBodyTooLarge(inner) => inner.body_text(),
+            Self::BodyReadError(inner) => inner.body_text(),
+            Self::DeclaredLengthTooLarge(inner) => inner.body_text(),
+            Self::UnsupportedContentEncoding(inner) => inner.body_text(),
+            Self::ValueTooLong(inner) => inner.body_text(),
+            Self::NestingTooDeep(inner) => inner.body_text(),
+            Self::CollectionTooLong(inner) => inner.body_text(),
+            Self::MissingMsgPackHeader(inner) => inner.body_text(),
+            Self::InvalidBase64Header(inner) => inner.body_text(),
+            Self::InvalidJsonBody(inner) => inner.body_text(),
+            Self::BodyReadTimeout(inner) => inner.body_text(),
+            Self::InvalidSignature(inner) => inner.body_text(),
+            Self::MsgPackEncodeError(inner) => inner.body_text(),
+        }
+    }
+
+    /// Converts into a response like [`IntoResponse::into_response`], with the status code
+    /// that `statuses` configures for this variant.
+    pub fn into_response_with(self, statuses: &RejectionStatusConfig) -> Response {
+        let status = statuses.status(self.kind());
+        let mut res = self.into_response();
+        if let Some(status) = status {
+            *res.status_mut() = status;
+        }
+        res
+    }
+
+    pub(crate) fn kind(&self) -> RejectionKind {
+        match self {
+            Self::InvalidMsgPackBody(_) => RejectionKind::InvalidMsgPackBody,
+            Self::MissingMsgPackContentType(_) => RejectionKind::MissingMsgPackContentType,
+            Self::BodyAlreadyExtracted(_) => RejectionKind::BodyAlreadyExtracted,
+            Self::BytesRejection(_) => RejectionKind::BytesRejection,
+            Self::InvalidMsgPackBatch(_) => RejectionKind::InvalidMsgPackBatch,
+            Self::SchemaVersionMismatch(_) => RejectionKind::SchemaVersionMismatch,
+            Self::TrailingMsgPackBytes(_) => RejectionKind::TrailingMsgPackBytes,
+            Self::MsgPackBodyTooLarge(_) => RejectionKind::MsgPackBodyTooLarge,
+            Self::BodyReadError(_) => RejectionKind::BodyReadError,
+            Self::DeclaredLengthTooLarge(_) => RejectionKind::DeclaredLengthTooLarge,
+            Self::UnsupportedContentEncoding(_) => RejectionKind::UnsupportedContentEncoding,
+            Self::ValueTooLong(_) => RejectionKind::ValueTooLong,
+            Self::NestingTooDeep(_) => RejectionKind::NestingTooDeep,
+            Self::CollectionTooLong(_) => RejectionKind::CollectionTooLong,
+            Self::MissingMsgPackHeader(_) => RejectionKind::MissingMsgPackHeader,
+            Self::InvalidBase64Header(_) => RejectionKind::InvalidBase64Header,
+            Self::InvalidJsonBody(_) => RejectionKind::InvalidJsonBody,
+            Self::BodyReadTimeout(_) => RejectionKind::BodyReadTimeout,
+            Self::InvalidSignature(_) => RejectionKind::InvalidSignature,
+            Self::MsgPackEncodeError(_) => RejectionKind::MsgPackEncodeError,
+        }
+    }
+}
+
+/// Converts the rejection into the status code and body of its response, see
+/// [`MsgPackRejection::status`] and [`MsgPackRejection::body_text`].
+///
+/// Folds rejections into an application-wide error type with `?`, e.g. one that renders every
+/// error in the same JSON envelope:
+///
+/// ```
+/// use axum::{
+///     http::StatusCode,
+///     response::{IntoResponse, Response},
+/// };
+/// use axum_msgpack::{rejection::MsgPackRejection, MsgPack};
+///
+/// struct ApiError {
+///     status: StatusCode,
+///     message: String,
+/// }
+///
+/// impl From<MsgPackRejection> for ApiError {
+///     fn from(rejection: MsgPackRejection) -> Self {
+///         let (status, message) = rejection.into();
+///         Self { status, message }
+///     }
+/// }
+///
+/// impl IntoResponse for ApiError {
+///     fn into_response(self) -> Response {
+///         let body = format!(r#"{{"error":{:?}}}"#, self.message);
+///         (self.status, body).into_response()
+///     }
+/// }
+///
+/// async fn create_user(
+///     payload: Result<MsgPack<String>, MsgPackRejection>,
+/// ) -> Result<String, ApiError> {
+///     let MsgPack(name) = payload?;
+///     Ok(name)
+/// }
+/// # let _: axum::Router = axum::Router::new().route("/users", axum::routing::post(create_user));
+/// ```
+impl From<MsgPackRejection> for (http::StatusCode, String) {
+    fn from(rejection: MsgPackRejection) -> Self {
+        (rejection.status(), rejection.body_text())
+    }
+}
+
+/// Status codes for the variants of [`MsgPackRejection`].
+///
+/// APIs differ in the status they use for bodies that fail to decode, e.g. `422 Unprocessable
+/// Entity` instead of `400 Bad Request`. Variants without a configured status keep the status of
+/// their own response:
+///
+/// | Variant                      | Default status                                                   |
+/// |------------------------------|------------------------------------------------------------------|
+/// | `InvalidMsgPackBody`         | `400 Bad Request`                                                |
+/// | `MissingMsgPackContentType`  | `400 Bad Request`                                                |
+/// | `BodyAlreadyExtracted`       | `500 Internal Server Error`                                      |
+/// | `BytesRejection`             | depends on the cause, e.g. `413 Payload Too Large`               |
+/// | `InvalidMsgPackBatch`        | `400 Bad Request`                                                |
+/// | `SchemaVersionMismatch`      | `400 Bad Request`                                                |
+/// | `TrailingMsgPackBytes`       | `400 Bad Request`                                                |
+/// | `MsgPackBodyTooLarge`        | `413 Payload Too Large`                                          |
+/// | `BodyReadError`              | `408 Request Timeout` on a timeout, else `400 Bad Request`       |
+/// | `DeclaredLengthTooLarge`     | `400 Bad Request`                                                |
+/// | `UnsupportedContentEncoding` | `415 Unsupported Media Type`                                     |
+/// | `ValueTooLong`               | `413 Payload Too Large`                                          |
+/// | `NestingTooDeep`             | `400 Bad Request`                                                |
+/// | `CollectionTooLong`          | `413 Payload Too Large`                                          |
+/// | `MissingMsgPackHeader`       | `400 Bad Request`                                                |
+/// | `InvalidBase64Header`        | `400 Bad Request`                                                |
+/// | `InvalidJsonBody`            | `400 Bad Request`                                                |
+/// | `BodyReadTimeout`            | `408 Request Timeout`                                            |
+/// | `InvalidSignature`           | `401 Unauthorized`                                               |
+/// | `MsgPackEncodeError`         | `500 Internal Server Error`                                      |
+///
+/// A rejection is created while the request is extracted, without access to the application
+/// state, so its [`IntoResponse`] implementation cannot consult the configuration. Either carry
+/// the configuration in the [`MsgPackConfig`](crate::MsgPackConfig) of the request with
+/// [`MsgPackConfig::rejection_statuses`](crate::MsgPackConfig::rejection_statuses), and apply it
+/// to every rejection response with the
+/// [`apply_rejection_statuses`](crate::apply_rejection_statuses) middleware, or keep the
+/// configuration in your state, take the rejection as the `Err` of a `Result` extractor, and
+/// respond with [`MsgPackRejection::into_response_with`]:
+///
+/// ```no_run
+/// use axum::{
+///     extract::State,
+///     http::StatusCode,
+///     response::{IntoResponse, Response},
+///     routing::post,
+///     Router,
+/// };
+/// use axum_msgpack::{
+///     rejection::{MsgPackRejection, RejectionStatusConfig},
+///     MsgPack,
+/// };
+///
+/// async fn create_user(
+///     State(statuses): State<RejectionStatusConfig>,
+///     payload: Result<MsgPack<String>, MsgPackRejection>,
+/// ) -> Response {
+///     let MsgPack(payload) = match payload {
+///         Ok(payload) => payload,
+///         Err(rejection) => return rejection.into_response_with(&statuses),
+///     };
+///     // ...
+///     # unimplemented!()
+/// }
+///
+/// let statuses = RejectionStatusConfig::new().invalid_body(StatusCode::UNPROCESSABLE_ENTITY);
+/// let app: Router = Router::new()
+///     .route("/users", post(create_user))
+///     .with_state(statuses);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RejectionStatusConfig {
+    invalid_body: Option<http::StatusCode>,
+    missing_content_type: Option<http::StatusCode>,
+    body_already_extracted: Option<http::StatusCode>,
+    bytes_rejection: Option<http::StatusCode>,
+    invalid_batch: Option<http::StatusCode>,
+    schema_version_mismatch: Option<http::StatusCode>,
+    trailing_bytes: Option<http::StatusCode>,
+    body_too_large: Option<http::StatusCode>,
+    body_read_error: Option<http::StatusCode>,
+    declared_length_too_large: Option<http::StatusCode>,
+    unsupported_content_encoding: Option<http::StatusCode>,
+    value_too_long: Option<http::StatusCode>,
+    nesting_too_deep: Option<http::StatusCode>,
+    collection_too_long: Option<http::StatusCode>,
+    missing_header: Option<http::StatusCode>,
+    invalid_base64_header: Option<http::StatusCode>,
+    invalid_json_body: Option<http::StatusCode>,
+    body_read_timeout: Option<http::StatusCode>,
+    invalid_signature: Option<http::StatusCode>,
+    encode_error: Option<http::StatusCode>,
+}
+
+impl RejectionStatusConfig {
+    /// Creates a configuration that keeps every status.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the status of [`MsgPackRejection::InvalidMsgPackBody`].
+    pub fn invalid_body(mut self, status: http::StatusCode) -> Self {
+        self.invalid_body = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::MissingMsgPackContentType`].
+    pub fn missing_content_type(mut self, status: http::StatusCode) -> Self {
+        self.missing_content_type = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::BodyAlreadyExtracted`].
+    pub fn body_already_extracted(mut self, status: http::StatusCode) -> Self {
+        self.body_already_extracted = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::BytesRejection`], which otherwise depends on the
+    /// cause, e.g. `413 Payload Too Large` if the body exceeds axum's limit.
+    pub fn bytes_rejection(mut self, status: http::StatusCode) -> Self {
+        self.bytes_rejection = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::InvalidMsgPackBatch`].
+    pub fn invalid_batch(mut self, status: http::StatusCode) -> Self {
+        self.invalid_batch = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::SchemaVersionMismatch`].
+    pub fn schema_version_mismatch(mut self, status: http::StatusCode) -> Self {
+        self.schema_version_mismatch = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::TrailingMsgPackBytes`].
+    pub fn trailing_bytes(mut self, status: http::StatusCode) -> Self {
+        self.trailing_bytes = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::MsgPackBodyTooLarge`].
+    pub fn body_too_large(mut self, status: http::StatusCode) -> Self {
+        self.body_too_large = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::BodyReadError`], which otherwise depends on the
+    /// cause.
+    pub fn body_read_error(mut self, status: http::StatusCode) -> Self {
+        self.body_read_error = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::DeclaredLengthTooLarge`].
+    pub fn declared_length_too_large(mut self, status: http::StatusCode) -> Self {
+        self.declared_length_too_large = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::UnsupportedContentEncoding`].
+    pub fn unsupported_content_encoding(mut self, status: http::StatusCode) -> Self {
+        self.unsupported_content_encoding = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::ValueTooLong`].
+    pub fn value_too_long(mut self, status: http::StatusCode) -> Self {
+        self.value_too_long = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::NestingTooDeep`].
+    pub fn nesting_too_deep(mut self, status: http::StatusCode) -> Self {
+        self.nesting_too_deep = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::CollectionTooLong`].
+    pub fn collection_too_long(mut self, status: http::StatusCode) -> Self {
+        self.collection_too_long = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::MissingMsgPackHeader`].
+    pub fn missing_header(mut self, status: http::StatusCode) -> Self {
+        self.missing_header = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::InvalidBase64Header`].
+    pub fn invalid_base64_header(mut self, status: http::StatusCode) -> Self {
+        self.invalid_base64_header = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::InvalidJsonBody`].
+    pub fn invalid_json_body(mut self, status: http::StatusCode) -> Self {
+        self.invalid_json_body = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::BodyReadTimeout`].
+    pub fn body_read_timeout(mut self, status: http::StatusCode) -> Self {
+        self.body_read_timeout = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::InvalidSignature`].
+    pub fn invalid_signature(mut self, status: http::StatusCode) -> Self {
+        self.invalid_signature = Some(status);
+        self
+    }
+
+    /// Sets the status of [`MsgPackRejection::MsgPackEncodeError`].
+    pub fn encode_error(mut self, status: http::StatusCode) -> Self {
+        self.encode_error = Some(status);
+        self
+    }
+
+    pub(crate) fn status(&self, kind: RejectionKind) -> Option<http::StatusCode> {
+        match kind {
+            RejectionKind::InvalidMsgPackBody => self.invalid_body,
+            RejectionKind::MissingMsgPackContentType => self.missing_content_type,
+            RejectionKind::BodyAlreadyExtracted => self.body_already_extracted,
+            RejectionKind::BytesRejection => self.bytes_rejection,
+            RejectionKind::InvalidMsgPackBatch => self.invalid_batch,
+            RejectionKind::SchemaVersionMismatch => self.schema_version_mismatch,
+            RejectionKind::TrailingMsgPackBytes => self.trailing_bytes,
+            RejectionKind::MsgPackBodyTooLarge => self.body_too_large,
+            RejectionKind::BodyReadError => self.body_read_error,
+            RejectionKind::DeclaredLengthTooLarge => self.declared_length_too_large,
+            RejectionKind::UnsupportedContentEncoding => self.unsupported_content_encoding,
+            RejectionKind::ValueTooLong => self.value_too_long,
+            RejectionKind::NestingTooDeep => self.nesting_too_deep,
+            RejectionKind::CollectionTooLong => self.collection_too_long,
+            RejectionKind::MissingMsgPackHeader => self.missing_header,
+            RejectionKind::InvalidBase64Header => self.invalid_base64_header,
+            RejectionKind::InvalidJsonBody => self.invalid_json_body,
+            RejectionKind::BodyReadTimeout => self.body_read_timeout,
+            RejectionKind::InvalidSignature => self.invalid_signature,
+            RejectionKind::MsgPackEncodeError => self.encode_error,
         }
     }
 }
@@ -156,6 +1799,241 @@ impl std::error::Error for MsgPackRejection {
             Self::MissingMsgPackContentType(inner) => Some(inner),
             Self::BodyAlreadyExtracted(inner) => Some(inner),
             Self::BytesRejection(inner) => Some(inner),
+            Self::InvalidMsgPackBatch(inner) => Some(inner),
+            Self::SchemaVersionMismatch(inner) => Some(inner),
+            Self::TrailingMsgPackBytes(inner) => Some(inner),
+            Self::MsgPackBodyTooLarge(inner) => Some(inner),
+            Self::BodyReadError(inner) => Some(inner),
+            Self::DeclaredLengthTooLarge(inner) => Some(inner),
+            Self::UnsupportedContentEncoding(inner) => Some(inner),
+            Self::ValueTooLong(inner) => Some(inner),
+            Self::NestingTooDeep(inner) => Some(inner),
+            Self::CollectionTooLong(inner) => Some(inner),
+            Self::MissingMsgPackHeader(inner) => Some(inner),
+            Self::InvalidBase64Header(inner) => Some(inner),
+            Self::InvalidJsonBody(inner) => Some(inner),
+            Self::BodyReadTimeout(inner) => Some(inner),
+            Self::InvalidSignature(inner) => Some(inner),
+            Self::MsgPackEncodeError(inner) => Some(inner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error as StdError, time::Duration};
+
+    use axum::{
+        body::{to_bytes, Body, Bytes},
+        extract::FromRequest,
+        http::{HeaderValue, Request, StatusCode},
+        response::IntoResponse,
+    };
+
+    use super::{
+        BodyAlreadyExtracted, BodyReadError, BodyReadTimeout, CollectionTooLong, ContentTypeMatch,
+        DeclaredLengthTooLarge, InvalidBase64Header, InvalidJsonBody, InvalidMsgPackBatch,
+        InvalidMsgPackBody, InvalidSignature, MissingMsgPackContentType, MissingMsgPackHeader,
+        MsgPackBodyTooLarge, MsgPackRejection, NestingTooDeep, RejectionStatusConfig,
+        SchemaVersionMismatch, TrailingMsgPackBytes, UnsupportedContentEncoding, ValueTooLong,
+    };
+    use crate::error::Error;
+
+    fn decode_error() -> rmp_serde::decode::Error {
+        rmp_serde::from_slice::<String>(&[0xc1]).unwrap_err()
+    }
+
+    #[test]
+    fn downcasts_boxed_rejection() {
+        let rejection: MsgPackRejection = InvalidMsgPackBody::from_err(decode_error()).into();
+        let boxed: Box<dyn StdError + Send + Sync> = Box::new(rejection);
+
+        let rejection = boxed
+            .downcast_ref::<MsgPackRejection>()
+            .expect("Expected a MsgPackRejection");
+        assert!(matches!(rejection, MsgPackRejection::InvalidMsgPackBody(_)));
+    }
+
+    #[test]
+    fn source_chain_reaches_decode_error() {
+        let rejection: MsgPackRejection = InvalidMsgPackBody::from_err(decode_error()).into();
+
+        let mut source = rejection.source();
+        let mut found = false;
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<rmp_serde::decode::Error>() {
+                assert_eq!(err.to_string(), decode_error().to_string());
+                found = true;
+            }
+            source = err.source();
+        }
+        assert!(found, "Expected the rmp-serde error in the source chain");
+    }
+
+    #[test]
+    fn overrides_configured_statuses_only() {
+        let statuses = RejectionStatusConfig::new().invalid_body(StatusCode::UNPROCESSABLE_ENTITY);
+
+        let rejection: MsgPackRejection = InvalidMsgPackBody::from_err(decode_error()).into();
+        let res = rejection.into_response_with(&statuses);
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let rejection: MsgPackRejection =
+            MissingMsgPackContentType::new(ContentTypeMatch::MissingHeader).into();
+        let res = rejection.into_response_with(&statuses);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let rejection: MsgPackRejection = InvalidMsgPackBody::from_err(decode_error()).into();
+        let res = rejection.into_response_with(&RejectionStatusConfig::new());
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let rejection: MsgPackRejection = InvalidMsgPackBody::from_err(decode_error()).into();
+        assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// One rejection of every variant.
+    async fn every_variant() -> Vec<MsgPackRejection> {
+        let failing = futures_util::stream::once(async {
+            Err::<Bytes, _>(std::io::Error::other("connection reset"))
+        });
+        let bytes_rejection = Bytes::from_request(Request::new(Body::from_stream(failing)), &())
+            .await
+            .unwrap_err();
+
+        vec![
+            InvalidMsgPackBody::from_err(decode_error()).into(),
+            MissingMsgPackContentType::new(ContentTypeMatch::WrongSubtype).into(),
+            BodyAlreadyExtracted.into(),
+            bytes_rejection.into(),
+            InvalidMsgPackBatch::new(vec![(1, Error::new(decode_error()))]).into(),
+            SchemaVersionMismatch::new("x-schema-version", 1..=2, Some(&HeaderValue::from(3)))
+                .into(),
+            TrailingMsgPackBytes::new(2).into(),
+            MsgPackBodyTooLarge::new(10, 5).into(),
+            BodyReadError::from_err(std::io::Error::other("connection reset")).into(),
+            DeclaredLengthTooLarge::new(10, 5).into(),
+            UnsupportedContentEncoding::new("gzip".into(), &["identity"]).into(),
+            ValueTooLong::new(false, 10, 5).into(),
+            NestingTooDeep::new(5).into(),
+            CollectionTooLong::new(true, 10, 5).into(),
+            MissingMsgPackHeader::new("x-payload").into(),
+            InvalidBase64Header::new("x-payload").into(),
+            InvalidJsonBody::from_err("expected value at line 1 column 1").into(),
+            BodyReadTimeout::new(Duration::from_secs(5)).into(),
+            InvalidSignature::new("x-signature").into(),
+            rmp_serde::encode::Error::Syntax("unsupported value".into()).into(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn displays_every_variant() {
+        let rejections = every_variant().await;
+
+        let mut messages = Vec::new();
+        for rejection in &rejections {
+            // Lists every variant, so new ones have to be added above.
+            let inner = match rejection {
+                MsgPackRejection::InvalidMsgPackBody(inner) => inner.to_string(),
+                MsgPackRejection::MissingMsgPackContentType(inner) => inner.to_string(),
+                MsgPackRejection::BodyAlreadyExtracted(inner) => inner.to_string(),
+                MsgPackRejection::BytesRejection(inner) => inner.to_string(),
+                MsgPackRejection::InvalidMsgPackBatch(inner) => inner.to_string(),
+                MsgPackRejection::SchemaVersionMismatch(inner) => inner.to_string(),
+                MsgPackRejection::TrailingMsgPackBytes(inner) => inner.to_string(),
+                MsgPackRejection::MsgPackBodyTooLarge(inner) => inner.to_string(),
+                MsgPackRejection::BodyReadError(inner) => inner.to_string(),
+                MsgPackRejection::DeclaredLengthTooLarge(inner) => inner.to_string(),
+                MsgPackRejection::UnsupportedContentEncoding(inner) => inner.to_string(),
+                MsgPackRejection::ValueTooLong(inner) => inner.to_string(),
+                MsgPackRejection::NestingTooDeep(inner) => inner.to_string(),
+                MsgPackRejection::CollectionTooLong(inner) => inner.to_string(),
+                MsgPackRejection::MissingMsgPackHeader(inner) => inner.to_string(),
+                MsgPackRejection::InvalidBase64Header(inner) => inner.to_string(),
+                MsgPackRejection::InvalidJsonBody(inner) => inner.to_string(),
+                MsgPackRejection::BodyReadTimeout(inner) => inner.to_string(),
+                MsgPackRejection::InvalidSignature(inner) => inner.to_string(),
+                MsgPackRejection::MsgPackEncodeError(inner) => inner.to_string(),
+            };
+            assert!(!inner.is_empty(), "{:?}", rejection);
+            assert_eq!(rejection.to_string(), inner);
+            messages.push(inner);
+        }
+
+        messages.sort();
+        messages.dedup();
+        assert_eq!(messages.len(), rejections.len(), "{:?}", messages);
+    }
+
+    #[tokio::test]
+    async fn converts_into_status_and_message() {
+        let statuses = [
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            StatusCode::BAD_REQUEST,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST,
+            StatusCode::REQUEST_TIMEOUT,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ];
+        let rejections = every_variant().await;
+        assert_eq!(rejections.len(), statuses.len());
+
+        for (rejection, expected) in rejections.into_iter().zip(statuses) {
+            let body_text = rejection.body_text();
+            let debug = format!("{:?}", rejection);
+            let (status, message): (StatusCode, String) = rejection.into();
+            assert_eq!(status, expected, "{}", debug);
+            assert_eq!(message, body_text);
+            assert!(!message.is_empty(), "{}", debug);
+        }
+    }
+
+    #[tokio::test]
+    async fn responds_with_status_and_body_text() {
+        for rejection in every_variant().await {
+            let status = rejection.status();
+            let body_text = rejection.body_text();
+            let res = rejection.into_response();
+            assert_eq!(res.status(), status);
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(body, body_text);
+        }
+
+        let rejection = InvalidMsgPackBody::from_err(decode_error()).with_body(b"{}");
+        assert!(rejection
+            .body_text()
+            .ends_with("(body looks like JSON, not MessagePack)"));
+        assert_eq!(
+            BodyReadError::from_err(std::io::Error::from(std::io::ErrorKind::TimedOut)).status(),
+            StatusCode::REQUEST_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn converts_rmp_serde_errors() {
+        let rejection = MsgPackRejection::from(decode_error());
+        assert!(matches!(rejection, MsgPackRejection::InvalidMsgPackBody(_)));
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+
+        let rejection = MsgPackRejection::from(rmp_serde::encode::Error::Syntax("nope".into()));
+        match &rejection {
+            MsgPackRejection::MsgPackEncodeError(inner) => {
+                assert_eq!(inner.error().to_string(), "nope");
+            }
+            other => unreachable!("Expected encode error, got: {:?}", other),
         }
+        assert_eq!(rejection.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 }
@@ -0,0 +1,164 @@
+use crate::{rejection::RejectionKind, MsgPackConfig};
+use axum::{extract::Request, middleware::Next, response::Response};
+
+/// Middleware that sets the status codes of rejection responses as the [`MsgPackConfig`] of the
+/// request configures.
+///
+/// Style guides map errors to different status codes, e.g. `415 Unsupported Media Type` for a
+/// missing content type or `422 Unprocessable Entity` for a body that does not match the
+/// expected type. Set the status codes with [`MsgPackConfig::rejection_statuses`], and add this
+/// middleware with [`from_fn`](axum::middleware::from_fn) inside the layer that adds the
+/// configuration, so it sees the configuration of the request:
+///
+/// ```no_run
+/// use axum::{http::StatusCode, middleware, routing::post, Extension, Router};
+/// use axum_msgpack::{
+///     apply_rejection_statuses, rejection::RejectionStatusConfig, MsgPack, MsgPackConfig,
+/// };
+///
+/// async fn create_user(MsgPack(payload): MsgPack<String>) {}
+///
+/// let statuses = RejectionStatusConfig::new()
+///     .missing_content_type(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+///     .invalid_body(StatusCode::UNPROCESSABLE_ENTITY);
+/// let app: Router = Router::new()
+///     .route("/users", post(create_user))
+///     .layer(middleware::from_fn(apply_rejection_statuses))
+///     .layer(Extension(MsgPackConfig::new().rejection_statuses(statuses)));
+/// ```
+///
+/// Only responses created by [`MsgPackRejection::into_response`] are changed, so a handler
+/// returning its own `400 Bad Request` keeps it. Variants without a configured status, and
+/// requests without a configuration, keep the status of the rejection.
+///
+/// [`MsgPackRejection::into_response`]: axum::response::IntoResponse::into_response
+pub async fn apply_rejection_statuses(req: Request, next: Next) -> Response {
+    let statuses = MsgPackConfig::of(&req).rejection_status_config().cloned();
+    let mut res = next.run(req).await;
+
+    let Some(statuses) = statuses else {
+        return res;
+    };
+    let status = res
+        .extensions()
+        .get::<RejectionKind>()
+        .and_then(|kind| statuses.status(*kind));
+    if let Some(status) = status {
+        *res.status_mut() = status;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        middleware,
+        routing::post,
+        Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    use super::apply_rejection_statuses;
+    use crate::{rejection::RejectionStatusConfig, MsgPack, MsgPackConfig, MsgPackLimits};
+
+    fn app(config: MsgPackConfig) -> Router {
+        Router::new()
+            .route(
+                "/users",
+                post(|MsgPack(name): MsgPack<String>| async move { name }),
+            )
+            .route(
+                "/failing",
+                post(|| async { (StatusCode::BAD_REQUEST, "handler error") }),
+            )
+            .layer(middleware::from_fn(apply_rejection_statuses))
+            .layer(Extension(config))
+    }
+
+    fn policy() -> MsgPackConfig {
+        MsgPackConfig::new()
+            .limits(MsgPackLimits::unlimited().max_body_len(16))
+            .rejection_statuses(
+                RejectionStatusConfig::new()
+                    .missing_content_type(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+                    .invalid_body(StatusCode::UNPROCESSABLE_ENTITY)
+                    .body_too_large(StatusCode::BAD_REQUEST),
+            )
+    }
+
+    async fn send(
+        config: MsgPackConfig,
+        uri: &str,
+        content_type: Option<&'static str>,
+        body: Vec<u8>,
+    ) -> StatusCode {
+        let mut request = Request::post(uri);
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        let res = app(config)
+            .oneshot(request.body(Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        res.status()
+    }
+
+    #[tokio::test]
+    async fn overrides_configured_statuses() {
+        let msgpack = Some("application/msgpack");
+        for (content_type, body, expected) in [
+            (
+                None,
+                rmp_serde::to_vec("steve").unwrap(),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            (
+                msgpack,
+                rmp_serde::to_vec(&42).unwrap(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                msgpack,
+                rmp_serde::to_vec(&"a".repeat(32)).unwrap(),
+                StatusCode::BAD_REQUEST,
+            ),
+            (msgpack, vec![0xc1], StatusCode::UNPROCESSABLE_ENTITY),
+            (msgpack, rmp_serde::to_vec("steve").unwrap(), StatusCode::OK),
+        ] {
+            assert_eq!(send(policy(), "/users", content_type, body).await, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_other_statuses() {
+        let msgpack = Some("application/msgpack");
+        let without_policy =
+            MsgPackConfig::new().limits(MsgPackLimits::unlimited().max_body_len(16));
+        for (config, body, expected) in [
+            (
+                without_policy.clone(),
+                rmp_serde::to_vec(&42).unwrap(),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                without_policy,
+                rmp_serde::to_vec(&"a".repeat(32)).unwrap(),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+            // Trailing bytes are not configured.
+            (
+                policy().strict(true),
+                vec![0xa1, b'a', 0xc0],
+                StatusCode::BAD_REQUEST,
+            ),
+        ] {
+            assert_eq!(send(config, "/users", msgpack, body).await, expected);
+        }
+
+        // Responses of handlers are not rejections.
+        let status = send(policy(), "/failing", msgpack, vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
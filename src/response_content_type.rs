@@ -0,0 +1,128 @@
+use crate::MsgPackConfig;
+use axum::{extract::Request, http::header, middleware::Next, response::Response};
+
+/// Marks responses sent with the default content type, `application/msgpack`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DefaultContentType;
+
+/// Middleware that sets the content type of MessagePack responses as the [`MsgPackConfig`] of
+/// the request configures.
+///
+/// Legacy clients may only understand `application/x-msgpack`. Set the content type with
+/// [`MsgPackConfig::response_content_type`], and add this middleware with
+/// [`from_fn`](axum::middleware::from_fn) inside the layer that adds the configuration, so it
+/// sees the configuration of the request:
+///
+/// ```no_run
+/// use axum::{middleware, routing::get, Extension, Router};
+/// use axum_msgpack::{apply_response_content_type, MsgPack, MsgPackConfig, XMsgPack};
+///
+/// async fn get_user() -> MsgPack<&'static str> {
+///     MsgPack("steve")
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/user", get(get_user))
+///     .layer(middleware::from_fn(apply_response_content_type))
+///     .layer(Extension(
+///         MsgPackConfig::new().response_content_type::<XMsgPack>(),
+///     ));
+/// ```
+///
+/// Only responses of this crate sent as `application/msgpack` are changed. Responses with
+/// another content type, e.g. from [`MsgPackX`](crate::MsgPackX) or the type echoed by
+/// [`AcceptedMsgPackMime::respond`](crate::AcceptedMsgPackMime::respond), keep it, and so do
+/// error responses and requests without a configured content type.
+pub async fn apply_response_content_type(req: Request, next: Next) -> Response {
+    let content_type = MsgPackConfig::of(&req)
+        .default_response_content_type()
+        .cloned();
+    let mut res = next.run(req).await;
+
+    if let Some(content_type) = content_type {
+        if res
+            .extensions_mut()
+            .remove::<DefaultContentType>()
+            .is_some()
+        {
+            res.headers_mut().insert(header::CONTENT_TYPE, content_type);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header, Request},
+        middleware,
+        response::Response,
+        routing::get,
+        Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    use super::apply_response_content_type;
+    use crate::{
+        AcceptedMsgPackMime, MsgPack, MsgPackAs, MsgPackConfig, MsgPackX, VndMsgPack, XMsgPack,
+    };
+
+    fn app(config: MsgPackConfig) -> Router {
+        Router::new()
+            .route("/default", get(|| async { MsgPack("steve") }))
+            .route("/x", get(|| async { MsgPackX::new("steve") }))
+            .route(
+                "/vnd",
+                get(|| async { MsgPackAs::<_, VndMsgPack>::new("steve") }),
+            )
+            .route(
+                "/echo",
+                get(|accepted: AcceptedMsgPackMime| async move { accepted.respond("steve") }),
+            )
+            .layer(middleware::from_fn(apply_response_content_type))
+            .layer(Extension(config))
+    }
+
+    async fn send(config: MsgPackConfig, uri: &str, accept: Option<&'static str>) -> Response {
+        let mut request = Request::get(uri);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        app(config)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn overrides_default_content_type() {
+        let config = MsgPackConfig::new().response_content_type::<XMsgPack>();
+        let res = send(config, "/default", None).await;
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/x-msgpack");
+
+        let res = send(MsgPackConfig::new(), "/default", None).await;
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+    }
+
+    #[tokio::test]
+    async fn keeps_explicit_content_types() {
+        let config = MsgPackConfig::new().response_content_type::<XMsgPack>();
+        for (uri, accept, expected) in [
+            ("/vnd", None, "application/vnd.msgpack"),
+            ("/echo", Some("application/msgpack"), "application/msgpack"),
+            (
+                "/echo",
+                Some("application/vnd.myapi+msgpack"),
+                "application/vnd.myapi+msgpack",
+            ),
+        ] {
+            let res = send(config.clone(), uri, accept).await;
+            assert_eq!(res.headers()[header::CONTENT_TYPE], expected, "{}", uri);
+        }
+
+        let config = MsgPackConfig::new().response_content_type::<VndMsgPack>();
+        let res = send(config, "/x", None).await;
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/x-msgpack");
+    }
+}
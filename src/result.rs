@@ -0,0 +1,144 @@
+use crate::MsgPack;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// Error types that know which status code they should be reported with.
+///
+/// Used by [`MsgPackResult`] to pick the status of error responses.
+pub trait IntoStatusCode {
+    /// The status code of the response carrying this error.
+    fn status_code(&self) -> StatusCode;
+}
+
+/// MessagePack Response for fallible handlers.
+///
+/// Both sides of the result are serialized as `MsgPack`. The `Ok` value is sent with `200 OK`,
+/// the `Err` value with the status returned by its [`IntoStatusCode`] implementation.
+///
+/// # Example
+///
+/// ```no_run
+/// use axum::{extract::Path, http::StatusCode, routing::get, Router};
+/// use axum_msgpack::{IntoStatusCode, MsgPackResult};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     username: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// enum ApiError {
+///     NotFound,
+/// }
+///
+/// impl IntoStatusCode for ApiError {
+///     fn status_code(&self) -> StatusCode {
+///         match self {
+///             ApiError::NotFound => StatusCode::NOT_FOUND,
+///         }
+///     }
+/// }
+///
+/// async fn get_user(Path(id): Path<u32>) -> MsgPackResult<User, ApiError> {
+///     if id == 0 {
+///         return Err(ApiError::NotFound).into();
+///     }
+///     Ok(User { username: "steve".into() }).into()
+/// }
+///
+/// let app: Router = Router::new().route("/users/:id", get(get_user));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MsgPackResult<T, E>(pub Result<T, E>);
+
+impl<T, E> From<Result<T, E>> for MsgPackResult<T, E> {
+    fn from(inner: Result<T, E>) -> Self {
+        Self(inner)
+    }
+}
+
+impl<T, E> IntoResponse for MsgPackResult<T, E>
+where
+    T: Serialize,
+    E: Serialize + IntoStatusCode,
+{
+    fn into_response(self) -> Response {
+        match self.0 {
+            Ok(value) => MsgPack(value).into_response(),
+            Err(err) => {
+                let status = err.status_code();
+                let mut res = MsgPack(err).into_response();
+                if res.status().is_success() {
+                    *res.status_mut() = status;
+                }
+                res
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use serde::{Deserialize, Serialize};
+
+    use super::{IntoStatusCode, MsgPackResult};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct ApiError {
+        code: u16,
+        message: String,
+    }
+
+    impl IntoStatusCode for ApiError {
+        fn status_code(&self) -> StatusCode {
+            StatusCode::from_u16(self.code).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn serializes_ok() {
+        let user = User { name: "steve".into() };
+        let res = MsgPackResult::<_, ApiError>(Ok(user)).into_response();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let decoded: User = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, User { name: "steve".into() });
+    }
+
+    #[tokio::test]
+    async fn serializes_err_with_status() {
+        let err = ApiError {
+            code: 404,
+            message: "no such user".into(),
+        };
+        let res = MsgPackResult::<User, _>::from(Err(err)).into_response();
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let decoded: ApiError = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(
+            decoded,
+            ApiError {
+                code: 404,
+                message: "no such user".into()
+            }
+        );
+    }
+}
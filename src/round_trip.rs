@@ -0,0 +1,161 @@
+//! Randomized round-trip tests of the encoding and decoding helpers.
+//!
+//! Values are generated from a fixed seed, so failures are reproducible. The failing case
+//! number is part of the assertion message.
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{decode, MsgPackCodec, MsgPackConfig};
+
+const CASES: u64 = 256;
+
+/// A xorshift64* generator, good enough to pick test values.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn bool(&mut self) -> bool {
+        self.next() & 1 == 1
+    }
+
+    fn string(&mut self) -> String {
+        const CHARS: &[char] = &['a', 'Z', '0', ' ', '-', 'ä', '€', '🦀'];
+        let len = self.below(12) as usize;
+        (0..len)
+            .map(|_| CHARS[self.below(CHARS.len() as u64) as usize])
+            .collect()
+    }
+
+    fn bytes(&mut self) -> ByteBuf {
+        // Long enough to need the `bin 16` format now and then.
+        let len = if self.below(8) == 0 { 300 } else { 8 };
+        ByteBuf((0..self.below(len)).map(|_| self.next() as u8).collect())
+    }
+}
+
+/// Bytes encoded as MessagePack `bin` rather than an array of integers.
+#[derive(Debug, Clone, PartialEq)]
+struct ByteBuf(Vec<u8>);
+
+impl Serialize for ByteBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl Visitor<'_> for BytesVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Polygon(Vec<(i32, i32)>),
+    Labeled {
+        label: Option<String>,
+        data: ByteBuf,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Record {
+    id: u64,
+    delta: i64,
+    name: String,
+    score: Option<f32>,
+    payload: ByteBuf,
+    shapes: BTreeMap<String, BTreeMap<u16, Shape>>,
+    children: Vec<Record>,
+}
+
+fn shape(rng: &mut Rng) -> Shape {
+    match rng.below(4) {
+        0 => Shape::Empty,
+        // Finite values only, `NaN` is not equal to itself.
+        1 => Shape::Circle(rng.next() as f64 / 7.0 - 1e18),
+        2 => Shape::Polygon(
+            (0..rng.below(4))
+                .map(|_| (rng.next() as i32, rng.next() as i32))
+                .collect(),
+        ),
+        _ => Shape::Labeled {
+            label: rng.bool().then(|| rng.string()),
+            data: rng.bytes(),
+        },
+    }
+}
+
+fn record(rng: &mut Rng, depth: u32) -> Record {
+    Record {
+        id: rng.next() >> rng.below(64),
+        delta: (rng.next() as i64) >> rng.below(64),
+        name: rng.string(),
+        score: rng.bool().then(|| rng.next() as f32 / 3.0),
+        payload: rng.bytes(),
+        shapes: (0..rng.below(3))
+            .map(|_| {
+                let inner = (0..rng.below(3))
+                    .map(|_| (rng.next() as u16, shape(rng)))
+                    .collect();
+                (rng.string(), inner)
+            })
+            .collect(),
+        children: if depth == 0 {
+            Vec::new()
+        } else {
+            (0..rng.below(3)).map(|_| record(rng, depth - 1)).collect()
+        },
+    }
+}
+
+#[test]
+fn round_trips_generated_records() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let config = MsgPackConfig::new().strict(true).deny_unknown_fields(true);
+
+    for case in 0..CASES {
+        let value = record(&mut rng, 2);
+
+        for named in [true, false] {
+            let codec = MsgPackCodec::new().named(named).config(config.clone());
+            let bytes = codec.encode(&value).unwrap();
+
+            let decoded: Record = codec.decode(&bytes).unwrap();
+            assert_eq!(decoded, value, "case {}, named: {}", case, named);
+            let decoded: Record = decode(&bytes, &config).unwrap();
+            assert_eq!(decoded, value, "case {}, named: {}", case, named);
+        }
+    }
+}
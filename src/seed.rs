@@ -0,0 +1,184 @@
+use crate::{
+    body_len::read_body,
+    check_trailing,
+    declared_len::check_declared_lengths,
+    duplicate::dedupe_keys,
+    deserialize_seed, message_pack_content_type,
+    rejection::{InvalidMsgPackBody, MsgPackRejection},
+    trace, MsgPackConfig,
+};
+use axum::extract::Request;
+use serde::de::DeserializeSeed;
+
+/// Decodes the MessagePack body of a request with a [`DeserializeSeed`].
+///
+/// Extractors can only decode types whose shape is known at compile time. A seed carries runtime
+/// state into decoding instead, such as a registry that picks the concrete type of a message
+/// from its tag. Content-type checking and rejections are the same as for
+/// [`MsgPack`](crate::MsgPack).
+///
+/// ```no_run
+/// use axum::{extract::{Request, State}, routing::post, Router};
+/// use axum_msgpack::{from_request_seed, rejection::MsgPackRejection};
+/// use serde::de::{DeserializeSeed, Deserializer, IgnoredAny};
+///
+/// #[derive(Clone)]
+/// struct Registry;
+///
+/// impl<'de> DeserializeSeed<'de> for &Registry {
+///     type Value = ();
+///
+///     fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+///         // look up the message kind in the registry and decode it
+///         # deserializer.deserialize_any(IgnoredAny)?;
+///         # Ok(())
+///     }
+/// }
+///
+/// async fn handle(State(registry): State<Registry>, req: Request) -> Result<(), MsgPackRejection> {
+///     let message = from_request_seed(req, &registry).await?;
+///     Ok(message)
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/messages", post(handle))
+///     .with_state(Registry);
+/// ```
+pub async fn from_request_seed<T, V>(req: Request, seed: T) -> Result<V, MsgPackRejection>
+where
+    T: for<'de> DeserializeSeed<'de, Value = V>,
+{
+    message_pack_content_type(&req)?;
+    let config = MsgPackConfig::of(&req);
+    let bytes = read_body(req, &()).await?;
+
+    trace::decoding::<V>(bytes.len());
+    trace::body::<V>(&bytes);
+    check_declared_lengths(&bytes[..], &config)?;
+    let bytes = &*dedupe_keys(&bytes, &config).map_err(InvalidMsgPackBody::from_err)?;
+    let mut rd = bytes;
+    let value = deserialize_seed(seed, &mut rmp_serde::Deserializer::new(&mut rd), &config)
+        .inspect_err(trace::decode_failed::<V, _>)
+        .map_err(|err| InvalidMsgPackBody::from_err(err).with_body(bytes))?;
+    check_trailing(rd.len(), &config)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fmt};
+
+    use axum::{body::Body, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{
+        de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor},
+        Deserialize, Serialize,
+    };
+
+    use super::from_request_seed;
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Ping {
+        seq: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Chat {
+        from: String,
+        text: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Ping(Ping),
+        Chat(Chat),
+    }
+
+    #[derive(Clone, Copy)]
+    enum Kind {
+        Ping,
+        Chat,
+    }
+
+    /// Decodes `[tag, payload]` pairs, looking the tag up at runtime.
+    struct Registry {
+        kinds: HashMap<u8, Kind>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for &Registry {
+        type Value = Message;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Message, D::Error> {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for &Registry {
+        type Value = Message;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a tagged message")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Message, A::Error> {
+            let tag: u8 = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let kind = self
+                .kinds
+                .get(&tag)
+                .ok_or_else(|| de::Error::custom(format!("unknown message tag {}", tag)))?;
+            let message = match kind {
+                Kind::Ping => seq.next_element()?.map(Message::Ping),
+                Kind::Chat => seq.next_element()?.map(Message::Chat),
+            };
+            message.ok_or_else(|| de::Error::invalid_length(1, &self))
+        }
+    }
+
+    fn registry() -> Registry {
+        Registry {
+            kinds: HashMap::from([(1, Kind::Ping), (2, Kind::Chat)]),
+        }
+    }
+
+    fn into_request<T: Serialize>(tag: u8, payload: &T) -> Request<Body> {
+        let body = rmp_serde::encode::to_vec_named(&(tag, payload)).unwrap();
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn decodes_type_chosen_by_seed() {
+        let registry = registry();
+
+        let ping = from_request_seed(into_request(1, &Ping { seq: 7 }), &registry)
+            .await
+            .unwrap();
+        assert_eq!(ping, Message::Ping(Ping { seq: 7 }));
+
+        let chat = Chat {
+            from: "steve".into(),
+            text: "hi".into(),
+        };
+        let decoded = from_request_seed(into_request(2, &chat), &registry)
+            .await
+            .unwrap();
+        assert_eq!(decoded, Message::Chat(chat));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_tag() {
+        let outcome = from_request_seed(into_request(3, &Ping { seq: 7 }), &registry()).await;
+
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}
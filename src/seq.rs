@@ -0,0 +1,212 @@
+use crate::{
+    body_len::read_body, decode_next, message_pack_content_type, rejection::MsgPackRejection,
+    trace, MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::de::DeserializeOwned;
+
+/// MessagePack Extractor for a body of values written one after another, without an enclosing
+/// array.
+///
+/// Decodes values until the body is exhausted, and rejects the request with
+/// [`InvalidMsgPackBody`](crate::rejection::InvalidMsgPackBody) if any of them fails to
+/// decode, including a value cut off at the end of the body. A value cut off within a string
+/// or binary value is rejected with
+/// [`DeclaredLengthTooLarge`](crate::rejection::DeclaredLengthTooLarge) instead, as it declares
+/// more bytes than the body has left. Use [`MsgPackPartial`](crate::MsgPackPartial) to keep the
+/// values before the failed one instead, and [`MsgPackBatch`](crate::MsgPackBatch) for a body
+/// that is a single
+/// array. An empty body decodes into no values.
+///
+/// Content-type checking is the same as for [`MsgPack`](crate::MsgPack), and every value is
+/// checked against the [`MsgPackLimits`](crate::MsgPackLimits) of the
+/// [`MsgPackConfig`] before it is decoded, and decoded with its other options, such as
+/// [`deny_unknown_fields`](MsgPackConfig::deny_unknown_fields) and
+/// [`duplicate_keys`](MsgPackConfig::duplicate_keys).
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackSeq;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     kind: String,
+/// }
+///
+/// async fn ingest(MsgPackSeq(events): MsgPackSeq<Event>) {
+///     // events is a `Vec<Event>`
+/// }
+///
+/// let app: Router = Router::new().route("/events", post(ingest));
+/// ```
+///
+/// [`DeclaredLengthTooLarge`]: crate::rejection::DeclaredLengthTooLarge
+#[derive(Debug, Clone, Default)]
+pub struct MsgPackSeq<T>(pub Vec<T>);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackSeq<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        message_pack_content_type(&req)?;
+        let config = MsgPackConfig::of(&req);
+        let bytes = read_body(req, state).await?;
+        decode_seq(&bytes, &config).map(MsgPackSeq)
+    }
+}
+
+fn decode_seq<T>(bytes: &[u8], config: &MsgPackConfig) -> Result<Vec<T>, MsgPackRejection>
+where
+    T: DeserializeOwned,
+{
+    trace::decoding::<T>(bytes.len());
+    let mut values = Vec::new();
+    let mut rd = bytes;
+    while !rd.is_empty() {
+        values.push(decode_next(&mut rd, bytes, config)?);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackSeq;
+    use crate::{rejection::MsgPackRejection, DuplicateKeys, MsgPackConfig, MsgPackLimits};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        kind: String,
+        id: u64,
+    }
+
+    fn event(id: u64) -> Event {
+        Event {
+            kind: "push".into(),
+            id,
+        }
+    }
+
+    async fn extract(
+        body: Vec<u8>,
+        config: MsgPackConfig,
+    ) -> Result<MsgPackSeq<Event>, MsgPackRejection> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request.extensions_mut().insert(config);
+        MsgPackSeq::from_request(request, &()).await
+    }
+
+    fn concatenated(count: u64) -> Vec<u8> {
+        (0..count)
+            .flat_map(|id| rmp_serde::encode::to_vec_named(&event(id)).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn decodes_concatenated_values() {
+        let MsgPackSeq(events) = extract(concatenated(2), MsgPackConfig::new())
+            .await
+            .unwrap();
+        assert_eq!(events, [event(0), event(1)]);
+
+        let MsgPackSeq(events) = extract(vec![], MsgPackConfig::new()).await.unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_trailing_partial_value() {
+        let mut body = concatenated(2);
+        // Cut off after the `kind` entry, so the map misses its `id` entry.
+        body.extend(&rmp_serde::encode::to_vec_named(&event(2)).unwrap()[..11]);
+        assert!(matches!(
+            extract(body, MsgPackConfig::new()).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+
+        let mut body = concatenated(2);
+        // Cut off within the `kind` key.
+        body.extend(&rmp_serde::encode::to_vec_named(&event(2)).unwrap()[..5]);
+        assert!(matches!(
+            extract(body, MsgPackConfig::new()).await,
+            Err(MsgPackRejection::DeclaredLengthTooLarge(_))
+        ));
+
+        // A single array is one value of the wrong type.
+        let body = rmp_serde::encode::to_vec_named(&[event(0), event(1)]).unwrap();
+        assert!(matches!(
+            extract(body, MsgPackConfig::new()).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_limits_of_every_value() {
+        let mut body = concatenated(1);
+        body.extend(rmp_serde::encode::to_vec_named(&("x".repeat(64), 1)).unwrap());
+        let config = MsgPackConfig::new().limits(MsgPackLimits::unlimited().max_str_len(16));
+        assert!(matches!(
+            extract(body, config).await,
+            Err(MsgPackRejection::ValueTooLong(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn applies_decoding_options_to_every_value() {
+        #[derive(Serialize)]
+        struct Loose {
+            kind: String,
+            id: f64,
+            extra: u8,
+        }
+
+        let config = MsgPackConfig::new()
+            .deny_unknown_fields(true)
+            .finite_floats(true)
+            .duplicate_keys(DuplicateKeys::Reject);
+
+        let mut body = concatenated(1);
+        body.extend(
+            rmp_serde::encode::to_vec_named(&Loose {
+                kind: "push".into(),
+                id: f64::NAN,
+                extra: 1,
+            })
+            .unwrap(),
+        );
+        assert!(matches!(
+            extract(body, config.clone()).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+
+        let mut body = concatenated(1);
+        // `{"kind": "push", "id": 1, "id": 2}`
+        body.extend([
+            0x83, 0xa4, b'k', b'i', b'n', b'd', 0xa4, b'p', b'u', b's', b'h',
+        ]);
+        body.extend([0xa2, b'i', b'd', 0x01, 0xa2, b'i', b'd', 0x02]);
+        assert!(matches!(
+            extract(body.clone(), config).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+
+        let config = MsgPackConfig::new().duplicate_keys(DuplicateKeys::KeepLast);
+        let MsgPackSeq(events) = extract(body, config).await.unwrap();
+        assert_eq!(events, [event(0), event(2)]);
+    }
+}
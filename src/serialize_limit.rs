@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::MsgPack;
+use axum::response::Response;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+/// Limits how many MessagePack responses are serialized at once.
+///
+/// Every serialization holds the whole encoding in memory, so many concurrent requests to an
+/// endpoint returning large values can exhaust it. Keep a `MsgPackSerializeLimit` in the state
+/// of such an endpoint, and respond with [`respond`](Self::respond): once all permits are taken,
+/// further serializations wait for one in the order they arrived.
+///
+/// ```no_run
+/// use axum::{extract::State, response::Response, routing::get, Router};
+/// use axum_msgpack::MsgPackSerializeLimit;
+///
+/// async fn export(State(limit): State<MsgPackSerializeLimit>) -> Response {
+///     let rows: Vec<(u64, String)> = load_rows().await;
+///     limit.respond(rows).await
+/// }
+///
+/// async fn load_rows() -> Vec<(u64, String)> {
+///     // ...
+///     # unimplemented!()
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/export", get(export))
+///     .with_state(MsgPackSerializeLimit::new(4));
+/// ```
+///
+/// Clones share their permits. A permit is released once the value is encoded, so encodings
+/// that are still being sent to the client do not count against the limit.
+#[derive(Debug, Clone)]
+pub struct MsgPackSerializeLimit {
+    permits: Arc<Semaphore>,
+}
+
+impl MsgPackSerializeLimit {
+    /// Allows up to `permits` serializations at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permits` is `0`, as no serialization could ever run, or exceeds
+    /// [`Semaphore::MAX_PERMITS`].
+    pub fn new(permits: usize) -> Self {
+        assert!(
+            permits > 0,
+            "a serialization limit needs at least one permit"
+        );
+        Self {
+            permits: Arc::new(Semaphore::new(permits)),
+        }
+    }
+
+    /// Returns the number of serializations that can start without waiting.
+    pub fn available_permits(&self) -> usize {
+        self.permits.available_permits()
+    }
+
+    /// Waits for a permit, then converts `value` into a response like
+    /// [`MsgPack::into_response_async`].
+    pub async fn respond<T>(&self, value: T) -> Response
+    where
+        T: Serialize + Send + 'static,
+    {
+        // The semaphore is never closed.
+        let _permit = self.permits.acquire().await.unwrap();
+        MsgPack(value).into_response_async().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use axum::http::StatusCode;
+    use serde::{Serialize, Serializer};
+
+    use super::MsgPackSerializeLimit;
+
+    const DELAY: Duration = Duration::from_millis(20);
+
+    /// Counts how many values are serialized at once.
+    #[derive(Default)]
+    struct Counter {
+        active: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    struct Slow(Arc<Counter>);
+
+    impl Serialize for Slow {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let active = self.0.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.0.max.fetch_max(active, Ordering::SeqCst);
+            std::thread::sleep(DELAY);
+            self.0.active.fetch_sub(1, Ordering::SeqCst);
+            serializer.serialize_u8(1)
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn queues_serializations_beyond_the_limit() {
+        let limit = MsgPackSerializeLimit::new(2);
+        let counter = Arc::new(Counter::default());
+
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let limit = limit.clone();
+                let value = Slow(counter.clone());
+                tokio::spawn(async move { limit.respond(value).await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().status(), StatusCode::OK);
+        }
+
+        // Six serializations, two at a time.
+        assert!(counter.max.load(Ordering::SeqCst) <= 2);
+        assert!(start.elapsed() >= DELAY * 3);
+        assert_eq!(limit.available_permits(), 2);
+    }
+}
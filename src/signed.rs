@@ -0,0 +1,272 @@
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    body_len::read_body,
+    check_content_type, decode,
+    rejection::{InvalidSignature, MissingMsgPackContentType, MsgPackRejection},
+    MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequest, Request},
+};
+use serde::de::DeserializeOwned;
+
+/// A key that verifies the signatures checked by [`SignedMsgPack`].
+///
+/// This crate does not depend on a cryptography library, so the signature scheme is up to the
+/// implementation, e.g. an HMAC-SHA256 from the `hmac` and `sha2` crates. Compare signatures in
+/// constant time, e.g. with `Mac::verify_slice` or [`constant_time_eq`], so the time taken does
+/// not reveal how much of a forged signature was correct.
+pub trait MsgPackSignature {
+    /// Name of the header carrying the signature, matched case-insensitively.
+    const HEADER: &'static str;
+
+    /// Returns `true` if `signature`, the value of the header, is a valid signature of `body`.
+    fn verify(&self, body: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Returns `true` if `a` and `b` are equal, in a time that only depends on their lengths.
+///
+/// Unlike `==`, the comparison does not stop at the first differing byte, so it suits
+/// [`MsgPackSignature::verify`] implementations comparing a computed signature with the one
+/// sent by the client.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b));
+    // Keeps the compiler from turning the fold back into an early return.
+    std::hint::black_box(diff) == 0
+}
+
+/// MessagePack Extractor for bodies signed with a key from the application state.
+///
+/// Reads the body, verifies the signature in the [`MsgPackSignature::HEADER`] header over the
+/// exact bytes the client sent with the key `K`, taken from the state with [`FromRef`], and only
+/// then decodes the body like [`MsgPack`](crate::MsgPack), with the same content type check,
+/// limits and [`MsgPackConfig`]. Requests without the header, or with a signature that does not
+/// match, are rejected with [`InvalidSignature`], a `401 Unauthorized` response, before
+/// anything is decoded.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::{MsgPackSignature, SignedMsgPack};
+/// use serde::Deserialize;
+///
+/// #[derive(Clone)]
+/// struct WebhookKey(Vec<u8>);
+///
+/// impl MsgPackSignature for WebhookKey {
+///     const HEADER: &'static str = "x-webhook-signature";
+///
+///     fn verify(&self, body: &[u8], signature: &[u8]) -> bool {
+///         // compare an HMAC of `body` with `signature` in constant time, e.g. with
+///         // `axum_msgpack::constant_time_eq`
+///         unimplemented!()
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     kind: String,
+/// }
+///
+/// async fn webhook(event: SignedMsgPack<Event, WebhookKey>) {
+///     // the event was sent by a holder of the key
+///     let event = event.into_inner();
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/webhook", post(webhook))
+///     .with_state(WebhookKey(b"secret".to_vec()));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignedMsgPack<T, K>(pub T, PhantomData<K>);
+
+impl<T, K> SignedMsgPack<T, K> {
+    /// Returns the decoded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, K> Deref for SignedMsgPack<T, K> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, K> DerefMut for SignedMsgPack<T, K> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[async_trait]
+impl<T, K, S> FromRequest<S> for SignedMsgPack<T, K>
+where
+    T: DeserializeOwned,
+    K: MsgPackSignature + FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of(&req);
+        let found = check_content_type(&req, &config)?;
+        let signature = req
+            .headers()
+            .get(K::HEADER)
+            .cloned()
+            .ok_or_else(|| InvalidSignature::new(K::HEADER))?;
+        let bytes = read_body(req, state).await?;
+
+        if !K::from_ref(state).verify(&bytes, signature.as_bytes()) {
+            return Err(InvalidSignature::new(K::HEADER).into());
+        }
+        let value = decode(&bytes, &config).map_err(|err| {
+            if found.is_match() {
+                err
+            } else {
+                MissingMsgPackContentType::new(found).into()
+            }
+        })?;
+        Ok(Self(value, PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::{FromRef, FromRequest},
+        http::{HeaderValue, StatusCode},
+    };
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::{constant_time_eq, MsgPackSignature, SignedMsgPack};
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        kind: String,
+        id: u64,
+    }
+
+    /// A toy scheme for the tests: the hex-encoded sum of the key and body bytes.
+    #[derive(Debug, Clone)]
+    struct Key(u8);
+
+    impl Key {
+        fn sign(&self, body: &[u8]) -> String {
+            let sum = body
+                .iter()
+                .fold(u32::from(self.0), |sum, &b| sum.wrapping_add(u32::from(b)));
+            format!("{:08x}", sum)
+        }
+    }
+
+    impl MsgPackSignature for Key {
+        const HEADER: &'static str = "x-signature";
+
+        fn verify(&self, body: &[u8], signature: &[u8]) -> bool {
+            constant_time_eq(self.sign(body).as_bytes(), signature)
+        }
+    }
+
+    #[derive(Clone)]
+    struct AppState {
+        key: Key,
+    }
+
+    impl FromRef<AppState> for Key {
+        fn from_ref(state: &AppState) -> Self {
+            state.key.clone()
+        }
+    }
+
+    async fn extract(
+        body: Vec<u8>,
+        signature: Option<String>,
+    ) -> Result<SignedMsgPack<Event, Key>, MsgPackRejection> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        if let Some(signature) = signature {
+            request
+                .headers_mut()
+                .insert("x-signature", signature.parse().unwrap());
+        }
+        let state = AppState { key: Key(7) };
+        SignedMsgPack::from_request(request, &state).await
+    }
+
+    fn event() -> Event {
+        Event {
+            kind: "push".into(),
+            id: 1,
+        }
+    }
+
+    #[test]
+    fn constant_time_eq_compares_bytes() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"signature", b"signature"));
+        assert!(!constant_time_eq(b"signature", b"signaturf"));
+        assert!(!constant_time_eq(b"signature", b"sign"));
+    }
+
+    #[tokio::test]
+    async fn decodes_correctly_signed_body() {
+        let body = rmp_serde::encode::to_vec_named(&event()).unwrap();
+        let signature = Key(7).sign(&body);
+        let signed = extract(body, Some(signature)).await.unwrap();
+        assert_eq!(signed.into_inner(), event());
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_or_wrong_signature() {
+        let body = rmp_serde::encode::to_vec_named(&event()).unwrap();
+        for signature in [None, Some(Key(8).sign(&body)), Some("garbage".into())] {
+            match extract(body.clone(), signature.clone()).await {
+                Err(MsgPackRejection::InvalidSignature(rejection)) => {
+                    assert_eq!(rejection.header(), "x-signature");
+                    assert_eq!(rejection.status(), StatusCode::UNAUTHORIZED);
+                }
+                other => unreachable!("Expected invalid signature, got: {:?}", other),
+            }
+        }
+
+        // The signature covers the exact bytes, so an equivalent encoding does not match.
+        let compact = rmp_serde::encode::to_vec(&event()).unwrap();
+        let signature = Key(7).sign(&body);
+        assert!(matches!(
+            extract(compact, Some(signature)).await,
+            Err(MsgPackRejection::InvalidSignature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verifies_before_decoding() {
+        let body = vec![0xc1];
+        assert!(matches!(
+            extract(body.clone(), None).await,
+            Err(MsgPackRejection::InvalidSignature(_))
+        ));
+
+        let signature = Key(7).sign(&body);
+        assert!(matches!(
+            extract(body, Some(signature)).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}
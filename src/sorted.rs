@@ -0,0 +1,287 @@
+use std::cmp::Ordering;
+
+use crate::{
+    empty::is_empty, failure::FailureFormat, msgpack_response, value::Value, ApplicationMsgPack,
+    ContentType, MsgPack,
+};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// MessagePack Response that encodes map entries sorted by key.
+///
+/// Serializes like [`MsgPack`](crate::MsgPack), but sorts the entries of every map by key, at
+/// any depth, so the same value is always encoded to the same bytes. The iteration order of a
+/// `HashMap` differs between instances, which breaks byte-for-byte comparisons of responses,
+/// e.g. for caching them or storing them by their hash. Created with [`MsgPack::sorted`]:
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// use axum::{routing::get, Router};
+/// use axum_msgpack::{MsgPack, MsgPackSorted};
+///
+/// async fn get_scores() -> MsgPackSorted<HashMap<String, u32>> {
+///     // encoded as `{"ann": 3, "bob": 5}`
+///     MsgPack(HashMap::from([("bob".into(), 5), ("ann".into(), 3)])).sorted()
+/// }
+///
+/// let app: Router = Router::new().route("/scores", get(get_scores));
+/// ```
+///
+/// Keys of different types are ordered `nil`, booleans, integers, floats, strings, binary
+/// values, arrays, maps and extension types. Keys of the same type are ordered by value, with
+/// strings and binary values ordered by their bytes as in a `BTreeMap<String, _>`, and arrays
+/// and maps ordered by their elements. Structs are encoded as maps of field names, so their
+/// fields are sorted too.
+///
+/// Sorting needs an untyped copy of the value: the value is encoded, decoded into an untyped
+/// value that is sorted, and encoded again.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackSorted<T>(pub T);
+
+impl<T> MsgPack<T> {
+    /// Encodes map entries sorted by key, for reproducible output.
+    pub fn sorted(self) -> MsgPackSorted<T> {
+        MsgPackSorted(self.0)
+    }
+}
+
+impl<T> MsgPackSorted<T>
+where
+    T: Serialize,
+{
+    /// Converts into a response like [`IntoResponse::into_response`], using `failure_format`
+    /// for the `500 Internal Server Error` response sent if the value cannot be serialized.
+    pub fn into_response_with(self, failure_format: FailureFormat) -> Response {
+        if is_empty(&self.0) {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+
+        msgpack_response::<T, _>(
+            encode_sorted(&self.0),
+            ApplicationMsgPack::CONTENT_TYPE,
+            failure_format,
+        )
+    }
+}
+
+impl<T> IntoResponse for MsgPackSorted<T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        self.into_response_with(FailureFormat::default())
+    }
+}
+
+fn encode_sorted<T>(value: &T) -> Result<Vec<u8>, rmp_serde::encode::Error>
+where
+    T: Serialize,
+{
+    let bytes = rmp_serde::encode::to_vec_named(value)?;
+    let mut value: Value = rmp_serde::from_slice(&bytes)
+        .map_err(|err| rmp_serde::encode::Error::Syntax(err.to_string()))?;
+    sort_keys(&mut value);
+    rmp_serde::encode::to_vec(&value)
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Map(entries) => {
+            for (key, value) in entries.iter_mut() {
+                sort_keys(key);
+                sort_keys(value);
+            }
+            entries.sort_by(|(a, _), (b, _)| compare(a, b));
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::U64(a), Value::U64(b)) => a.cmp(b),
+        (Value::I64(a), Value::I64(b)) => a.cmp(b),
+        // Negative integers are only decoded as `I64`.
+        (Value::U64(_), Value::I64(b)) if *b < 0 => Ordering::Greater,
+        (Value::I64(a), Value::U64(_)) if *a < 0 => Ordering::Less,
+        (Value::U64(a), Value::I64(b)) => a.cmp(&b.unsigned_abs()),
+        (Value::I64(a), Value::U64(b)) => a.unsigned_abs().cmp(b),
+        (Value::F32(_) | Value::F64(_), Value::F32(_) | Value::F64(_)) => {
+            float(a).total_cmp(&float(b))
+        }
+        (Value::Str(a), Value::Str(b)) => a.cmp(b),
+        (Value::Bin(a), Value::Bin(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Map(a), Value::Map(b)) => a
+            .iter()
+            .zip(b)
+            .map(|((a_key, a_value), (b_key, b_value))| {
+                compare(a_key, b_key).then_with(|| compare(a_value, b_value))
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Ext(a_tag, a_data), Value::Ext(b_tag, b_data)) => {
+            a_tag.cmp(b_tag).then_with(|| a_data.cmp(b_data))
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn float(value: &Value) -> f64 {
+    match value {
+        Value::F32(v) => f64::from(*v),
+        Value::F64(v) => *v,
+        _ => unreachable!("not a float"),
+    }
+}
+
+/// The position of the type of `value` in the order of types.
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Nil => 0,
+        Value::Bool(_) => 1,
+        Value::U64(_) | Value::I64(_) => 2,
+        Value::F32(_) | Value::F64(_) => 3,
+        Value::Str(_) => 4,
+        Value::Bin(_) => 5,
+        Value::Array(_) => 6,
+        Value::Map(_) => 7,
+        Value::Ext(..) => 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use axum::{body::to_bytes, http::header, response::IntoResponse};
+    use serde::Serialize;
+
+    use super::sort_keys;
+    use crate::{value::Value, MsgPack};
+
+    #[derive(Serialize)]
+    struct Scores {
+        total: u32,
+        by_user: HashMap<String, u32>,
+    }
+
+    /// `Scores` with its fields and map entries in sorted order.
+    #[derive(Serialize)]
+    struct SortedScores {
+        by_user: BTreeMap<String, u32>,
+        total: u32,
+    }
+
+    fn scores() -> Scores {
+        Scores {
+            total: 10,
+            by_user: (0..64).map(|i| (format!("user {}", i), i)).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn encodes_hash_maps_identically() {
+        // Every `HashMap` gets its own random hash keys, so the two maps of `scores` iterate in
+        // different orders.
+        let mut encodings = Vec::new();
+        for _ in 0..2 {
+            let res = MsgPack(scores()).sorted().into_response();
+            assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+            encodings.push(to_bytes(res.into_body(), usize::MAX).await.unwrap());
+        }
+        assert_eq!(encodings[0], encodings[1]);
+
+        let expected = rmp_serde::encode::to_vec_named(&SortedScores {
+            by_user: scores().by_user.into_iter().collect(),
+            total: 10,
+        })
+        .unwrap();
+        assert_eq!(encodings[0], expected);
+    }
+
+    #[test]
+    fn orders_keys_by_type_and_value() {
+        let keys = [
+            Value::Str("b".into()),
+            Value::Map(vec![]),
+            Value::Str("aa".into()),
+            Value::F64(0.5),
+            Value::U64(u64::MAX),
+            Value::I64(-5),
+            Value::U64(3),
+            Value::F32(-1.5),
+            Value::Bool(true),
+            Value::Bin(vec![1]),
+            Value::Nil,
+            Value::Array(vec![Value::U64(1)]),
+            Value::Array(vec![]),
+            Value::Ext(1, vec![]),
+        ];
+        let mut value = Value::Map(keys.into_iter().map(|key| (key, Value::Nil)).collect());
+        sort_keys(&mut value);
+
+        let Value::Map(entries) = value else {
+            unreachable!()
+        };
+        let sorted: Vec<_> = entries.into_iter().map(|(key, _)| key).collect();
+        assert_eq!(
+            sorted,
+            [
+                Value::Nil,
+                Value::Bool(true),
+                Value::I64(-5),
+                Value::U64(3),
+                Value::U64(u64::MAX),
+                Value::F32(-1.5),
+                Value::F64(0.5),
+                Value::Str("aa".into()),
+                Value::Str("b".into()),
+                Value::Bin(vec![1]),
+                Value::Array(vec![]),
+                Value::Array(vec![Value::U64(1)]),
+                Value::Map(vec![]),
+                Value::Ext(1, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn sorts_nested_maps() {
+        let mut value = Value::Array(vec![Value::Map(vec![
+            (
+                Value::Str("z".into()),
+                Value::Map(vec![
+                    (Value::U64(2), Value::Nil),
+                    (Value::U64(1), Value::Nil),
+                ]),
+            ),
+            (Value::Str("a".into()), Value::Nil),
+        ])]);
+        sort_keys(&mut value);
+        assert_eq!(
+            value,
+            Value::Array(vec![Value::Map(vec![
+                (Value::Str("a".into()), Value::Nil),
+                (
+                    Value::Str("z".into()),
+                    Value::Map(vec![
+                        (Value::U64(1), Value::Nil),
+                        (Value::U64(2), Value::Nil),
+                    ])
+                ),
+            ])])
+        );
+    }
+}
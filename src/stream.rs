@@ -0,0 +1,182 @@
+use std::io::{self, Read};
+
+use crate::{
+    check_content_encoding, message_pack_content_type,
+    rejection::{BodyReadError, InvalidMsgPackBody, MsgPackRejection},
+    trace,
+};
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    RequestExt,
+};
+use http_body_util::BodyExt;
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc;
+
+/// Number of body chunks buffered between the request body and the decoder.
+const CHANNEL_CAPACITY: usize = 8;
+
+/// MessagePack Extractor that decodes the request body while it is being received.
+///
+/// The body is never buffered as a whole. Instead, the decoder runs on tokio's blocking thread
+/// pool and reads the body through a bounded channel, so at most a few chunks of the body are
+/// held in memory at any time on top of the decoded value itself. This keeps memory usage
+/// bounded for large uploads, at the cost of a thread hand-off per request, so prefer
+/// [`MsgPack`](crate::MsgPack) for small bodies.
+///
+/// Content-type checking and rejections are the same as for [`MsgPack`](crate::MsgPack).
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::MsgPackStream;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Upload {
+///     name: String,
+///     data: Vec<u8>,
+/// }
+///
+/// async fn upload(MsgPackStream(upload): MsgPackStream<Upload>) {
+///     // upload is an `Upload`
+/// }
+///
+/// let app: Router = Router::new().route("/upload", post(upload));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackStream<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackStream<T>
+where
+    T: DeserializeOwned + Send + 'static,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+        message_pack_content_type(&req)?;
+        check_content_encoding(req.headers())?;
+
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let decode = tokio::task::spawn_blocking(move || {
+            rmp_serde::from_read::<_, T>(ChannelReader {
+                rx,
+                chunk: Bytes::new(),
+            })
+        });
+
+        let mut body = req.into_limited_body();
+        while let Some(frame) = body.frame().await {
+            let frame = frame.map_err(BodyReadError::from_err)?;
+            if let Ok(data) = frame.into_data() {
+                if tx.send(data).await.is_err() {
+                    // The decoder is done, either with a value or with an error.
+                    break;
+                }
+            }
+        }
+        drop(tx);
+
+        let value = match decode.await {
+            Ok(value) => value
+                .inspect_err(trace::decode_failed::<T, _>)
+                .map_err(InvalidMsgPackBody::from_err)?,
+            Err(err) => match err.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(err) => return Err(InvalidMsgPackBody::from_err(err).into()),
+            },
+        };
+        Ok(MsgPackStream(value))
+    }
+}
+
+/// A blocking [`Read`] over body chunks received from a channel.
+struct ChannelReader {
+    rx: mpsc::Receiver<Bytes>,
+    chunk: Bytes,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => self.chunk = chunk,
+                None => return Ok(0),
+            }
+        }
+
+        let len = buf.len().min(self.chunk.len());
+        buf[..len].copy_from_slice(&self.chunk.split_to(len));
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackStream;
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Input {
+        foo: String,
+        bar: Vec<u32>,
+    }
+
+    fn into_request(body: Vec<u8>, chunk_size: usize) -> Request<Body> {
+        let chunks: Vec<Result<Vec<u8>, std::io::Error>> = body
+            .chunks(chunk_size)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        let mut request = Request::new(Body::from_stream(futures_util::stream::iter(chunks)));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn deserializes_streamed_body() {
+        let input = Input {
+            foo: "a string long enough to be split".into(),
+            bar: (0..1000).collect(),
+        };
+        let body = rmp_serde::encode::to_vec_named(&input).unwrap();
+
+        let outcome = <MsgPackStream<Input> as FromRequest<_, _>>::from_request(
+            into_request(body, 7),
+            &|| {},
+        )
+        .await;
+
+        assert_eq!(outcome.unwrap().0, input);
+    }
+
+    #[tokio::test]
+    async fn rejects_truncated_body() {
+        let input = Input {
+            foo: "bar".into(),
+            bar: vec![1, 2, 3],
+        };
+        let mut body = rmp_serde::encode::to_vec_named(&input).unwrap();
+        body.truncate(body.len() - 2);
+
+        let outcome = <MsgPackStream<Input> as FromRequest<_, _>>::from_request(
+            into_request(body, 4),
+            &|| {},
+        )
+        .await;
+
+        assert!(matches!(
+            outcome,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}
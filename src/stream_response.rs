@@ -0,0 +1,162 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{trace, ApplicationMsgPack, ContentType};
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use futures_core::Stream;
+use serde::Serialize;
+
+/// MessagePack response that sends the items of a stream as they are produced.
+///
+/// Each item is encoded like a [`MsgPack`](crate::MsgPack) response, with structs as maps of
+/// field names, and written to the body as soon as the stream yields it, so the items are never
+/// buffered as a whole and a slow client slows down the stream. The response has
+/// `Content-Type: application/msgpack` and no `Content-Length`.
+///
+/// # Framing
+///
+/// The body is the encoded items written one after another, without separators or length
+/// prefixes. MessagePack values are self-delimiting, so clients read the body by decoding
+/// values until it ends, e.g. with [`decode_all`](crate::decode_all) once the body is complete,
+/// or by decoding from the body as it arrives. A body of no items is empty.
+///
+/// An item that is an error, or that cannot be encoded, ends the body with that error, which
+/// aborts the response, e.g. by closing the connection, since the status has already been sent.
+/// Clients see a truncated body, and should not treat the items they received as complete.
+///
+/// ```no_run
+/// use axum::{routing::get, Router};
+/// use axum_msgpack::MsgPackStreamResponse;
+/// use futures_util::stream::{self, Stream};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Row {
+///     id: u64,
+/// }
+///
+/// async fn export() -> MsgPackStreamResponse<impl Stream<Item = Result<Row, std::io::Error>>> {
+///     MsgPackStreamResponse(stream::iter((0..1000).map(|id| Ok(Row { id }))))
+/// }
+///
+/// let app: Router = Router::new().route("/export", get(export));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackStreamResponse<S>(pub S);
+
+impl<S, T, E> IntoResponse for MsgPackStreamResponse<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize,
+    E: Into<BoxError>,
+{
+    fn into_response(self) -> Response {
+        let mut res = Body::from_stream(EncodeStream {
+            stream: Box::pin(self.0),
+        })
+        .into_response();
+        res.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(ApplicationMsgPack::CONTENT_TYPE),
+        );
+        res
+    }
+}
+
+/// Encodes the items of a stream.
+struct EncodeStream<S> {
+    stream: Pin<Box<S>>,
+}
+
+impl<S, T, E> Stream for EncodeStream<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: Serialize,
+    E: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = match self.get_mut().stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(item))) => item,
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err.into()))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+        let bytes = rmp_serde::encode::to_vec_named(&item)
+            .inspect_err(trace::encode_failed::<T, _>)
+            .map(Bytes::from)
+            .map_err(BoxError::from);
+        Poll::Ready(Some(bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use futures_util::stream;
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackStreamResponse;
+    use crate::decode_all;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Row {
+        id: u64,
+        name: String,
+    }
+
+    fn row(id: u64) -> Row {
+        Row {
+            id,
+            name: format!("row {}", id),
+        }
+    }
+
+    #[tokio::test]
+    async fn concatenates_encoded_items() {
+        let rows = stream::iter((0..3).map(|id| Ok::<_, std::io::Error>(row(id))));
+        let res = MsgPackStreamResponse(rows).into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/msgpack");
+        assert!(res.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let (values, remaining, error) = decode_all::<Row>(&bytes);
+        assert_eq!(values, [row(0), row(1), row(2)]);
+        assert_eq!(remaining, 0);
+        assert!(error.is_none());
+        assert!(bytes.starts_with(&rmp_serde::encode::to_vec_named(&row(0)).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn sends_empty_body_for_empty_stream() {
+        let rows = stream::empty::<Result<Row, std::io::Error>>();
+        let res = MsgPackStreamResponse(rows).into_response();
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert!(bytes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ends_body_with_errors() {
+        let rows = stream::iter([Ok(row(0)), Err(std::io::Error::other("database gone"))]);
+        let res = MsgPackStreamResponse(rows).into_response();
+        let err = to_bytes(res.into_body(), usize::MAX).await.unwrap_err();
+        assert!(format!("{:?}", err).contains("database gone"), "{:?}", err);
+    }
+}
@@ -0,0 +1,196 @@
+//! Helpers for testing types sent as MessagePack.
+
+use std::fmt::Debug;
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, Method},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{decode, ApplicationMsgPack, ContentType, MsgPackCodec, MsgPackConfig};
+
+/// Encodes `value` the way a [`MsgPack`](crate::MsgPack) response does, with structs as maps
+/// of field names.
+///
+/// # Panics
+///
+/// Panics if `value` cannot be encoded.
+#[track_caller]
+pub fn encode_named<T>(value: &T) -> Vec<u8>
+where
+    T: Serialize + ?Sized,
+{
+    encode_with(MsgPackCodec::new(), value)
+}
+
+/// Encodes `value` the way a [`MsgPackRaw`](crate::MsgPackRaw) response does, with structs as
+/// positional arrays.
+///
+/// # Panics
+///
+/// Panics if `value` cannot be encoded.
+#[track_caller]
+pub fn encode_compact<T>(value: &T) -> Vec<u8>
+where
+    T: Serialize + ?Sized,
+{
+    encode_with(MsgPackCodec::new().named(false), value)
+}
+
+/// Builds a request to `uri` with `value` as its body, encoded with [`encode_named`], and
+/// `Content-Type: application/msgpack`, e.g. to send to a router with `oneshot`:
+///
+/// ```
+/// use axum::{http::Method, routing::post, Router};
+/// use axum_msgpack::{test_util::request, MsgPack};
+/// use tower::ServiceExt;
+///
+/// # async {
+/// let app: Router = Router::new().route(
+///     "/users",
+///     post(|MsgPack(name): MsgPack<String>| async move { name }),
+/// );
+/// let res = app
+///     .oneshot(request(Method::POST, "/users", "steve"))
+///     .await
+///     .unwrap();
+/// assert!(res.status().is_success());
+/// # };
+/// ```
+///
+/// # Panics
+///
+/// Panics if `value` cannot be encoded or `uri` is not a valid URI.
+#[track_caller]
+pub fn request<T>(method: Method, uri: &str, value: &T) -> Request
+where
+    T: Serialize + ?Sized,
+{
+    let mut req = Request::new(Body::from(encode_named(value)));
+    *req.method_mut() = method;
+    *req.uri_mut() = uri.parse().expect("invalid request URI");
+    req.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(ApplicationMsgPack::CONTENT_TYPE),
+    );
+    req
+}
+
+#[track_caller]
+fn encode_with<T>(codec: MsgPackCodec, value: &T) -> Vec<u8>
+where
+    T: Serialize + ?Sized,
+{
+    match codec.encode(value) {
+        Ok(bytes) => bytes,
+        Err(err) => panic!("failed to encode value: {}", err),
+    }
+}
+
+/// Asserts that `value` survives being sent as a [`MsgPack`](crate::MsgPack) response and
+/// extracted again.
+///
+/// Encodes `value` the way a `MsgPack` response does, decodes the bytes the way the `MsgPack`
+/// extractor does with the default [`MsgPackConfig`], and asserts that the result equals
+/// `value`. Catches types whose `Serialize` and `Deserialize` impls disagree, e.g. because of
+/// serde attributes only applied to one of them.
+///
+/// ```
+/// use axum_msgpack::test_util::assert_round_trip;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+///     #[serde(default)]
+///     tags: Vec<String>,
+/// }
+///
+/// assert_round_trip(User {
+///     name: "steve".into(),
+///     tags: vec!["admin".into()],
+/// });
+/// ```
+///
+/// # Panics
+///
+/// Panics if `value` cannot be encoded, if the bytes cannot be decoded, or if the decoded value
+/// differs from `value`.
+#[track_caller]
+pub fn assert_round_trip<T>(value: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let bytes = match MsgPackCodec::new().encode(&value) {
+        Ok(bytes) => bytes,
+        Err(err) => panic!("failed to encode {:?}: {}", value, err),
+    };
+    let decoded: T = match decode(&bytes, &MsgPackConfig::default()) {
+        Ok(decoded) => decoded,
+        Err(err) => panic!("failed to decode {:?}: {}", value, err),
+    };
+    assert_eq!(decoded, value, "value changed in a round trip");
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, Method},
+        response::IntoResponse,
+        routing::post,
+        Router,
+    };
+    use serde::{Deserialize, Serialize};
+    use tower::ServiceExt;
+
+    use super::{encode_compact, encode_named, request};
+    use crate::{MsgPack, MsgPackRaw};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    fn user() -> User {
+        User {
+            name: "steve".into(),
+            age: 42,
+        }
+    }
+
+    #[tokio::test]
+    async fn encodes_like_responses() {
+        let body = MsgPack(user()).into_response().into_body();
+        let bytes = to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(encode_named(&user()), bytes);
+
+        let body = MsgPackRaw(user()).into_response().into_body();
+        let bytes = to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(encode_compact(&user()), bytes);
+        assert_ne!(encode_named(&user()), encode_compact(&user()));
+    }
+
+    #[tokio::test]
+    async fn builds_extractable_requests() {
+        let req = request(Method::PUT, "/users/1?dry_run=true", &user());
+        assert_eq!(req.method(), Method::PUT);
+        assert_eq!(req.uri(), "/users/1?dry_run=true");
+        assert_eq!(req.headers()[header::CONTENT_TYPE], "application/msgpack");
+
+        let app: Router = Router::new().route(
+            "/users",
+            post(|MsgPack(user): MsgPack<User>| async move { MsgPack(user) }),
+        );
+        let res = app
+            .oneshot(request(Method::POST, "/users", &user()))
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(rmp_serde::from_slice::<User>(&bytes).unwrap(), user());
+    }
+}
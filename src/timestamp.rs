@@ -0,0 +1,261 @@
+//! Serde helpers encoding [`SystemTime`] as the MessagePack timestamp extension type.
+//!
+//! By default, serde encodes a `SystemTime` as a map of seconds and nanoseconds since the Unix
+//! epoch, which other languages do not understand. The timestamp extension type (`-1`) is the
+//! representation MessagePack libraries of other languages decode into their native time
+//! types. Annotate the field with `#[serde(with = "axum_msgpack::timestamp")]`:
+//!
+//! ```
+//! use std::time::SystemTime;
+//!
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     name: String,
+//!     #[serde(with = "axum_msgpack::timestamp")]
+//!     at: SystemTime,
+//! }
+//! ```
+//!
+//! `chrono::DateTime<Utc>` and `time::OffsetDateTime` both convert to and from `SystemTime`
+//! without losing nanoseconds, including times before the epoch, so fields of those types can
+//! be encoded as timestamps with a small `with` module converting through `SystemTime`.
+//!
+//! Times are encoded in the smallest of the three timestamp formats that can hold them, as the
+//! specification requires, and all three formats are decoded. The helpers only work with the
+//! MessagePack serializer and deserializer of `rmp-serde`, as other formats lack extension
+//! types.
+
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rmp_serde::MSGPACK_EXT_STRUCT_NAME;
+use serde::{
+    de::{self, Deserializer, Visitor},
+    ser::{self, Serializer},
+    Deserialize, Serialize,
+};
+
+/// Extension type of timestamps.
+const TIMESTAMP: i8 = -1;
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// Serializes `time` as a MessagePack timestamp.
+pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => (
+            i64::try_from(since.as_secs()).map_err(ser::Error::custom)?,
+            since.subsec_nanos(),
+        ),
+        Err(err) => {
+            let before = err.duration();
+            let secs = i64::try_from(before.as_secs()).map_err(ser::Error::custom)?;
+            match before.subsec_nanos() {
+                0 => (-secs, 0),
+                nanos => (-secs - 1, NANOS_PER_SEC - nanos),
+            }
+        }
+    };
+
+    let data = match (u32::try_from(secs), secs >> 34) {
+        (Ok(secs), _) if nanos == 0 => secs.to_be_bytes().to_vec(),
+        (_, 0) => ((u64::from(nanos) << 34) | secs as u64)
+            .to_be_bytes()
+            .to_vec(),
+        _ => {
+            let mut data = nanos.to_be_bytes().to_vec();
+            data.extend_from_slice(&secs.to_be_bytes());
+            data
+        }
+    };
+    serializer.serialize_newtype_struct(MSGPACK_EXT_STRUCT_NAME, &(TIMESTAMP, ExtData(data)))
+}
+
+/// Deserializes a MessagePack timestamp into a `SystemTime`.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_newtype_struct(MSGPACK_EXT_STRUCT_NAME, TimestampVisitor)
+}
+
+struct TimestampVisitor;
+
+impl<'de> Visitor<'de> for TimestampVisitor {
+    type Value = SystemTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a MessagePack timestamp")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (tag, ExtData(data)) = <(i8, ExtData)>::deserialize(deserializer)?;
+        if tag != TIMESTAMP {
+            return Err(de::Error::custom(format_args!(
+                "expected extension type {}, found {}",
+                TIMESTAMP, tag
+            )));
+        }
+
+        let (secs, nanos) = match data[..] {
+            [a, b, c, d] => (i64::from(u32::from_be_bytes([a, b, c, d])), 0),
+            [a, b, c, d, e, f, g, h] => {
+                let value = u64::from_be_bytes([a, b, c, d, e, f, g, h]);
+                ((value & ((1 << 34) - 1)) as i64, (value >> 34) as u32)
+            }
+            [a, b, c, d, ref secs @ ..] if data.len() == 12 => {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(secs);
+                (i64::from_be_bytes(buf), u32::from_be_bytes([a, b, c, d]))
+            }
+            _ => {
+                return Err(de::Error::invalid_length(
+                    data.len(),
+                    &"a timestamp of 4, 8 or 12 bytes",
+                ))
+            }
+        };
+        if nanos >= NANOS_PER_SEC {
+            return Err(de::Error::custom(format_args!(
+                "timestamp nanoseconds out of range: {}",
+                nanos
+            )));
+        }
+
+        let time = if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(secs as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))
+        };
+        time.and_then(|time| time.checked_add(Duration::from_nanos(u64::from(nanos))))
+            .ok_or_else(|| de::Error::custom("timestamp out of range"))
+    }
+}
+
+/// The data of an extension, encoded as MessagePack `bin`.
+struct ExtData(Vec<u8>);
+
+impl Serialize for ExtData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExtData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ExtDataVisitor;
+
+        impl Visitor<'_> for ExtDataVisitor {
+            type Value = ExtData;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "extension data")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ExtData(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_bytes(ExtDataVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{test_util::assert_round_trip, MsgPack, MsgPackConfig};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        #[serde(with = "crate::timestamp")]
+        at: SystemTime,
+    }
+
+    fn encode(at: SystemTime) -> Vec<u8> {
+        rmp_serde::encode::to_vec(&Event { at }).unwrap()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<SystemTime, rmp_serde::decode::Error> {
+        rmp_serde::from_slice::<Event>(bytes).map(|event| event.at)
+    }
+
+    #[test]
+    fn encodes_smallest_format() {
+        // Timestamp 32: fixext 4, seconds.
+        let at = UNIX_EPOCH + Duration::from_secs(1);
+        assert_eq!(encode(at), [0x91, 0xd6, 0xff, 0, 0, 0, 1]);
+
+        // Timestamp 64: fixext 8, nanoseconds in the upper 30 bits, seconds in the lower 34.
+        let at = UNIX_EPOCH + Duration::new(1, 1);
+        assert_eq!(encode(at), [0x91, 0xd7, 0xff, 0, 0, 0, 0x04, 0, 0, 0, 1]);
+        let at = UNIX_EPOCH + Duration::from_secs(1 << 32);
+        assert_eq!(encode(at), [0x91, 0xd7, 0xff, 0, 0, 0, 1, 0, 0, 0, 0]);
+
+        // Timestamp 96: ext 8 of 12 bytes, nanoseconds, then signed seconds.
+        let at = UNIX_EPOCH - Duration::new(1, 500_000_000);
+        let mut expected = vec![0x91, 0xc7, 12, 0xff];
+        expected.extend_from_slice(&500_000_000u32.to_be_bytes());
+        expected.extend_from_slice(&(-2i64).to_be_bytes());
+        assert_eq!(encode(at), expected);
+    }
+
+    #[test]
+    fn round_trips_times() {
+        for at in [
+            UNIX_EPOCH,
+            UNIX_EPOCH + Duration::new(1_700_000_000, 0),
+            UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789),
+            UNIX_EPOCH + Duration::new(1 << 40, 999_999_999),
+            UNIX_EPOCH - Duration::new(86_400, 0),
+            UNIX_EPOCH - Duration::new(0, 1),
+            SystemTime::now(),
+        ] {
+            assert_round_trip(Event { at });
+        }
+    }
+
+    #[test]
+    fn rejects_other_extension_types() {
+        let err = decode(&[0x91, 0xd6, 0x01, 0, 0, 0, 1]).unwrap_err();
+        assert_eq!(err.to_string(), "expected extension type -1, found 1");
+
+        let err = decode(&[0x91, 0xd5, 0xff, 0, 1]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 2, expected a timestamp of 4, 8 or 12 bytes"
+        );
+    }
+
+    #[tokio::test]
+    async fn extracts_timestamps() {
+        let at = UNIX_EPOCH + Duration::new(1_700_000_000, 5);
+        let body = rmp_serde::encode::to_vec_named(&Event { at }).unwrap();
+
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let config = MsgPackConfig::new().strict(true).deny_unknown_fields(true);
+        request.extensions_mut().insert(config);
+
+        let outcome = <MsgPack<Event> as FromRequest<_, _>>::from_request(request, &|| {}).await;
+        assert_eq!(outcome.unwrap().0, Event { at });
+    }
+}
@@ -0,0 +1,252 @@
+//! Instrumentation hooks, compiled to nothing unless the `tracing` feature is enabled.
+
+use std::fmt;
+
+/// Records that a body of `len` bytes is about to be decoded into `T`.
+///
+/// Emits a `DEBUG` event with the `type_name` and `body_len` fields.
+#[cfg(feature = "tracing")]
+pub(crate) fn decoding<T: ?Sized>(len: usize) {
+    tracing::debug!(
+        type_name = std::any::type_name::<T>(),
+        body_len = len,
+        "decoding MsgPack body"
+    );
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn decoding<T: ?Sized>(_len: usize) {}
+
+/// Records the body about to be decoded into `T`, transcoded to pretty-printed JSON.
+///
+/// Emits a `DEBUG` event with the `type_name` and `body` fields, only if such events are enabled.
+/// Bodies that JSON cannot represent, e.g. with binary data or non-string map keys, are
+/// recorded as the transcoding error instead.
+#[cfg(feature = "pretty-debug")]
+pub(crate) fn body<T: ?Sized>(bytes: &[u8]) {
+    if !tracing::enabled!(tracing::Level::DEBUG) {
+        return;
+    }
+
+    let body = rmp_serde::from_slice::<serde_json::Value>(bytes)
+        .map_err(|err| err.to_string())
+        .and_then(|value| serde_json::to_string_pretty(&value).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| format!("<not representable as JSON: {}>", err));
+    tracing::debug!(
+        type_name = std::any::type_name::<T>(),
+        body = %body,
+        "MsgPack body as JSON"
+    );
+}
+
+#[cfg(not(feature = "pretty-debug"))]
+#[inline(always)]
+pub(crate) fn body<T: ?Sized>(_bytes: &[u8]) {}
+
+/// Records that decoding a body into `T` failed.
+///
+/// Emits a `WARN` event with the `type_name` and `error` fields.
+#[cfg(feature = "tracing")]
+pub(crate) fn decode_failed<T: ?Sized, E: fmt::Display>(error: &E) {
+    tracing::warn!(
+        type_name = std::any::type_name::<T>(),
+        error = %error,
+        "failed to decode MsgPack body"
+    );
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn decode_failed<T: ?Sized, E: fmt::Display>(_error: &E) {}
+
+/// Records that encoding a `T` for a response failed.
+///
+/// Emits a `WARN` event with the `type_name` and `error` fields.
+#[cfg(feature = "tracing")]
+pub(crate) fn encode_failed<T: ?Sized, E: fmt::Display>(error: &E) {
+    tracing::warn!(
+        type_name = std::any::type_name::<T>(),
+        error = %error,
+        "failed to encode MsgPack response"
+    );
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn encode_failed<T: ?Sized, E: fmt::Display>(_error: &E) {}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::{
+        fmt,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        body::Body,
+        extract::FromRequest,
+        http::{HeaderValue, StatusCode},
+        response::IntoResponse,
+    };
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    use crate::MsgPack;
+
+    /// A subscriber that records the fields of every event as `name=value` strings.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<Vec<String>>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut Vec<String>);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.push(format!("{}={:?}", field.name(), value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Vec::new();
+            event.record(&mut FieldVisitor(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Input {
+        foo: String,
+    }
+
+    fn into_request(body: Vec<u8>) -> Request<Body> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        request
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn records_type_name_and_body_len() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let body = rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap();
+        let len = body.len();
+        <MsgPack<Input> as FromRequest<_, _>>::from_request(into_request(body), &|| {})
+            .await
+            .unwrap();
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            events[0],
+            [
+                "message=decoding MsgPack body".to_owned(),
+                format!("type_name={}", std::any::type_name::<Input>()),
+                format!("body_len={}", len),
+            ]
+        );
+        assert_eq!(
+            events.len(),
+            if cfg!(feature = "pretty-debug") { 2 } else { 1 }
+        );
+    }
+
+    #[cfg(feature = "pretty-debug")]
+    #[tokio::test(flavor = "current_thread")]
+    async fn records_body_as_json() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let body = rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap();
+        let outcome =
+            <MsgPack<Input> as FromRequest<_, _>>::from_request(into_request(body.clone()), &|| {})
+                .await;
+        assert_eq!(outcome.unwrap().0.foo, "bar");
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            events[1],
+            [
+                "message=MsgPack body as JSON".to_owned(),
+                format!("type_name={}", std::any::type_name::<Input>()),
+                "body={\n  \"foo\": \"bar\"\n}".to_owned(),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn records_decode_failure() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let body = rmp_serde::encode::to_vec_named(&1u32).unwrap();
+        <MsgPack<Input> as FromRequest<_, _>>::from_request(into_request(body), &|| {})
+            .await
+            .unwrap_err();
+
+        let events = recorder.events.lock().unwrap();
+        let failure = events.last().unwrap();
+        assert_eq!(failure[0], "message=failed to decode MsgPack body");
+        assert_eq!(
+            failure[1],
+            format!("type_name={}", std::any::type_name::<Input>())
+        );
+        assert!(failure[2].starts_with("error=invalid type: integer `1`"));
+    }
+
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("no way"))
+        }
+    }
+
+    #[test]
+    fn records_encode_failure() {
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let res = MsgPack(Unserializable).into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            *events,
+            [vec![
+                "message=failed to encode MsgPack response".to_owned(),
+                format!("type_name={}", std::any::type_name::<Unserializable>()),
+                "error=no way".to_owned(),
+            ]]
+        );
+    }
+}
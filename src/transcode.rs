@@ -0,0 +1,306 @@
+use crate::{
+    accept::accept_range_qualities, is_message_pack_type, vary::append_vary_accept,
+    AcceptedMsgPackMime,
+};
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Middleware that sends JSON responses as MessagePack to clients that prefer MessagePack.
+///
+/// Adds MessagePack support to a service whose handlers respond with axum's
+/// [`Json`](axum::Json), without touching the handlers. Add it with
+/// [`from_fn`](axum::middleware::from_fn):
+///
+/// ```no_run
+/// use axum::{middleware, routing::get, Json, Router};
+/// use axum_msgpack::msgpack_transcode;
+///
+/// async fn get_user() -> Json<String> {
+///     Json("steve".to_owned())
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/user", get(get_user))
+///     .layer(middleware::from_fn(msgpack_transcode));
+/// ```
+///
+/// A JSON response, i.e. one of type `application/json` or `application/*+json`, is transcoded
+/// if the `Accept` header of the request lists a MessagePack type with at least the quality of
+/// JSON. Each format gets the quality of the most specific media range matching it, so
+/// `application/json;q=0.9, application/msgpack` picks MessagePack, and
+/// `application/msgpack;q=0.5, */*` keeps JSON. A malformed quality, e.g. `q=high`, also picks
+/// MessagePack. The response then gets the accepted MessagePack type, see
+/// [`AcceptedMsgPackMime`], as `Content-Type`. Other responses, and JSON bodies that cannot be
+/// parsed, are passed through untouched. JSON responses get a `Vary: Accept` header.
+pub async fn msgpack_transcode(req: Request, next: Next) -> Response {
+    let wants_message_pack = prefers_message_pack(req.headers());
+    let accepted = AcceptedMsgPackMime::from_headers(req.headers());
+    let mut res = next.run(req).await;
+
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| is_json_type(&mime));
+    if !is_json {
+        return res;
+    }
+
+    append_vary_accept(res.headers_mut());
+    if !wants_message_pack {
+        return res;
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let message = format!("Failed to read JSON response: {}", err);
+            return (StatusCode::INTERNAL_SERVER_ERROR, message).into_response();
+        }
+    };
+    let message_pack = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|value| rmp_serde::encode::to_vec_named(&value).ok());
+    let Some(message_pack) = message_pack else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    if let Ok(content_type) = HeaderValue::from_str(accepted.0.as_ref()) {
+        parts.headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    Response::from_parts(parts, Body::from(message_pack))
+}
+
+/// Returns `true` if the `Accept` header lists a MessagePack type, and prefers MessagePack to
+/// JSON.
+fn prefers_message_pack(headers: &HeaderMap) -> bool {
+    let qualities = FormatQualities::from_headers(headers);
+    qualities.message_pack.is_named()
+        && qualities.message_pack.quality > 0.0
+        && qualities.prefers_message_pack()
+}
+
+/// The qualities the `Accept` header gives JSON and MessagePack, to choose between them.
+#[derive(Debug, Default)]
+pub(crate) struct FormatQualities {
+    json: RangeQuality,
+    message_pack: RangeQuality,
+    malformed: bool,
+}
+
+impl FormatQualities {
+    /// Reads the qualities from `headers`.
+    ///
+    /// Each format gets the quality of the most specific range matching it: a JSON or
+    /// MessagePack type, then `application/*`, then `*/*`. Several ranges of the same
+    /// specificity give it their highest quality, and a range with a malformed quality counts
+    /// with the default quality of `1`.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let mut qualities = Self::default();
+        for (mime, quality) in accept_range_qualities(headers) {
+            let specificity = if mime.type_() == mime::STAR {
+                Specificity::AnyType
+            } else if mime.type_() == mime::APPLICATION && mime.subtype() == mime::STAR {
+                Specificity::AnySubtype
+            } else if is_message_pack_type(&mime) || is_json_type(&mime) {
+                Specificity::Named
+            } else {
+                continue;
+            };
+            qualities.malformed |= quality.is_none();
+            let quality = quality.unwrap_or(1.0);
+
+            if specificity != Specificity::Named || is_json_type(&mime) {
+                qualities.json.update(specificity, quality);
+            }
+            if specificity != Specificity::Named || is_message_pack_type(&mime) {
+                qualities.message_pack.update(specificity, quality);
+            }
+        }
+        qualities
+    }
+
+    /// Returns `true` unless JSON has a higher quality than MessagePack. Ties, and headers with
+    /// a malformed quality for either format, prefer MessagePack.
+    pub(crate) fn prefers_message_pack(&self) -> bool {
+        self.malformed || self.message_pack.quality >= self.json.quality
+    }
+}
+
+/// The quality of a format, and how specific the range it was taken from is.
+#[derive(Debug, Default, Clone, Copy)]
+struct RangeQuality {
+    specificity: Specificity,
+    quality: f32,
+}
+
+impl RangeQuality {
+    fn update(&mut self, specificity: Specificity, quality: f32) {
+        if specificity > self.specificity {
+            *self = Self {
+                specificity,
+                quality,
+            };
+        } else if specificity == self.specificity {
+            self.quality = self.quality.max(quality);
+        }
+    }
+
+    fn is_named(&self) -> bool {
+        self.specificity == Specificity::Named
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Specificity {
+    #[default]
+    Unlisted,
+    AnyType,
+    AnySubtype,
+    Named,
+}
+
+pub(crate) fn is_json_type(mime: &mime::Mime) -> bool {
+    mime.type_() == mime::APPLICATION
+        && (mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Request, StatusCode},
+        middleware,
+        routing::get,
+        Json, Router,
+    };
+    use serde::{Deserialize, Serialize};
+    use tower::ServiceExt;
+
+    use super::msgpack_transcode;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    fn user() -> User {
+        User {
+            name: "steve".into(),
+            age: 42,
+        }
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/user", get(|| async { Json(user()) }))
+            .route(
+                "/private",
+                get(|| async { ([(header::VARY, "Cookie")], Json(user())) }),
+            )
+            .route("/text", get(|| async { "steve" }))
+            .layer(middleware::from_fn(msgpack_transcode))
+    }
+
+    async fn send(uri: &str, accept: Option<&'static str>) -> (StatusCode, String, Vec<u8>) {
+        let mut request = Request::get(uri);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        let res = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let content_type = res
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|content_type| content_type.to_str().unwrap().to_owned())
+            .unwrap_or_default();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn sends_message_pack_to_message_pack_clients() {
+        for (accept, expected) in [
+            ("application/msgpack", "application/msgpack"),
+            (
+                "application/vnd.myapi+msgpack",
+                "application/vnd.myapi+msgpack",
+            ),
+            (
+                "application/json;q=0.5, application/x-msgpack",
+                "application/x-msgpack",
+            ),
+            (
+                "application/json;q=0.9, application/msgpack;q=1.0",
+                "application/msgpack",
+            ),
+            (
+                "application/json, application/msgpack",
+                "application/msgpack",
+            ),
+            (
+                "application/json;q=high, application/msgpack;q=0.5",
+                "application/msgpack",
+            ),
+            (
+                "application/json;q=0.8, application/msgpack;q=-1",
+                "application/msgpack",
+            ),
+        ] {
+            let (status, content_type, body) = send("/user", Some(accept)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type, expected, "{}", accept);
+            assert_eq!(rmp_serde::from_slice::<User>(&body).unwrap(), user());
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_json_for_other_clients() {
+        for accept in [
+            None,
+            Some("application/json"),
+            Some("*/*"),
+            Some("application/json, application/msgpack;q=0.5"),
+            Some("application/msgpack;q=0.5, */*"),
+            Some("application/json;q=0.9, application/msgpack;q=0"),
+        ] {
+            let (status, content_type, body) = send("/user", accept).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type, "application/json", "{:?}", accept);
+            assert_eq!(body, br#"{"name":"steve","age":42}"#);
+        }
+    }
+
+    #[tokio::test]
+    async fn merges_vary_accept_with_existing_vary() {
+        for accept in ["application/msgpack", "application/json"] {
+            let request = Request::get("/private")
+                .header(header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap();
+            let res = app().oneshot(request).await.unwrap();
+            let vary: Vec<_> = res.headers().get_all(header::VARY).iter().collect();
+            assert_eq!(vary, ["Cookie, accept"], "{}", accept);
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_other_responses_through() {
+        let (status, content_type, body) = send("/text", Some("application/msgpack")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "text/plain; charset=utf-8");
+        assert_eq!(body, b"steve");
+    }
+}
@@ -0,0 +1,132 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+};
+
+/// Response wrapper that adds `Vary: Accept` to the wrapped response.
+///
+/// Responses whose format depends on the `Accept` header of the request must say so, or caches
+/// may serve a response in one format to a client that asked for another. The
+/// `json_fallback` middleware of the `json` feature adds the header on its own. Wrap any other
+/// negotiated response, e.g. a [`MsgPack`](crate::MsgPack) response a handler picked after
+/// looking at the `Accept` header itself:
+///
+/// ```no_run
+/// use axum::{
+///     http::{header, HeaderMap},
+///     response::{IntoResponse, Response},
+///     routing::get,
+///     Router,
+/// };
+/// use axum_msgpack::{MsgPack, VaryAccept};
+///
+/// async fn get_user(headers: HeaderMap) -> VaryAccept<Response> {
+///     let user = "steve".to_owned();
+///     if headers.get(header::ACCEPT).is_some_and(|accept| accept == "text/plain") {
+///         VaryAccept(user.into_response())
+///     } else {
+///         VaryAccept(MsgPack(user).into_response())
+///     }
+/// }
+///
+/// let app: Router = Router::new().route("/user", get(get_user));
+/// ```
+///
+/// `Accept` is only added if the response does not list `Accept` or `*` already, and is merged
+/// with existing values into a single header, so `Vary: Cookie` becomes `Vary: Cookie, accept`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VaryAccept<R>(pub R);
+
+impl<R> IntoResponse for VaryAccept<R>
+where
+    R: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut res = self.0.into_response();
+        append_vary_accept(res.headers_mut());
+        res
+    }
+}
+
+/// Adds `Accept` to the `Vary` header of `headers`, unless they already vary on `Accept` or
+/// everything.
+///
+/// Existing `Vary` values are kept and merged with `Accept` into a single header, e.g.
+/// `Vary: Cookie` becomes `Vary: Cookie, accept`.
+pub(crate) fn append_vary_accept(headers: &mut HeaderMap) {
+    let Ok(names) = headers
+        .get_all(header::VARY)
+        .iter()
+        .map(|vary| vary.to_str())
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        // Leave values that are not visible ASCII alone.
+        headers.append(header::VARY, HeaderValue::from_static("accept"));
+        return;
+    };
+    let mut names: Vec<&str> = names
+        .into_iter()
+        .flat_map(|vary| vary.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names
+        .iter()
+        .any(|name| *name == "*" || name.eq_ignore_ascii_case("accept"))
+    {
+        return;
+    }
+
+    names.push("accept");
+    let vary = HeaderValue::from_str(&names.join(", ")).expect("vary is a valid header");
+    headers.insert(header::VARY, vary);
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        http::{header, HeaderValue},
+        response::IntoResponse,
+    };
+
+    use super::VaryAccept;
+    use crate::MsgPack;
+
+    #[test]
+    fn adds_vary_accept_once() {
+        let res = VaryAccept(MsgPack("steve")).into_response();
+        let vary: Vec<_> = res.headers().get_all(header::VARY).iter().collect();
+        assert_eq!(vary, ["accept"]);
+
+        let mut res = MsgPack("steve").into_response();
+        res.headers_mut().insert(
+            header::VARY,
+            HeaderValue::from_static("Accept-Encoding, Accept"),
+        );
+        let res = VaryAccept(res).into_response();
+        let vary: Vec<_> = res.headers().get_all(header::VARY).iter().collect();
+        assert_eq!(vary, ["Accept-Encoding, Accept"]);
+
+        let res = MsgPack("steve").into_response();
+        assert!(res.headers().get(header::VARY).is_none());
+    }
+
+    #[test]
+    fn merges_with_existing_vary() {
+        let mut res = MsgPack("steve").into_response();
+        res.headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("Cookie"));
+        res.headers_mut()
+            .append(header::VARY, HeaderValue::from_static("Origin"));
+        let res = VaryAccept(res).into_response();
+        let vary: Vec<_> = res.headers().get_all(header::VARY).iter().collect();
+        assert_eq!(vary, ["Cookie, Origin, accept"]);
+
+        let mut res = MsgPack("steve").into_response();
+        res.headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("*"));
+        let res = VaryAccept(res).into_response();
+        let vary: Vec<_> = res.headers().get_all(header::VARY).iter().collect();
+        assert_eq!(vary, ["*"]);
+    }
+}
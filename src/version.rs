@@ -0,0 +1,256 @@
+use std::ops::{Deref, DerefMut, RangeInclusive};
+
+use crate::{
+    check_content_type,
+    rejection::{MsgPackRejection, SchemaVersionMismatch},
+    MsgPack, MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    http::HeaderName,
+};
+use serde::de::DeserializeOwned;
+
+/// Name of the header checked by [`VersionedMsgPack`].
+pub const SCHEMA_VERSION_HEADER: HeaderName = HeaderName::from_static(SCHEMA_VERSION);
+
+const SCHEMA_VERSION: &str = "x-msgpack-schema-version";
+
+/// Name of the header read by [`Versioned`].
+pub const X_SCHEMA_VERSION: HeaderName = HeaderName::from_static(X_SCHEMA_VERSION_STR);
+
+const X_SCHEMA_VERSION_STR: &str = "x-schema-version";
+
+/// MessagePack Extractor that requires a specific schema version.
+///
+/// Works like [`MsgPack`], but after the content-type check it also requires the
+/// `X-MsgPack-Schema-Version` header to equal `V`. Requests with a missing or different version
+/// are rejected with [`SchemaVersionMismatch`] and a `400 Bad Request` response before the body
+/// is decoded.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::VersionedMsgPack;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct CreateUser {
+///     email: String,
+/// }
+///
+/// async fn create_user(VersionedMsgPack(payload): VersionedMsgPack<CreateUser, 2>) {
+///     // payload is a `CreateUser` sent with `X-MsgPack-Schema-Version: 2`
+/// }
+///
+/// let app: Router = Router::new().route("/users", post(create_user));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VersionedMsgPack<T, const V: u32>(pub T);
+
+#[async_trait]
+impl<T, S, const V: u32> FromRequest<S> for VersionedMsgPack<T, V>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        check_content_type(&req, &MsgPackConfig::of(&req))?;
+        check_version(&req, SCHEMA_VERSION, V..=V)?;
+
+        let MsgPack(value) = MsgPack::from_request(req, state).await?;
+        Ok(VersionedMsgPack(value))
+    }
+}
+
+impl<T, const V: u32> Deref for VersionedMsgPack<T, V> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const V: u32> DerefMut for VersionedMsgPack<T, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// MessagePack Extractor that exposes the requested schema version.
+///
+/// Reads the version from the `X-Schema-Version` header and requires it to lie within
+/// `MIN..=MAX`. Requests with a missing, malformed or unsupported version are rejected with
+/// [`SchemaVersionMismatch`], whose message names the supported range. Otherwise the body is
+/// decoded like [`MsgPack`] and handed to the handler together with the version, so the handler
+/// can interpret the payload according to the schema the client used.
+///
+/// ```no_run
+/// use axum::{routing::post, Router};
+/// use axum_msgpack::Versioned;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct CreateUser {
+///     email: String,
+///     #[serde(default)]
+///     display_name: Option<String>,
+/// }
+///
+/// async fn create_user(user: Versioned<CreateUser, 1, 2>) {
+///     if user.version == 1 {
+///         // version 1 clients never send a display name
+///     }
+/// }
+///
+/// let app: Router = Router::new().route("/users", post(create_user));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Versioned<T, const MIN: u32, const MAX: u32> {
+    /// The schema version sent by the client.
+    pub version: u32,
+    /// The decoded body.
+    pub value: T,
+}
+
+#[async_trait]
+impl<T, S, const MIN: u32, const MAX: u32> FromRequest<S> for Versioned<T, MIN, MAX>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        check_content_type(&req, &MsgPackConfig::of(&req))?;
+        let version = check_version(&req, X_SCHEMA_VERSION_STR, MIN..=MAX)?;
+
+        let MsgPack(value) = MsgPack::from_request(req, state).await?;
+        Ok(Versioned { version, value })
+    }
+}
+
+fn check_version(
+    req: &Request,
+    header: &'static str,
+    expected: RangeInclusive<u32>,
+) -> Result<u32, SchemaVersionMismatch> {
+    let value = req.headers().get(header);
+    value
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u32>().ok())
+        .filter(|version| expected.contains(version))
+        .ok_or_else(|| SchemaVersionMismatch::new(header, expected, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::FromRequest,
+        http::{HeaderName, HeaderValue},
+    };
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::{Versioned, VersionedMsgPack, SCHEMA_VERSION_HEADER, X_SCHEMA_VERSION};
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Input {
+        foo: String,
+    }
+
+    fn into_request(version: Option<&'static str>) -> Request<Body> {
+        let body = rmp_serde::encode::to_vec_named(&Input { foo: "bar".into() }).unwrap();
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        if let Some(version) = version {
+            request
+                .headers_mut()
+                .insert(SCHEMA_VERSION_HEADER, HeaderValue::from_static(version));
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn accepts_matching_version() {
+        let outcome = <VersionedMsgPack<Input, 2> as FromRequest<_, _>>::from_request(
+            into_request(Some("2")),
+            &|| {},
+        )
+        .await;
+
+        assert_eq!(outcome.unwrap().0, Input { foo: "bar".into() });
+    }
+
+    #[tokio::test]
+    async fn rejects_mismatched_or_missing_version() {
+        for (version, found) in [
+            (Some("1"), Some("1")),
+            (Some("two"), Some("two")),
+            (None, None),
+        ] {
+            let outcome = <VersionedMsgPack<Input, 2> as FromRequest<_, _>>::from_request(
+                into_request(version),
+                &|| {},
+            )
+            .await;
+
+            match outcome {
+                Err(MsgPackRejection::SchemaVersionMismatch(rejection)) => {
+                    assert_eq!(rejection.found(), found);
+                    assert!(rejection
+                        .to_string()
+                        .starts_with("Expected request with `x-msgpack-schema-version: 2`"));
+                }
+                other => unreachable!("Expected schema version rejection, got: {:?}", other),
+            }
+        }
+    }
+
+    fn into_request_with(header: HeaderName, version: &'static str) -> Request<Body> {
+        let mut request = into_request(None);
+        request
+            .headers_mut()
+            .insert(header, HeaderValue::from_static(version));
+        request
+    }
+
+    #[tokio::test]
+    async fn exposes_supported_version() {
+        let outcome = <Versioned<Input, 1, 3> as FromRequest<_, _>>::from_request(
+            into_request_with(X_SCHEMA_VERSION, "2"),
+            &|| {},
+        )
+        .await;
+
+        let outcome = outcome.unwrap();
+        assert_eq!(outcome.version, 2);
+        assert_eq!(outcome.value, Input { foo: "bar".into() });
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_version() {
+        let outcome = <Versioned<Input, 1, 3> as FromRequest<_, _>>::from_request(
+            into_request_with(X_SCHEMA_VERSION, "4"),
+            &|| {},
+        )
+        .await;
+
+        match outcome {
+            Err(MsgPackRejection::SchemaVersionMismatch(rejection)) => {
+                assert_eq!(
+                    rejection.to_string(),
+                    "Expected request with `x-schema-version` between 1 and 3, found `4`"
+                );
+            }
+            other => unreachable!("Expected schema version rejection, got: {:?}", other),
+        }
+    }
+}
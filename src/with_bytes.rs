@@ -0,0 +1,117 @@
+use crate::{
+    body_len::read_body,
+    check_content_type, decode,
+    rejection::{MissingMsgPackContentType, MsgPackRejection},
+    MsgPackConfig,
+};
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+};
+use serde::de::DeserializeOwned;
+
+/// MessagePack extractor that keeps the raw request body next to the decoded value.
+///
+/// Decodes the body like [`MsgPack`](crate::MsgPack), with the same content type check,
+/// limits and [`MsgPackConfig`], and hands out the exact bytes the client sent along with the
+/// value, e.g. to verify a signature computed over the body. Encoding the value again would not
+/// reproduce those bytes in general, as MessagePack allows several encodings of the same value.
+/// The body is only read once, and the [`Bytes`] share its buffer.
+///
+/// ```no_run
+/// use axum::{body::Bytes, routing::post, Router};
+/// use axum_msgpack::MsgPackWithBytes;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     kind: String,
+/// }
+///
+/// async fn webhook(MsgPackWithBytes(event, bytes): MsgPackWithBytes<Event>) {
+///     // verify the signature of `bytes`, then handle `event`
+/// }
+///
+/// let app: Router = Router::new().route("/webhook", post(webhook));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MsgPackWithBytes<T>(pub T, pub Bytes);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for MsgPackWithBytes<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = MsgPackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = MsgPackConfig::of(&req);
+        let found = check_content_type(&req, &config)?;
+        let bytes = read_body(req, state).await?;
+        let value = decode(&bytes, &config).map_err(|err| {
+            if found.is_match() {
+                err
+            } else {
+                MissingMsgPackContentType::new(found).into()
+            }
+        })?;
+        Ok(MsgPackWithBytes(value, bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, http::HeaderValue};
+    use hyper::{header, Request};
+    use serde::{Deserialize, Serialize};
+
+    use super::MsgPackWithBytes;
+    use crate::rejection::MsgPackRejection;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        kind: String,
+        id: u64,
+    }
+
+    async fn extract(body: Vec<u8>) -> Result<MsgPackWithBytes<Event>, MsgPackRejection> {
+        let mut request = Request::new(Body::from(body));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        MsgPackWithBytes::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn keeps_exact_body_bytes() {
+        // `id` encoded as uint 32 although it fits a positive fixint, which encoding the value
+        // again would not reproduce.
+        let mut body = vec![0x82];
+        rmp::encode::write_str(&mut body, "kind").unwrap();
+        rmp::encode::write_str(&mut body, "created").unwrap();
+        rmp::encode::write_str(&mut body, "id").unwrap();
+        rmp::encode::write_u32(&mut body, 7).unwrap();
+
+        let MsgPackWithBytes(event, bytes) = extract(body.clone()).await.unwrap();
+        assert_eq!(
+            event,
+            Event {
+                kind: "created".into(),
+                id: 7
+            }
+        );
+        assert_eq!(bytes, body);
+        assert_ne!(rmp_serde::encode::to_vec_named(&event).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_bodies() {
+        assert!(matches!(
+            extract(vec![0xc1]).await,
+            Err(MsgPackRejection::InvalidMsgPackBody(_))
+        ));
+    }
+}